@@ -18,6 +18,13 @@ Future<String> initializeNew({required String serverUri, String? walletDir}) =>
     RustLib.instance.api
         .crateApiInitializeNew(serverUri: serverUri, walletDir: walletDir);
 
+/// Initialize a new wallet whose seed is protected by a BIP39 passphrase, and return the seed phrase. The
+/// passphrase is needed, along with the seed phrase, to restore the wallet
+Future<String> initializeNewWithPassphrase(
+        {required String serverUri, String? passphrase, String? walletDir}) =>
+    RustLib.instance.api.crateApiInitializeNewWithPassphrase(
+        serverUri: serverUri, passphrase: passphrase, walletDir: walletDir);
+
 /// Initialize a new wallet and return both seed phrase and birthday
 Future<String> initializeNewWithInfo(
         {required String serverUri, String? walletDir}) =>
@@ -58,6 +65,44 @@ Future<String> initializeFromPhrase(
         overwrite: overwrite,
         walletDir: walletDir);
 
+/// Initialize from seed phrase and the BIP39 passphrase the wallet was created with
+Future<String> initializeFromPhraseWithPassphrase(
+        {required String serverUri,
+        required String seedPhrase,
+        String? passphrase,
+        required BigInt birthday,
+        required bool overwrite,
+        String? walletDir}) =>
+    RustLib.instance.api.crateApiInitializeFromPhraseWithPassphrase(
+        serverUri: serverUri,
+        seedPhrase: seedPhrase,
+        passphrase: passphrase,
+        birthday: birthday,
+        overwrite: overwrite,
+        walletDir: walletDir);
+
+/// Initialize a watch-only wallet from Sapling extended full viewing keys (btczxviews...) and t-addresses. It
+/// has no seed, so it shows the funds and history of those keys but can't spend them
+Future<String> initializeWatchOnly(
+        {required String serverUri,
+        required List<String> viewingKeys,
+        required List<String> taddrs,
+        required BigInt birthday,
+        required bool overwrite,
+        String? walletDir}) =>
+    RustLib.instance.api.crateApiInitializeWatchOnly(
+        serverUri: serverUri,
+        viewingKeys: viewingKeys,
+        taddrs: taddrs,
+        birthday: birthday,
+        overwrite: overwrite,
+        walletDir: walletDir);
+
+/// Enter the BIP39 passphrase of a wallet that isn't encrypted. It isn't saved with such a wallet, and is
+/// needed before the wallet can derive new addresses
+Future<String> setPassphrase({required String passphrase}) =>
+    RustLib.instance.api.crateApiSetPassphrase(passphrase: passphrase);
+
 /// Execute a command (main wallet interface)
 Future<String> execute({required String command, required String args}) =>
     RustLib.instance.api.crateApiExecute(command: command, args: args);
@@ -77,13 +122,174 @@ String getBalance() => RustLib.instance.api.crateApiGetBalance();
 /// Get transaction list
 String getTransactions() => RustLib.instance.api.crateApiGetTransactions();
 
-/// Send transaction
+/// Send transaction. `utxo_strategy` picks the UTXOs to spend if transparent funds are needed: "avoid_mixing"
+/// (the default), "single_address", "minimal", "oldest_first" or "all"
 Future<String> sendTransaction(
         {required String address,
         required PlatformInt64 amount,
-        String? memo}) =>
+        String? memo,
+        String? utxoStrategy}) =>
+    RustLib.instance.api.crateApiSendTransaction(
+        address: address,
+        amount: amount,
+        memo: memo,
+        utxoStrategy: utxoStrategy);
+
+/// Send everything that can be spent to a single address. The fee is deducted from the amount and no change
+/// is created, so this empties the wallet
+Future<String> sendMaxTransaction({required String address, String? memo}) =>
     RustLib.instance.api
-        .crateApiSendTransaction(address: address, amount: amount, memo: memo);
+        .crateApiSendMaxTransaction(address: address, memo: memo);
+
+/// Shield the funds of each of the given t-addresses (or of every t-address, if none are given) in a
+/// separate transaction, so they aren't linked together. Without a destination, the change policy picks
+/// the z-address
+Future<String> shieldFromAddresses(
+        {required List<String> fromAddresses, String? toAddress}) =>
+    RustLib.instance.api.crateApiShieldFromAddresses(
+        fromAddresses: fromAddresses, toAddress: toAddress);
+
+/// Shield transparent funds automatically after each sync, once UTXOs with at least `min_confirmations`
+/// confirmations add up to `threshold` zats. Without an address, the change policy picks the z-address
+String enableAutoShield(
+        {required BigInt threshold,
+        required int minConfirmations,
+        String? address}) =>
+    RustLib.instance.api.crateApiEnableAutoShield(
+        threshold: threshold,
+        minConfirmations: minConfirmations,
+        address: address);
+
+/// Stop shielding transparent funds automatically
+String disableAutoShield() => RustLib.instance.api.crateApiDisableAutoShield();
+
+/// Get the auto-shield policy
+String getAutoShield() => RustLib.instance.api.crateApiGetAutoShield();
+
+/// Show how the smallest notes would be merged into fewer notes, and the fees that would cost
+Future<String> previewConsolidation({int? maxNotes, BigInt? dustThreshold}) =>
+    RustLib.instance.api.crateApiPreviewConsolidation(
+        maxNotes: maxNotes, dustThreshold: dustThreshold);
+
+/// Merge the smallest notes into fewer notes sent to this wallet
+Future<String> consolidateNotes({int? maxNotes, BigInt? dustThreshold}) =>
+    RustLib.instance.api.crateApiConsolidateNotes(
+        maxNotes: maxNotes, dustThreshold: dustThreshold);
+
+/// Check a memo as it is being typed: whether it can be sent (to `address`, if given), whether it is text or
+/// "0x" hex, and how many of its 512 bytes are used and left
+String validateMemo({required String memo, String? address}) =>
+    RustLib.instance.api.crateApiValidateMemo(memo: memo, address: address);
+
+/// Check a seed phrase, partial or complete, as it is entered: every word that isn't in the BIP39 word list, with
+/// its position (from 1), completions and closest matches, and whether the phrase has the right length and
+/// checksum. This doesn't need a wallet
+String validateSeedPhrase({required String seedPhrase}) =>
+    RustLib.instance.api.crateApiValidateSeedPhrase(seedPhrase: seedPhrase);
+
+/// Suggest seed phrase words for `word`: the words it is the start of, and the closest words if it is mistyped
+String seedWordSuggestions({required String word}) =>
+    RustLib.instance.api.crateApiSeedWordSuggestions(word: word);
+
+/// Parse a `bitcoinz:` payment URI (ZIP-321). Returns the payments, with amounts in zats. This doesn't need a
+/// wallet, so it can be used right after scanning a QR code
+String parsePaymentUri({required String uri}) =>
+    RustLib.instance.api.crateApiParsePaymentUri(uri: uri);
+
+/// Create a `bitcoinz:` payment URI from a JSON array of {"address", "amount" (in zats), "memo", "label",
+/// "message"}, where everything but the address is optional
+String createPaymentUri({required String paymentsJson}) =>
+    RustLib.instance.api.crateApiCreatePaymentUri(paymentsJson: paymentsJson);
+
+/// Pay all the recipients in a CSV or JSON file of (address, amount, memo). Nothing is sent if any row is
+/// invalid. Returns the result of every row
+Future<String> sendBatch({required String filePath}) =>
+    RustLib.instance.api.crateApiSendBatch(filePath: filePath);
+
+/// Schedule a payment to `recipients_json` (a JSON array of {"address", "amount", "memo"}), first due at
+/// `due_height` or at the unix time `due_time`, and then every `interval` blocks or seconds. Without an
+/// interval it is only paid once. Returns the id of the scheduled payment
+String schedulePayment(
+        {required String recipientsJson,
+        int? dueHeight,
+        BigInt? dueTime,
+        BigInt? interval}) =>
+    RustLib.instance.api.crateApiSchedulePayment(
+        recipientsJson: recipientsJson,
+        dueHeight: dueHeight,
+        dueTime: dueTime,
+        interval: interval);
+
+/// List all the scheduled payments, with the txids that paid each of them
+String listScheduledPayments() =>
+    RustLib.instance.api.crateApiListScheduledPayments();
+
+/// Remove a scheduled payment
+String cancelScheduledPayment({required BigInt id}) =>
+    RustLib.instance.api.crateApiCancelScheduledPayment(id: id);
+
+/// List the scheduled payments that are due now
+String getDuePayments() => RustLib.instance.api.crateApiGetDuePayments();
+
+/// Pay every scheduled payment that is due. The wallet has to be unlocked
+Future<String> payDuePayments() =>
+    RustLib.instance.api.crateApiPayDuePayments();
+
+/// Create an unsigned transaction (works in watch-only wallets). Returns the proposal hex, to be signed by
+/// the wallet that has the spending keys
+Future<String> createUnsignedTransaction(
+        {required String address, required BigInt amount, String? memo}) =>
+    RustLib.instance.api.crateApiCreateUnsignedTransaction(
+        address: address, amount: amount, memo: memo);
+
+/// Sign an unsigned transaction created by `create_unsigned_transaction`, without broadcasting it
+Future<String> signUnsignedTransaction({required String proposalHex}) =>
+    RustLib.instance.api
+        .crateApiSignUnsignedTransaction(proposalHex: proposalHex);
+
+/// Broadcast a transaction that was signed by another (offline) wallet, or any other raw transaction hex
+Future<String> broadcastSignedTransaction({required String txHex}) =>
+    RustLib.instance.api.crateApiBroadcastSignedTransaction(txHex: txHex);
+
+/// Load the Sapling prover ahead of the next send, so it can start proving right away
+Future<String> preloadProver() => RustLib.instance.api.crateApiPreloadProver();
+
+/// Free the memory used by the Sapling prover, for example when the app goes to the background. It is loaded
+/// again by the next send
+String releaseProver() => RustLib.instance.api.crateApiReleaseProver();
+
+/// Decode a raw transaction hex: its inputs and outputs, expiry, value balance and which parts are this wallet's
+String decodeRawTransaction({required String txHex}) =>
+    RustLib.instance.api.crateApiDecodeRawTransaction(txHex: txHex);
+
+/// Set the extra servers every transaction is also broadcast to. An empty list only uses the wallet's server
+String setBroadcastServers({required List<String> servers}) =>
+    RustLib.instance.api.crateApiSetBroadcastServers(servers: servers);
+
+/// Get the wallet's server and the extra broadcast servers
+String getBroadcastServers() =>
+    RustLib.instance.api.crateApiGetBroadcastServers();
+
+/// Wait (up to `timeout_secs`, 30 by default) until every broadcast server has the transaction, and report
+/// what each of them said about it
+Future<String> checkPropagation({required String txid, BigInt? timeoutSecs}) =>
+    RustLib.instance.api
+        .crateApiCheckPropagation(txid: txid, timeoutSecs: timeoutSecs);
+
+/// Set where the change of sends goes: "same_as_input", "fresh" or one of the wallet's z-addresses
+String setChangePolicy({required String policy}) =>
+    RustLib.instance.api.crateApiSetChangePolicy(policy: policy);
+
+/// Get the current change policy
+String getChangePolicy() => RustLib.instance.api.crateApiGetChangePolicy();
+
+/// Set how much sends may reveal on chain: "full_privacy", "allow_revealed_amounts",
+/// "allow_revealed_recipients" or "allow_linking_transparent"
+String setPrivacyPolicy({required String policy}) =>
+    RustLib.instance.api.crateApiSetPrivacyPolicy(policy: policy);
+
+/// Get the current privacy policy
+String getPrivacyPolicy() => RustLib.instance.api.crateApiGetPrivacyPolicy();
 
 /// Get addresses
 String getAddresses() => RustLib.instance.api.crateApiGetAddresses();
@@ -92,6 +298,73 @@ String getAddresses() => RustLib.instance.api.crateApiGetAddresses();
 String newAddress({required String addressType}) =>
     RustLib.instance.api.crateApiNewAddress(addressType: addressType);
 
+/// Generate a diversified address of an existing z-address's key (or of the first key). Unlike a new z-address,
+/// it doesn't add a key to scan for, so it's free to hand out one per payer
+String newDiversifiedAddress({String? zaddr}) =>
+    RustLib.instance.api.crateApiNewDiversifiedAddress(zaddr: zaddr);
+
+/// List the accounts of the wallet, with their addresses
+String getAccounts() => RustLib.instance.api.crateApiGetAccounts();
+
+/// Create a new account, with its own z- and t-address
+String createAccount({required String name}) =>
+    RustLib.instance.api.crateApiCreateAccount(name: name);
+
+String renameAccount({required int account, required String name}) =>
+    RustLib.instance.api.crateApiRenameAccount(account: account, name: name);
+
+/// Create a new address ("z" or "t") in an account
+String newAccountAddress({required int account, required String addressType}) =>
+    RustLib.instance.api
+        .crateApiNewAccountAddress(account: account, addressType: addressType);
+
+String getAccountBalance({required int account}) =>
+    RustLib.instance.api.crateApiGetAccountBalance(account: account);
+
+/// The transactions received at an account's addresses, and the sends that spent its funds
+String getAccountTransactions({required int account}) =>
+    RustLib.instance.api.crateApiGetAccountTransactions(account: account);
+
+/// Send only the funds of an account. The change stays in the account
+Future<String> sendFromAccount(
+        {required int account,
+        required String address,
+        required BigInt amount,
+        String? memo}) =>
+    RustLib.instance.api.crateApiSendFromAccount(
+        account: account, address: address, amount: amount, memo: memo);
+
+/// List the notes and UTXOs that can be spent right now, for coin control. Each has an "input_id" that can be
+/// passed to `send_from_inputs`, `freeze_inputs` and `unfreeze_inputs`
+String getSpendableInputs() =>
+    RustLib.instance.api.crateApiGetSpendableInputs();
+
+/// Send, spending exactly the notes and UTXOs with these input ids (from `get_spendable_inputs`)
+Future<String> sendFromInputs(
+        {required List<String> inputIds,
+        required String address,
+        required BigInt amount,
+        String? memo}) =>
+    RustLib.instance.api.crateApiSendFromInputs(
+        inputIds: inputIds, address: address, amount: amount, memo: memo);
+
+/// Send, only spending the funds that were received at `from_address`, to keep funds apart for accounting
+Future<String> sendFromAddress(
+        {required String fromAddress,
+        required String address,
+        required BigInt amount,
+        String? memo}) =>
+    RustLib.instance.api.crateApiSendFromAddress(
+        fromAddress: fromAddress, address: address, amount: amount, memo: memo);
+
+/// Freeze notes / UTXOs (by input id) so they are never spent
+String freezeInputs({required List<String> inputIds}) =>
+    RustLib.instance.api.crateApiFreezeInputs(inputIds: inputIds);
+
+/// Unfreeze previously frozen notes / UTXOs
+String unfreezeInputs({required List<String> inputIds}) =>
+    RustLib.instance.api.crateApiUnfreezeInputs(inputIds: inputIds);
+
 /// Get wallet height
 int getHeight() => RustLib.instance.api.crateApiGetHeight();
 
@@ -106,6 +379,10 @@ Future<String> getServerInfo({required String serverUri}) =>
 /// Get send progress (synchronous version for polling)
 String getSendProgress() => RustLib.instance.api.crateApiGetSendProgress();
 
+/// Get the sent transactions that aren't mined yet, with their expiry height and how many times they were
+/// rebroadcast, and the ones that expired without being mined
+String getPendingSends() => RustLib.instance.api.crateApiGetPendingSends();
+
 /// Initialize progress stream
 Future<String> initProgressStream() =>
     RustLib.instance.api.crateApiInitProgressStream();
@@ -117,13 +394,3 @@ Future<String> getNextProgressUpdate() =>
 /// Send progress update (called from transaction building)
 Future<String> sendProgressUpdate({required String progressData}) =>
     RustLib.instance.api.crateApiSendProgressUpdate(progressData: progressData);
-
-/// Export C-compatible function for zecwalletlitelib to call
-/// This allows the fallback progress system to emit stream events
-Future<void> emitProgressUpdate({required int progress, required int total}) =>
-    RustLib.instance.api
-        .crateApiEmitProgressUpdate(progress: progress, total: total);
-
-/// Initialization function to set up global progress sender for C bridge
-Future<void> initProgressBridge() =>
-    RustLib.instance.api.crateApiInitProgressBridge();
@@ -68,7 +68,7 @@ class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
   String get codegenVersion => '2.11.1';
 
   @override
-  int get rustContentHash => -1645151663;
+  int get rustContentHash => 339822779;
 
   static const kDefaultExternalLibraryLoaderConfig =
       ExternalLibraryLoaderConfig(
@@ -79,34 +79,77 @@ class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
 }
 
 abstract class RustLibApi extends BaseApi {
+  Future<String> crateApiBroadcastSignedTransaction({required String txHex});
+
+  String crateApiCancelScheduledPayment({required BigInt id});
+
+  Future<String> crateApiCheckPropagation(
+      {required String txid, BigInt? timeoutSecs});
+
+  Future<String> crateApiConsolidateNotes(
+      {int? maxNotes, BigInt? dustThreshold});
+
+  String crateApiCreateAccount({required String name});
+
+  String crateApiCreatePaymentUri({required String paymentsJson});
+
+  Future<String> crateApiCreateUnsignedTransaction(
+      {required String address, required BigInt amount, String? memo});
+
+  String crateApiDecodeRawTransaction({required String txHex});
+
   Future<String> crateApiDeinitialize();
 
-  Future<void> crateApiEmitProgressUpdate(
-      {required int progress, required int total});
+  String crateApiDisableAutoShield();
+
+  String crateApiEnableAutoShield(
+      {required BigInt threshold,
+      required int minConfirmations,
+      String? address});
 
   Future<String> crateApiExecute(
       {required String command, required String args});
 
+  String crateApiFreezeInputs({required List<String> inputIds});
+
+  String crateApiGetAccountBalance({required int account});
+
+  String crateApiGetAccountTransactions({required int account});
+
+  String crateApiGetAccounts();
+
   String crateApiGetAddresses();
 
+  String crateApiGetAutoShield();
+
   String crateApiGetBalance();
 
+  String crateApiGetBroadcastServers();
+
+  String crateApiGetChangePolicy();
+
+  String crateApiGetDuePayments();
+
   int crateApiGetHeight();
 
   String crateApiGetInfo();
 
   Future<String> crateApiGetNextProgressUpdate();
 
+  String crateApiGetPendingSends();
+
+  String crateApiGetPrivacyPolicy();
+
   String crateApiGetSendProgress();
 
   Future<String> crateApiGetServerInfo({required String serverUri});
 
+  String crateApiGetSpendableInputs();
+
   String crateApiGetSyncStatus();
 
   String crateApiGetTransactions();
 
-  Future<void> crateApiInitProgressBridge();
-
   Future<String> crateApiInitProgressStream();
 
   Future<String> crateApiInitializeExisting(
@@ -125,21 +168,113 @@ abstract class RustLibApi extends BaseApi {
   Future<String> crateApiInitializeFromPhraseSimple(
       {required String serverUri, required String seedPhrase});
 
+  Future<String> crateApiInitializeFromPhraseWithPassphrase(
+      {required String serverUri,
+      required String seedPhrase,
+      String? passphrase,
+      required BigInt birthday,
+      required bool overwrite,
+      String? walletDir});
+
   Future<String> crateApiInitializeNew(
       {required String serverUri, String? walletDir});
 
   Future<String> crateApiInitializeNewWithInfo(
       {required String serverUri, String? walletDir});
 
+  Future<String> crateApiInitializeNewWithPassphrase(
+      {required String serverUri, String? passphrase, String? walletDir});
+
+  Future<String> crateApiInitializeWatchOnly(
+      {required String serverUri,
+      required List<String> viewingKeys,
+      required List<String> taddrs,
+      required BigInt birthday,
+      required bool overwrite,
+      String? walletDir});
+
+  String crateApiListScheduledPayments();
+
+  String crateApiNewAccountAddress(
+      {required int account, required String addressType});
+
   String crateApiNewAddress({required String addressType});
 
+  String crateApiNewDiversifiedAddress({String? zaddr});
+
+  String crateApiParsePaymentUri({required String uri});
+
+  Future<String> crateApiPayDuePayments();
+
+  Future<String> crateApiPreloadProver();
+
+  Future<String> crateApiPreviewConsolidation(
+      {int? maxNotes, BigInt? dustThreshold});
+
+  String crateApiReleaseProver();
+
+  String crateApiRenameAccount({required int account, required String name});
+
+  String crateApiSchedulePayment(
+      {required String recipientsJson,
+      int? dueHeight,
+      BigInt? dueTime,
+      BigInt? interval});
+
+  String crateApiSeedWordSuggestions({required String word});
+
+  Future<String> crateApiSendBatch({required String filePath});
+
+  Future<String> crateApiSendFromAccount(
+      {required int account,
+      required String address,
+      required BigInt amount,
+      String? memo});
+
+  Future<String> crateApiSendFromAddress(
+      {required String fromAddress,
+      required String address,
+      required BigInt amount,
+      String? memo});
+
+  Future<String> crateApiSendFromInputs(
+      {required List<String> inputIds,
+      required String address,
+      required BigInt amount,
+      String? memo});
+
+  Future<String> crateApiSendMaxTransaction(
+      {required String address, String? memo});
+
   Future<String> crateApiSendProgressUpdate({required String progressData});
 
   Future<String> crateApiSendTransaction(
-      {required String address, required PlatformInt64 amount, String? memo});
+      {required String address,
+      required PlatformInt64 amount,
+      String? memo,
+      String? utxoStrategy});
+
+  String crateApiSetBroadcastServers({required List<String> servers});
+
+  String crateApiSetChangePolicy({required String policy});
+
+  Future<String> crateApiSetPassphrase({required String passphrase});
+
+  String crateApiSetPrivacyPolicy({required String policy});
+
+  Future<String> crateApiShieldFromAddresses(
+      {required List<String> fromAddresses, String? toAddress});
+
+  Future<String> crateApiSignUnsignedTransaction({required String proposalHex});
 
   Future<String> crateApiSync();
 
+  String crateApiUnfreezeInputs({required List<String> inputIds});
+
+  String crateApiValidateMemo({required String memo, String? address});
+
+  String crateApiValidateSeedPhrase({required String seedPhrase});
+
   Future<bool> crateApiWalletExists({String? walletDir});
 }
 
@@ -152,10 +287,11 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   });
 
   @override
-  Future<String> crateApiDeinitialize() {
+  Future<String> crateApiBroadcastSignedTransaction({required String txHex}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(txHex, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
             funcId: 1, port: port_);
       },
@@ -163,51 +299,50 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiDeinitializeConstMeta,
-      argValues: [],
+      constMeta: kCrateApiBroadcastSignedTransactionConstMeta,
+      argValues: [txHex],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiDeinitializeConstMeta => const TaskConstMeta(
-        debugName: "deinitialize",
-        argNames: [],
+  TaskConstMeta get kCrateApiBroadcastSignedTransactionConstMeta =>
+      const TaskConstMeta(
+        debugName: "broadcast_signed_transaction",
+        argNames: ["txHex"],
       );
 
   @override
-  Future<void> crateApiEmitProgressUpdate(
-      {required int progress, required int total}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiCancelScheduledPayment({required BigInt id}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_u_32(progress, serializer);
-        sse_encode_u_32(total, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 2, port: port_);
+        sse_encode_u_64(id, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 2)!;
       },
       codec: SseCodec(
-        decodeSuccessData: sse_decode_unit,
+        decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiEmitProgressUpdateConstMeta,
-      argValues: [progress, total],
+      constMeta: kCrateApiCancelScheduledPaymentConstMeta,
+      argValues: [id],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiEmitProgressUpdateConstMeta => const TaskConstMeta(
-        debugName: "emit_progress_update",
-        argNames: ["progress", "total"],
+  TaskConstMeta get kCrateApiCancelScheduledPaymentConstMeta =>
+      const TaskConstMeta(
+        debugName: "cancel_scheduled_payment",
+        argNames: ["id"],
       );
 
   @override
-  Future<String> crateApiExecute(
-      {required String command, required String args}) {
+  Future<String> crateApiCheckPropagation(
+      {required String txid, BigInt? timeoutSecs}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(command, serializer);
-        sse_encode_String(args, serializer);
+        sse_encode_String(txid, serializer);
+        sse_encode_opt_box_autoadd_u_64(timeoutSecs, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
             funcId: 3, port: port_);
       },
@@ -215,536 +350,1743 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiExecuteConstMeta,
-      argValues: [command, args],
+      constMeta: kCrateApiCheckPropagationConstMeta,
+      argValues: [txid, timeoutSecs],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiExecuteConstMeta => const TaskConstMeta(
-        debugName: "execute",
-        argNames: ["command", "args"],
+  TaskConstMeta get kCrateApiCheckPropagationConstMeta => const TaskConstMeta(
+        debugName: "check_propagation",
+        argNames: ["txid", "timeoutSecs"],
       );
 
   @override
-  String crateApiGetAddresses() {
-    return handler.executeSync(SyncTask(
-      callFfi: () {
+  Future<String> crateApiConsolidateNotes(
+      {int? maxNotes, BigInt? dustThreshold}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 4)!;
+        sse_encode_opt_box_autoadd_u_32(maxNotes, serializer);
+        sse_encode_opt_box_autoadd_u_64(dustThreshold, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 4, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetAddressesConstMeta,
-      argValues: [],
+      constMeta: kCrateApiConsolidateNotesConstMeta,
+      argValues: [maxNotes, dustThreshold],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetAddressesConstMeta => const TaskConstMeta(
-        debugName: "get_addresses",
-        argNames: [],
+  TaskConstMeta get kCrateApiConsolidateNotesConstMeta => const TaskConstMeta(
+        debugName: "consolidate_notes",
+        argNames: ["maxNotes", "dustThreshold"],
       );
 
   @override
-  String crateApiGetBalance() {
+  String crateApiCreateAccount({required String name}) {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(name, serializer);
         return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 5)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetBalanceConstMeta,
-      argValues: [],
+      constMeta: kCrateApiCreateAccountConstMeta,
+      argValues: [name],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetBalanceConstMeta => const TaskConstMeta(
-        debugName: "get_balance",
-        argNames: [],
+  TaskConstMeta get kCrateApiCreateAccountConstMeta => const TaskConstMeta(
+        debugName: "create_account",
+        argNames: ["name"],
       );
 
   @override
-  int crateApiGetHeight() {
+  String crateApiCreatePaymentUri({required String paymentsJson}) {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(paymentsJson, serializer);
         return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 6)!;
       },
       codec: SseCodec(
-        decodeSuccessData: sse_decode_u_32,
+        decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetHeightConstMeta,
-      argValues: [],
+      constMeta: kCrateApiCreatePaymentUriConstMeta,
+      argValues: [paymentsJson],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetHeightConstMeta => const TaskConstMeta(
-        debugName: "get_height",
-        argNames: [],
+  TaskConstMeta get kCrateApiCreatePaymentUriConstMeta => const TaskConstMeta(
+        debugName: "create_payment_uri",
+        argNames: ["paymentsJson"],
       );
 
   @override
-  String crateApiGetInfo() {
+  Future<String> crateApiCreateUnsignedTransaction(
+      {required String address, required BigInt amount, String? memo}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(address, serializer);
+        sse_encode_u_64(amount, serializer);
+        sse_encode_opt_String(memo, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 7, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiCreateUnsignedTransactionConstMeta,
+      argValues: [address, amount, memo],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiCreateUnsignedTransactionConstMeta =>
+      const TaskConstMeta(
+        debugName: "create_unsigned_transaction",
+        argNames: ["address", "amount", "memo"],
+      );
+
+  @override
+  String crateApiDecodeRawTransaction({required String txHex}) {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 7)!;
+        sse_encode_String(txHex, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 8)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetInfoConstMeta,
-      argValues: [],
+      constMeta: kCrateApiDecodeRawTransactionConstMeta,
+      argValues: [txHex],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetInfoConstMeta => const TaskConstMeta(
-        debugName: "get_info",
-        argNames: [],
+  TaskConstMeta get kCrateApiDecodeRawTransactionConstMeta =>
+      const TaskConstMeta(
+        debugName: "decode_raw_transaction",
+        argNames: ["txHex"],
       );
 
   @override
-  Future<String> crateApiGetNextProgressUpdate() {
+  Future<String> crateApiDeinitialize() {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 8, port: port_);
+            funcId: 9, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetNextProgressUpdateConstMeta,
+      constMeta: kCrateApiDeinitializeConstMeta,
       argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetNextProgressUpdateConstMeta =>
-      const TaskConstMeta(
-        debugName: "get_next_progress_update",
+  TaskConstMeta get kCrateApiDeinitializeConstMeta => const TaskConstMeta(
+        debugName: "deinitialize",
         argNames: [],
       );
 
   @override
-  String crateApiGetSendProgress() {
+  String crateApiDisableAutoShield() {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 9)!;
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 10)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetSendProgressConstMeta,
+      constMeta: kCrateApiDisableAutoShieldConstMeta,
       argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetSendProgressConstMeta => const TaskConstMeta(
-        debugName: "get_send_progress",
+  TaskConstMeta get kCrateApiDisableAutoShieldConstMeta => const TaskConstMeta(
+        debugName: "disable_auto_shield",
         argNames: [],
       );
 
   @override
-  Future<String> crateApiGetServerInfo({required String serverUri}) {
+  String crateApiEnableAutoShield(
+      {required BigInt threshold,
+      required int minConfirmations,
+      String? address}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_64(threshold, serializer);
+        sse_encode_u_32(minConfirmations, serializer);
+        sse_encode_opt_String(address, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 11)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiEnableAutoShieldConstMeta,
+      argValues: [threshold, minConfirmations, address],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiEnableAutoShieldConstMeta => const TaskConstMeta(
+        debugName: "enable_auto_shield",
+        argNames: ["threshold", "minConfirmations", "address"],
+      );
+
+  @override
+  Future<String> crateApiExecute(
+      {required String command, required String args}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
+        sse_encode_String(command, serializer);
+        sse_encode_String(args, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 10, port: port_);
+            funcId: 12, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetServerInfoConstMeta,
-      argValues: [serverUri],
+      constMeta: kCrateApiExecuteConstMeta,
+      argValues: [command, args],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetServerInfoConstMeta => const TaskConstMeta(
-        debugName: "get_server_info",
-        argNames: ["serverUri"],
+  TaskConstMeta get kCrateApiExecuteConstMeta => const TaskConstMeta(
+        debugName: "execute",
+        argNames: ["command", "args"],
       );
 
   @override
-  String crateApiGetSyncStatus() {
+  String crateApiFreezeInputs({required List<String> inputIds}) {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 11)!;
+        sse_encode_list_String(inputIds, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 13)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetSyncStatusConstMeta,
+      constMeta: kCrateApiFreezeInputsConstMeta,
+      argValues: [inputIds],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiFreezeInputsConstMeta => const TaskConstMeta(
+        debugName: "freeze_inputs",
+        argNames: ["inputIds"],
+      );
+
+  @override
+  String crateApiGetAccountBalance({required int account}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_32(account, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 14)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetAccountBalanceConstMeta,
+      argValues: [account],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetAccountBalanceConstMeta => const TaskConstMeta(
+        debugName: "get_account_balance",
+        argNames: ["account"],
+      );
+
+  @override
+  String crateApiGetAccountTransactions({required int account}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_32(account, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 15)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetAccountTransactionsConstMeta,
+      argValues: [account],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetAccountTransactionsConstMeta =>
+      const TaskConstMeta(
+        debugName: "get_account_transactions",
+        argNames: ["account"],
+      );
+
+  @override
+  String crateApiGetAccounts() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 16)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetAccountsConstMeta,
       argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetSyncStatusConstMeta => const TaskConstMeta(
-        debugName: "get_sync_status",
+  TaskConstMeta get kCrateApiGetAccountsConstMeta => const TaskConstMeta(
+        debugName: "get_accounts",
         argNames: [],
       );
 
   @override
-  String crateApiGetTransactions() {
+  String crateApiGetAddresses() {
     return handler.executeSync(SyncTask(
       callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 12)!;
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 17)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiGetTransactionsConstMeta,
+      constMeta: kCrateApiGetAddressesConstMeta,
       argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiGetTransactionsConstMeta => const TaskConstMeta(
-        debugName: "get_transactions",
+  TaskConstMeta get kCrateApiGetAddressesConstMeta => const TaskConstMeta(
+        debugName: "get_addresses",
         argNames: [],
       );
 
   @override
-  Future<void> crateApiInitProgressBridge() {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiGetAutoShield() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 13, port: port_);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 18)!;
       },
       codec: SseCodec(
-        decodeSuccessData: sse_decode_unit,
+        decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitProgressBridgeConstMeta,
+      constMeta: kCrateApiGetAutoShieldConstMeta,
       argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitProgressBridgeConstMeta => const TaskConstMeta(
-        debugName: "init_progress_bridge",
+  TaskConstMeta get kCrateApiGetAutoShieldConstMeta => const TaskConstMeta(
+        debugName: "get_auto_shield",
         argNames: [],
       );
 
   @override
-  Future<String> crateApiInitProgressStream() {
+  String crateApiGetBalance() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 19)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetBalanceConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetBalanceConstMeta => const TaskConstMeta(
+        debugName: "get_balance",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetBroadcastServers() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 20)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetBroadcastServersConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetBroadcastServersConstMeta =>
+      const TaskConstMeta(
+        debugName: "get_broadcast_servers",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetChangePolicy() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 21)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetChangePolicyConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetChangePolicyConstMeta => const TaskConstMeta(
+        debugName: "get_change_policy",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetDuePayments() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 22)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetDuePaymentsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetDuePaymentsConstMeta => const TaskConstMeta(
+        debugName: "get_due_payments",
+        argNames: [],
+      );
+
+  @override
+  int crateApiGetHeight() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 23)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_u_32,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetHeightConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetHeightConstMeta => const TaskConstMeta(
+        debugName: "get_height",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetInfo() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 24)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetInfoConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetInfoConstMeta => const TaskConstMeta(
+        debugName: "get_info",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiGetNextProgressUpdate() {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 25, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetNextProgressUpdateConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetNextProgressUpdateConstMeta =>
+      const TaskConstMeta(
+        debugName: "get_next_progress_update",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetPendingSends() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 26)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetPendingSendsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetPendingSendsConstMeta => const TaskConstMeta(
+        debugName: "get_pending_sends",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetPrivacyPolicy() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 27)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetPrivacyPolicyConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetPrivacyPolicyConstMeta => const TaskConstMeta(
+        debugName: "get_privacy_policy",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetSendProgress() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 28)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetSendProgressConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetSendProgressConstMeta => const TaskConstMeta(
+        debugName: "get_send_progress",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiGetServerInfo({required String serverUri}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 29, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetServerInfoConstMeta,
+      argValues: [serverUri],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetServerInfoConstMeta => const TaskConstMeta(
+        debugName: "get_server_info",
+        argNames: ["serverUri"],
+      );
+
+  @override
+  String crateApiGetSpendableInputs() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 30)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetSpendableInputsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetSpendableInputsConstMeta => const TaskConstMeta(
+        debugName: "get_spendable_inputs",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetSyncStatus() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 31)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetSyncStatusConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetSyncStatusConstMeta => const TaskConstMeta(
+        debugName: "get_sync_status",
+        argNames: [],
+      );
+
+  @override
+  String crateApiGetTransactions() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 32)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiGetTransactionsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiGetTransactionsConstMeta => const TaskConstMeta(
+        debugName: "get_transactions",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiInitProgressStream() {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 33, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitProgressStreamConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitProgressStreamConstMeta => const TaskConstMeta(
+        debugName: "init_progress_stream",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiInitializeExisting(
+      {required String serverUri, String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 34, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeExistingConstMeta,
+      argValues: [serverUri, walletDir],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeExistingConstMeta => const TaskConstMeta(
+        debugName: "initialize_existing",
+        argNames: ["serverUri", "walletDir"],
+      );
+
+  @override
+  Future<String> crateApiInitializeExistingWithBirthday(
+      {required String serverUri,
+      String? walletDir,
+      required BigInt birthday}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        sse_encode_u_64(birthday, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 35, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeExistingWithBirthdayConstMeta,
+      argValues: [serverUri, walletDir, birthday],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeExistingWithBirthdayConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_existing_with_birthday",
+        argNames: ["serverUri", "walletDir", "birthday"],
+      );
+
+  @override
+  Future<String> crateApiInitializeFromPhrase(
+      {required String serverUri,
+      required String seedPhrase,
+      required BigInt birthday,
+      required bool overwrite,
+      String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_String(seedPhrase, serializer);
+        sse_encode_u_64(birthday, serializer);
+        sse_encode_bool(overwrite, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 36, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeFromPhraseConstMeta,
+      argValues: [serverUri, seedPhrase, birthday, overwrite, walletDir],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeFromPhraseConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_from_phrase",
+        argNames: [
+          "serverUri",
+          "seedPhrase",
+          "birthday",
+          "overwrite",
+          "walletDir"
+        ],
+      );
+
+  @override
+  Future<String> crateApiInitializeFromPhraseSimple(
+      {required String serverUri, required String seedPhrase}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_String(seedPhrase, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 37, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeFromPhraseSimpleConstMeta,
+      argValues: [serverUri, seedPhrase],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeFromPhraseSimpleConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_from_phrase_simple",
+        argNames: ["serverUri", "seedPhrase"],
+      );
+
+  @override
+  Future<String> crateApiInitializeFromPhraseWithPassphrase(
+      {required String serverUri,
+      required String seedPhrase,
+      String? passphrase,
+      required BigInt birthday,
+      required bool overwrite,
+      String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_String(seedPhrase, serializer);
+        sse_encode_opt_String(passphrase, serializer);
+        sse_encode_u_64(birthday, serializer);
+        sse_encode_bool(overwrite, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 38, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeFromPhraseWithPassphraseConstMeta,
+      argValues: [
+        serverUri,
+        seedPhrase,
+        passphrase,
+        birthday,
+        overwrite,
+        walletDir
+      ],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeFromPhraseWithPassphraseConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_from_phrase_with_passphrase",
+        argNames: [
+          "serverUri",
+          "seedPhrase",
+          "passphrase",
+          "birthday",
+          "overwrite",
+          "walletDir"
+        ],
+      );
+
+  @override
+  Future<String> crateApiInitializeNew(
+      {required String serverUri, String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 39, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeNewConstMeta,
+      argValues: [serverUri, walletDir],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeNewConstMeta => const TaskConstMeta(
+        debugName: "initialize_new",
+        argNames: ["serverUri", "walletDir"],
+      );
+
+  @override
+  Future<String> crateApiInitializeNewWithInfo(
+      {required String serverUri, String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 40, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeNewWithInfoConstMeta,
+      argValues: [serverUri, walletDir],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeNewWithInfoConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_new_with_info",
+        argNames: ["serverUri", "walletDir"],
+      );
+
+  @override
+  Future<String> crateApiInitializeNewWithPassphrase(
+      {required String serverUri, String? passphrase, String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_opt_String(passphrase, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 41, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeNewWithPassphraseConstMeta,
+      argValues: [serverUri, passphrase, walletDir],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeNewWithPassphraseConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_new_with_passphrase",
+        argNames: ["serverUri", "passphrase", "walletDir"],
+      );
+
+  @override
+  Future<String> crateApiInitializeWatchOnly(
+      {required String serverUri,
+      required List<String> viewingKeys,
+      required List<String> taddrs,
+      required BigInt birthday,
+      required bool overwrite,
+      String? walletDir}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(serverUri, serializer);
+        sse_encode_list_String(viewingKeys, serializer);
+        sse_encode_list_String(taddrs, serializer);
+        sse_encode_u_64(birthday, serializer);
+        sse_encode_bool(overwrite, serializer);
+        sse_encode_opt_String(walletDir, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 42, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiInitializeWatchOnlyConstMeta,
+      argValues: [
+        serverUri,
+        viewingKeys,
+        taddrs,
+        birthday,
+        overwrite,
+        walletDir
+      ],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiInitializeWatchOnlyConstMeta =>
+      const TaskConstMeta(
+        debugName: "initialize_watch_only",
+        argNames: [
+          "serverUri",
+          "viewingKeys",
+          "taddrs",
+          "birthday",
+          "overwrite",
+          "walletDir"
+        ],
+      );
+
+  @override
+  String crateApiListScheduledPayments() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 43)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiListScheduledPaymentsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiListScheduledPaymentsConstMeta =>
+      const TaskConstMeta(
+        debugName: "list_scheduled_payments",
+        argNames: [],
+      );
+
+  @override
+  String crateApiNewAccountAddress(
+      {required int account, required String addressType}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_32(account, serializer);
+        sse_encode_String(addressType, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 44)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiNewAccountAddressConstMeta,
+      argValues: [account, addressType],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiNewAccountAddressConstMeta => const TaskConstMeta(
+        debugName: "new_account_address",
+        argNames: ["account", "addressType"],
+      );
+
+  @override
+  String crateApiNewAddress({required String addressType}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(addressType, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 45)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiNewAddressConstMeta,
+      argValues: [addressType],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiNewAddressConstMeta => const TaskConstMeta(
+        debugName: "new_address",
+        argNames: ["addressType"],
+      );
+
+  @override
+  String crateApiNewDiversifiedAddress({String? zaddr}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_opt_String(zaddr, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 46)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiNewDiversifiedAddressConstMeta,
+      argValues: [zaddr],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiNewDiversifiedAddressConstMeta =>
+      const TaskConstMeta(
+        debugName: "new_diversified_address",
+        argNames: ["zaddr"],
+      );
+
+  @override
+  String crateApiParsePaymentUri({required String uri}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(uri, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 47)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiParsePaymentUriConstMeta,
+      argValues: [uri],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiParsePaymentUriConstMeta => const TaskConstMeta(
+        debugName: "parse_payment_uri",
+        argNames: ["uri"],
+      );
+
+  @override
+  Future<String> crateApiPayDuePayments() {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 48, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiPayDuePaymentsConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiPayDuePaymentsConstMeta => const TaskConstMeta(
+        debugName: "pay_due_payments",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiPreloadProver() {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 49, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiPreloadProverConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiPreloadProverConstMeta => const TaskConstMeta(
+        debugName: "preload_prover",
+        argNames: [],
+      );
+
+  @override
+  Future<String> crateApiPreviewConsolidation(
+      {int? maxNotes, BigInt? dustThreshold}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_opt_box_autoadd_u_32(maxNotes, serializer);
+        sse_encode_opt_box_autoadd_u_64(dustThreshold, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 50, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiPreviewConsolidationConstMeta,
+      argValues: [maxNotes, dustThreshold],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiPreviewConsolidationConstMeta =>
+      const TaskConstMeta(
+        debugName: "preview_consolidation",
+        argNames: ["maxNotes", "dustThreshold"],
+      );
+
+  @override
+  String crateApiReleaseProver() {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 51)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiReleaseProverConstMeta,
+      argValues: [],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiReleaseProverConstMeta => const TaskConstMeta(
+        debugName: "release_prover",
+        argNames: [],
+      );
+
+  @override
+  String crateApiRenameAccount({required int account, required String name}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_32(account, serializer);
+        sse_encode_String(name, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 52)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiRenameAccountConstMeta,
+      argValues: [account, name],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiRenameAccountConstMeta => const TaskConstMeta(
+        debugName: "rename_account",
+        argNames: ["account", "name"],
+      );
+
+  @override
+  String crateApiSchedulePayment(
+      {required String recipientsJson,
+      int? dueHeight,
+      BigInt? dueTime,
+      BigInt? interval}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(recipientsJson, serializer);
+        sse_encode_opt_box_autoadd_u_32(dueHeight, serializer);
+        sse_encode_opt_box_autoadd_u_64(dueTime, serializer);
+        sse_encode_opt_box_autoadd_u_64(interval, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 53)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSchedulePaymentConstMeta,
+      argValues: [recipientsJson, dueHeight, dueTime, interval],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSchedulePaymentConstMeta => const TaskConstMeta(
+        debugName: "schedule_payment",
+        argNames: ["recipientsJson", "dueHeight", "dueTime", "interval"],
+      );
+
+  @override
+  String crateApiSeedWordSuggestions({required String word}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(word, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 54)!;
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSeedWordSuggestionsConstMeta,
+      argValues: [word],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSeedWordSuggestionsConstMeta =>
+      const TaskConstMeta(
+        debugName: "seed_word_suggestions",
+        argNames: ["word"],
+      );
+
+  @override
+  Future<String> crateApiSendBatch({required String filePath}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(filePath, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 55, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendBatchConstMeta,
+      argValues: [filePath],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendBatchConstMeta => const TaskConstMeta(
+        debugName: "send_batch",
+        argNames: ["filePath"],
+      );
+
+  @override
+  Future<String> crateApiSendFromAccount(
+      {required int account,
+      required String address,
+      required BigInt amount,
+      String? memo}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_u_32(account, serializer);
+        sse_encode_String(address, serializer);
+        sse_encode_u_64(amount, serializer);
+        sse_encode_opt_String(memo, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 56, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendFromAccountConstMeta,
+      argValues: [account, address, amount, memo],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendFromAccountConstMeta => const TaskConstMeta(
+        debugName: "send_from_account",
+        argNames: ["account", "address", "amount", "memo"],
+      );
+
+  @override
+  Future<String> crateApiSendFromAddress(
+      {required String fromAddress,
+      required String address,
+      required BigInt amount,
+      String? memo}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(fromAddress, serializer);
+        sse_encode_String(address, serializer);
+        sse_encode_u_64(amount, serializer);
+        sse_encode_opt_String(memo, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 57, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendFromAddressConstMeta,
+      argValues: [fromAddress, address, amount, memo],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendFromAddressConstMeta => const TaskConstMeta(
+        debugName: "send_from_address",
+        argNames: ["fromAddress", "address", "amount", "memo"],
+      );
+
+  @override
+  Future<String> crateApiSendFromInputs(
+      {required List<String> inputIds,
+      required String address,
+      required BigInt amount,
+      String? memo}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_list_String(inputIds, serializer);
+        sse_encode_String(address, serializer);
+        sse_encode_u_64(amount, serializer);
+        sse_encode_opt_String(memo, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 58, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendFromInputsConstMeta,
+      argValues: [inputIds, address, amount, memo],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendFromInputsConstMeta => const TaskConstMeta(
+        debugName: "send_from_inputs",
+        argNames: ["inputIds", "address", "amount", "memo"],
+      );
+
+  @override
+  Future<String> crateApiSendMaxTransaction(
+      {required String address, String? memo}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(address, serializer);
+        sse_encode_opt_String(memo, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 59, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendMaxTransactionConstMeta,
+      argValues: [address, memo],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendMaxTransactionConstMeta => const TaskConstMeta(
+        debugName: "send_max_transaction",
+        argNames: ["address", "memo"],
+      );
+
+  @override
+  Future<String> crateApiSendProgressUpdate({required String progressData}) {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
+        final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(progressData, serializer);
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 60, port: port_);
+      },
+      codec: SseCodec(
+        decodeSuccessData: sse_decode_String,
+        decodeErrorData: null,
+      ),
+      constMeta: kCrateApiSendProgressUpdateConstMeta,
+      argValues: [progressData],
+      apiImpl: this,
+    ));
+  }
+
+  TaskConstMeta get kCrateApiSendProgressUpdateConstMeta => const TaskConstMeta(
+        debugName: "send_progress_update",
+        argNames: ["progressData"],
+      );
+
+  @override
+  Future<String> crateApiSendTransaction(
+      {required String address,
+      required PlatformInt64 amount,
+      String? memo,
+      String? utxoStrategy}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
+        sse_encode_String(address, serializer);
+        sse_encode_i_64(amount, serializer);
+        sse_encode_opt_String(memo, serializer);
+        sse_encode_opt_String(utxoStrategy, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 14, port: port_);
+            funcId: 61, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitProgressStreamConstMeta,
-      argValues: [],
+      constMeta: kCrateApiSendTransactionConstMeta,
+      argValues: [address, amount, memo, utxoStrategy],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitProgressStreamConstMeta => const TaskConstMeta(
-        debugName: "init_progress_stream",
-        argNames: [],
+  TaskConstMeta get kCrateApiSendTransactionConstMeta => const TaskConstMeta(
+        debugName: "send_transaction",
+        argNames: ["address", "amount", "memo", "utxoStrategy"],
       );
 
   @override
-  Future<String> crateApiInitializeExisting(
-      {required String serverUri, String? walletDir}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiSetBroadcastServers({required List<String> servers}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_opt_String(walletDir, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 15, port: port_);
+        sse_encode_list_String(servers, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 62)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeExistingConstMeta,
-      argValues: [serverUri, walletDir],
+      constMeta: kCrateApiSetBroadcastServersConstMeta,
+      argValues: [servers],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeExistingConstMeta => const TaskConstMeta(
-        debugName: "initialize_existing",
-        argNames: ["serverUri", "walletDir"],
+  TaskConstMeta get kCrateApiSetBroadcastServersConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_broadcast_servers",
+        argNames: ["servers"],
       );
 
   @override
-  Future<String> crateApiInitializeExistingWithBirthday(
-      {required String serverUri,
-      String? walletDir,
-      required BigInt birthday}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiSetChangePolicy({required String policy}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_opt_String(walletDir, serializer);
-        sse_encode_u_64(birthday, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 16, port: port_);
+        sse_encode_String(policy, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 63)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeExistingWithBirthdayConstMeta,
-      argValues: [serverUri, walletDir, birthday],
+      constMeta: kCrateApiSetChangePolicyConstMeta,
+      argValues: [policy],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeExistingWithBirthdayConstMeta =>
-      const TaskConstMeta(
-        debugName: "initialize_existing_with_birthday",
-        argNames: ["serverUri", "walletDir", "birthday"],
+  TaskConstMeta get kCrateApiSetChangePolicyConstMeta => const TaskConstMeta(
+        debugName: "set_change_policy",
+        argNames: ["policy"],
       );
 
   @override
-  Future<String> crateApiInitializeFromPhrase(
-      {required String serverUri,
-      required String seedPhrase,
-      required BigInt birthday,
-      required bool overwrite,
-      String? walletDir}) {
+  Future<String> crateApiSetPassphrase({required String passphrase}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_String(seedPhrase, serializer);
-        sse_encode_u_64(birthday, serializer);
-        sse_encode_bool(overwrite, serializer);
-        sse_encode_opt_String(walletDir, serializer);
+        sse_encode_String(passphrase, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 17, port: port_);
+            funcId: 64, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeFromPhraseConstMeta,
-      argValues: [serverUri, seedPhrase, birthday, overwrite, walletDir],
+      constMeta: kCrateApiSetPassphraseConstMeta,
+      argValues: [passphrase],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeFromPhraseConstMeta =>
-      const TaskConstMeta(
-        debugName: "initialize_from_phrase",
-        argNames: [
-          "serverUri",
-          "seedPhrase",
-          "birthday",
-          "overwrite",
-          "walletDir"
-        ],
+  TaskConstMeta get kCrateApiSetPassphraseConstMeta => const TaskConstMeta(
+        debugName: "set_passphrase",
+        argNames: ["passphrase"],
       );
 
   @override
-  Future<String> crateApiInitializeFromPhraseSimple(
-      {required String serverUri, required String seedPhrase}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiSetPrivacyPolicy({required String policy}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_String(seedPhrase, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 18, port: port_);
+        sse_encode_String(policy, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 65)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeFromPhraseSimpleConstMeta,
-      argValues: [serverUri, seedPhrase],
+      constMeta: kCrateApiSetPrivacyPolicyConstMeta,
+      argValues: [policy],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeFromPhraseSimpleConstMeta =>
-      const TaskConstMeta(
-        debugName: "initialize_from_phrase_simple",
-        argNames: ["serverUri", "seedPhrase"],
+  TaskConstMeta get kCrateApiSetPrivacyPolicyConstMeta => const TaskConstMeta(
+        debugName: "set_privacy_policy",
+        argNames: ["policy"],
       );
 
   @override
-  Future<String> crateApiInitializeNew(
-      {required String serverUri, String? walletDir}) {
+  Future<String> crateApiShieldFromAddresses(
+      {required List<String> fromAddresses, String? toAddress}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_opt_String(walletDir, serializer);
+        sse_encode_list_String(fromAddresses, serializer);
+        sse_encode_opt_String(toAddress, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 19, port: port_);
+            funcId: 66, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeNewConstMeta,
-      argValues: [serverUri, walletDir],
+      constMeta: kCrateApiShieldFromAddressesConstMeta,
+      argValues: [fromAddresses, toAddress],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeNewConstMeta => const TaskConstMeta(
-        debugName: "initialize_new",
-        argNames: ["serverUri", "walletDir"],
+  TaskConstMeta get kCrateApiShieldFromAddressesConstMeta =>
+      const TaskConstMeta(
+        debugName: "shield_from_addresses",
+        argNames: ["fromAddresses", "toAddress"],
       );
 
   @override
-  Future<String> crateApiInitializeNewWithInfo(
-      {required String serverUri, String? walletDir}) {
+  Future<String> crateApiSignUnsignedTransaction(
+      {required String proposalHex}) {
     return handler.executeNormal(NormalTask(
       callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(serverUri, serializer);
-        sse_encode_opt_String(walletDir, serializer);
+        sse_encode_String(proposalHex, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 20, port: port_);
+            funcId: 67, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiInitializeNewWithInfoConstMeta,
-      argValues: [serverUri, walletDir],
+      constMeta: kCrateApiSignUnsignedTransactionConstMeta,
+      argValues: [proposalHex],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiInitializeNewWithInfoConstMeta =>
+  TaskConstMeta get kCrateApiSignUnsignedTransactionConstMeta =>
       const TaskConstMeta(
-        debugName: "initialize_new_with_info",
-        argNames: ["serverUri", "walletDir"],
+        debugName: "sign_unsigned_transaction",
+        argNames: ["proposalHex"],
       );
 
   @override
-  String crateApiNewAddress({required String addressType}) {
-    return handler.executeSync(SyncTask(
-      callFfi: () {
+  Future<String> crateApiSync() {
+    return handler.executeNormal(NormalTask(
+      callFfi: (port_) {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(addressType, serializer);
-        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 21)!;
+        pdeCallFfi(generalizedFrbRustBinding, serializer,
+            funcId: 68, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiNewAddressConstMeta,
-      argValues: [addressType],
+      constMeta: kCrateApiSyncConstMeta,
+      argValues: [],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiNewAddressConstMeta => const TaskConstMeta(
-        debugName: "new_address",
-        argNames: ["addressType"],
+  TaskConstMeta get kCrateApiSyncConstMeta => const TaskConstMeta(
+        debugName: "sync",
+        argNames: [],
       );
 
   @override
-  Future<String> crateApiSendProgressUpdate({required String progressData}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiUnfreezeInputs({required List<String> inputIds}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(progressData, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 22, port: port_);
+        sse_encode_list_String(inputIds, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 69)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiSendProgressUpdateConstMeta,
-      argValues: [progressData],
+      constMeta: kCrateApiUnfreezeInputsConstMeta,
+      argValues: [inputIds],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiSendProgressUpdateConstMeta => const TaskConstMeta(
-        debugName: "send_progress_update",
-        argNames: ["progressData"],
+  TaskConstMeta get kCrateApiUnfreezeInputsConstMeta => const TaskConstMeta(
+        debugName: "unfreeze_inputs",
+        argNames: ["inputIds"],
       );
 
   @override
-  Future<String> crateApiSendTransaction(
-      {required String address, required PlatformInt64 amount, String? memo}) {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiValidateMemo({required String memo, String? address}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        sse_encode_String(address, serializer);
-        sse_encode_i_64(amount, serializer);
-        sse_encode_opt_String(memo, serializer);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 23, port: port_);
+        sse_encode_String(memo, serializer);
+        sse_encode_opt_String(address, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 70)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiSendTransactionConstMeta,
-      argValues: [address, amount, memo],
+      constMeta: kCrateApiValidateMemoConstMeta,
+      argValues: [memo, address],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiSendTransactionConstMeta => const TaskConstMeta(
-        debugName: "send_transaction",
-        argNames: ["address", "amount", "memo"],
+  TaskConstMeta get kCrateApiValidateMemoConstMeta => const TaskConstMeta(
+        debugName: "validate_memo",
+        argNames: ["memo", "address"],
       );
 
   @override
-  Future<String> crateApiSync() {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
+  String crateApiValidateSeedPhrase({required String seedPhrase}) {
+    return handler.executeSync(SyncTask(
+      callFfi: () {
         final serializer = SseSerializer(generalizedFrbRustBinding);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 24, port: port_);
+        sse_encode_String(seedPhrase, serializer);
+        return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 71)!;
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_String,
         decodeErrorData: null,
       ),
-      constMeta: kCrateApiSyncConstMeta,
-      argValues: [],
+      constMeta: kCrateApiValidateSeedPhraseConstMeta,
+      argValues: [seedPhrase],
       apiImpl: this,
     ));
   }
 
-  TaskConstMeta get kCrateApiSyncConstMeta => const TaskConstMeta(
-        debugName: "sync",
-        argNames: [],
+  TaskConstMeta get kCrateApiValidateSeedPhraseConstMeta => const TaskConstMeta(
+        debugName: "validate_seed_phrase",
+        argNames: ["seedPhrase"],
       );
 
   @override
@@ -754,7 +2096,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         final serializer = SseSerializer(generalizedFrbRustBinding);
         sse_encode_opt_String(walletDir, serializer);
         pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 25, port: port_);
+            funcId: 72, port: port_);
       },
       codec: SseCodec(
         decodeSuccessData: sse_decode_bool,
@@ -783,12 +2125,30 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw as bool;
   }
 
+  @protected
+  int dco_decode_box_autoadd_u_32(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw as int;
+  }
+
+  @protected
+  BigInt dco_decode_box_autoadd_u_64(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return dco_decode_u_64(raw);
+  }
+
   @protected
   PlatformInt64 dco_decode_i_64(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     return dcoDecodeI64(raw);
   }
 
+  @protected
+  List<String> dco_decode_list_String(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_String).toList();
+  }
+
   @protected
   Uint8List dco_decode_list_prim_u_8_strict(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -801,6 +2161,18 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw == null ? null : dco_decode_String(raw);
   }
 
+  @protected
+  int? dco_decode_opt_box_autoadd_u_32(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw == null ? null : dco_decode_box_autoadd_u_32(raw);
+  }
+
+  @protected
+  BigInt? dco_decode_opt_box_autoadd_u_64(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return raw == null ? null : dco_decode_box_autoadd_u_64(raw);
+  }
+
   @protected
   int dco_decode_u_32(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -819,12 +2191,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw as int;
   }
 
-  @protected
-  void dco_decode_unit(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return;
-  }
-
   @protected
   String sse_decode_String(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -838,12 +2204,36 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return deserializer.buffer.getUint8() != 0;
   }
 
+  @protected
+  int sse_decode_box_autoadd_u_32(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_u_32(deserializer));
+  }
+
+  @protected
+  BigInt sse_decode_box_autoadd_u_64(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_u_64(deserializer));
+  }
+
   @protected
   PlatformInt64 sse_decode_i_64(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     return deserializer.buffer.getPlatformInt64();
   }
 
+  @protected
+  List<String> sse_decode_list_String(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <String>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_String(deserializer));
+    }
+    return ans_;
+  }
+
   @protected
   Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -862,6 +2252,28 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    if (sse_decode_bool(deserializer)) {
+      return (sse_decode_box_autoadd_u_32(deserializer));
+    } else {
+      return null;
+    }
+  }
+
+  @protected
+  BigInt? sse_decode_opt_box_autoadd_u_64(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    if (sse_decode_bool(deserializer)) {
+      return (sse_decode_box_autoadd_u_64(deserializer));
+    } else {
+      return null;
+    }
+  }
+
   @protected
   int sse_decode_u_32(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -880,11 +2292,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return deserializer.buffer.getUint8();
   }
 
-  @protected
-  void sse_decode_unit(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
   @protected
   int sse_decode_i_32(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -903,12 +2310,33 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     serializer.buffer.putUint8(self ? 1 : 0);
   }
 
+  @protected
+  void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_u_32(self, serializer);
+  }
+
+  @protected
+  void sse_encode_box_autoadd_u_64(BigInt self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_u_64(self, serializer);
+  }
+
   @protected
   void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     serializer.buffer.putPlatformInt64(self);
   }
 
+  @protected
+  void sse_encode_list_String(List<String> self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_String(item, serializer);
+    }
+  }
+
   @protected
   void sse_encode_list_prim_u_8_strict(
       Uint8List self, SseSerializer serializer) {
@@ -927,6 +2355,26 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    sse_encode_bool(self != null, serializer);
+    if (self != null) {
+      sse_encode_box_autoadd_u_32(self, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_opt_box_autoadd_u_64(BigInt? self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    sse_encode_bool(self != null, serializer);
+    if (self != null) {
+      sse_encode_box_autoadd_u_64(self, serializer);
+    }
+  }
+
   @protected
   void sse_encode_u_32(int self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -945,11 +2393,6 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     serializer.buffer.putUint8(self);
   }
 
-  @protected
-  void sse_encode_unit(void self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
   @protected
   void sse_encode_i_32(int self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
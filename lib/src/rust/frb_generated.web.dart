@@ -30,58 +30,82 @@ import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated_web.dart';
 
 @protected bool dco_decode_bool(dynamic raw);
 
+@protected int dco_decode_box_autoadd_u_32(dynamic raw);
+
+@protected BigInt dco_decode_box_autoadd_u_64(dynamic raw);
+
 @protected PlatformInt64 dco_decode_i_64(dynamic raw);
 
+@protected List<String> dco_decode_list_String(dynamic raw);
+
 @protected Uint8List dco_decode_list_prim_u_8_strict(dynamic raw);
 
 @protected String? dco_decode_opt_String(dynamic raw);
 
+@protected int? dco_decode_opt_box_autoadd_u_32(dynamic raw);
+
+@protected BigInt? dco_decode_opt_box_autoadd_u_64(dynamic raw);
+
 @protected int dco_decode_u_32(dynamic raw);
 
 @protected BigInt dco_decode_u_64(dynamic raw);
 
 @protected int dco_decode_u_8(dynamic raw);
 
-@protected void dco_decode_unit(dynamic raw);
-
 @protected String sse_decode_String(SseDeserializer deserializer);
 
 @protected bool sse_decode_bool(SseDeserializer deserializer);
 
+@protected int sse_decode_box_autoadd_u_32(SseDeserializer deserializer);
+
+@protected BigInt sse_decode_box_autoadd_u_64(SseDeserializer deserializer);
+
 @protected PlatformInt64 sse_decode_i_64(SseDeserializer deserializer);
 
+@protected List<String> sse_decode_list_String(SseDeserializer deserializer);
+
 @protected Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer);
 
 @protected String? sse_decode_opt_String(SseDeserializer deserializer);
 
+@protected int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer);
+
+@protected BigInt? sse_decode_opt_box_autoadd_u_64(SseDeserializer deserializer);
+
 @protected int sse_decode_u_32(SseDeserializer deserializer);
 
 @protected BigInt sse_decode_u_64(SseDeserializer deserializer);
 
 @protected int sse_decode_u_8(SseDeserializer deserializer);
 
-@protected void sse_decode_unit(SseDeserializer deserializer);
-
 @protected int sse_decode_i_32(SseDeserializer deserializer);
 
 @protected void sse_encode_String(String self, SseSerializer serializer);
 
 @protected void sse_encode_bool(bool self, SseSerializer serializer);
 
+@protected void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_u_64(BigInt self, SseSerializer serializer);
+
 @protected void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer);
 
+@protected void sse_encode_list_String(List<String> self, SseSerializer serializer);
+
 @protected void sse_encode_list_prim_u_8_strict(Uint8List self, SseSerializer serializer);
 
 @protected void sse_encode_opt_String(String? self, SseSerializer serializer);
 
+@protected void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_u_64(BigInt? self, SseSerializer serializer);
+
 @protected void sse_encode_u_32(int self, SseSerializer serializer);
 
 @protected void sse_encode_u_64(BigInt self, SseSerializer serializer);
 
 @protected void sse_encode_u_8(int self, SseSerializer serializer);
 
-@protected void sse_encode_unit(void self, SseSerializer serializer);
-
 @protected void sse_encode_i_32(int self, SseSerializer serializer);
                 }
                 
@@ -12,9 +12,11 @@ use chrono;
 use zecwalletlitelib::{commands, lightclient::LightClient, MainNetwork};
 use zecwalletlitelib::lightclient::lightclient_config::LightClientConfig;
 use zecwalletlitelib::grpc_connector::GrpcConnector;
-
-// Global reference to progress sender for use from zecwalletlitelib
-static mut GLOBAL_PROGRESS_SENDER: Option<broadcast::Sender<String>> = None;
+use zecwalletlitelib::lightwallet::{SendOptions, SendPhase, SendProgress, UtxoSelectionStrategy};
+use zecwalletlitelib::lightwallet::memo::memo_report;
+use zecwalletlitelib::lightwallet::seed_phrase::{seed_phrase_report, word_suggestions};
+use zecwalletlitelib::lightwallet::zip321::{Payment, PaymentRequest};
+use zecwalletlitelib::BITCOINZ_MAINNET;
 
 // Global LightClient instance (same as BitcoinZ Blue)
 lazy_static! {
@@ -50,6 +52,16 @@ pub fn wallet_exists(wallet_dir: Option<String>) -> bool {
 
 /// Initialize a new wallet and return the seed phrase
 pub fn initialize_new(server_uri: String, wallet_dir: Option<String>) -> String {
+    initialize_new_with_passphrase(server_uri, None, wallet_dir)
+}
+
+/// Initialize a new wallet whose seed is protected by a BIP39 passphrase, and return the seed phrase. The
+/// passphrase is needed, along with the seed phrase, to restore the wallet
+pub fn initialize_new_with_passphrase(
+    server_uri: String,
+    passphrase: Option<String>,
+    wallet_dir: Option<String>,
+) -> String {
     let server = LightClientConfig::<MainNetwork>::get_server_or_default(Some(server_uri));
     
     let (config, latest_block_height) = match LightClientConfig::create(MainNetwork, server, wallet_dir) {
@@ -57,7 +69,8 @@ pub fn initialize_new(server_uri: String, wallet_dir: Option<String>) -> String
         Err(e) => return format!("Error: {}", e),
     };
 
-    let lightclient = match LightClient::new(&config, latest_block_height.saturating_sub(100)) {
+    let birthday = latest_block_height.saturating_sub(100);
+    let lightclient = match LightClient::new_with_passphrase(&config, passphrase, birthday) {
         Ok(l) => l,
         Err(e) => return format!("Error: {}", e),
     };
@@ -255,6 +268,18 @@ pub fn initialize_from_phrase(
     birthday: u64, 
     overwrite: bool,
     wallet_dir: Option<String>
+) -> String {
+    initialize_from_phrase_with_passphrase(server_uri, seed_phrase, None, birthday, overwrite, wallet_dir)
+}
+
+/// Initialize from seed phrase and the BIP39 passphrase the wallet was created with
+pub fn initialize_from_phrase_with_passphrase(
+    server_uri: String,
+    seed_phrase: String,
+    passphrase: Option<String>,
+    birthday: u64,
+    overwrite: bool,
+    wallet_dir: Option<String>,
 ) -> String {
     let server = LightClientConfig::<MainNetwork>::get_server_or_default(Some(server_uri));
     
@@ -269,8 +294,9 @@ pub fn initialize_from_phrase(
         // Delete wallet file if needed
     }
 
-    let lightclient = match LightClient::new_from_phrase(
+    let lightclient = match LightClient::new_from_phrase_with_passphrase(
         seed_phrase, 
+        passphrase,
         &config, 
         birthday, 
         false
@@ -296,6 +322,53 @@ pub fn initialize_from_phrase(
     "OK".to_string()
 }
 
+/// Initialize a watch-only wallet from Sapling extended full viewing keys (btczxviews...) and t-addresses. It
+/// has no seed, so it shows the funds and history of those keys but can't spend them
+pub fn initialize_watch_only(
+    server_uri: String,
+    viewing_keys: Vec<String>,
+    taddrs: Vec<String>,
+    birthday: u64,
+    overwrite: bool,
+    wallet_dir: Option<String>,
+) -> String {
+    let server = LightClientConfig::<MainNetwork>::get_server_or_default(Some(server_uri));
+
+    let (config, _latest_block_height) = match LightClientConfig::create(MainNetwork, server, wallet_dir) {
+        Ok((c, h)) => (c, h),
+        Err(e) => return format!("Error: {}", e),
+    };
+
+    let lightclient = match LightClient::new_watch_only(&config, viewing_keys, taddrs, birthday, overwrite) {
+        Ok(l) => l,
+        Err(e) => return format!("Error: {}", e),
+    };
+
+    let _ = lightclient.init_logging();
+
+    let lc = Arc::new(lightclient);
+    LightClient::start_mempool_monitor(lc.clone());
+
+    LIGHTCLIENT.lock().unwrap().replace(Some(lc));
+
+    "OK".to_string()
+}
+
+/// Enter the BIP39 passphrase of a wallet that isn't encrypted. It isn't saved with such a wallet, and is
+/// needed before the wallet can derive new addresses
+pub async fn set_passphrase(passphrase: String) -> String {
+    let lightclient = LIGHTCLIENT.lock().unwrap().borrow().clone();
+    let lightclient = match lightclient {
+        Some(l) => l,
+        None => return r#"{"error": "Wallet not initialized"}"#.to_string(),
+    };
+
+    match lightclient.do_set_passphrase(passphrase).await {
+        Ok(j) => j.pretty(2),
+        Err(e) => serde_json::json!({ "error": e }).to_string(),
+    }
+}
+
 /// Execute a command (main wallet interface)
 pub fn execute(command: String, args: String) -> String {
     // Debug logging only in debug builds to reduce log spam
@@ -311,8 +384,13 @@ pub fn execute(command: String, args: String) -> String {
 
     let args_vec: Vec<&str> = if args.is_empty() {
         vec![]
-    } else if command == "send" && args.starts_with('[') {
-        // For send command with JSON format, pass as single argument
+    } else if ((command == "send" || command == "propose") && args.starts_with('['))
+        || ((command == "shield" || command == "schedulepayment") && args.starts_with('{'))
+    {
+        // For commands with JSON format, pass as single argument
+        vec![&args]
+    } else if command == "sendbatch" {
+        // The file path may have spaces in it
         vec![&args]
     } else {
         // For other commands, use normal whitespace splitting
@@ -356,8 +434,9 @@ pub fn get_transactions() -> String {
 
 
 
-/// Send transaction
-pub async fn send_transaction(address: String, amount: i64, memo: Option<String>) -> String {
+/// Send transaction. `utxo_strategy` picks the UTXOs to spend if transparent funds are needed: "avoid_mixing"
+/// (the default), "single_address", "minimal", "oldest_first" or "all"
+pub async fn send_transaction(address: String, amount: i64, memo: Option<String>, utxo_strategy: Option<String>) -> String {
     println!("PROGRESS STREAM: Send transaction initiated");
 
     // Emit initial progress
@@ -381,35 +460,57 @@ pub async fn send_transaction(address: String, amount: i64, memo: Option<String>
         amount as u64
     };
 
+    let options = match utxo_strategy.map(|s| s.parse::<UtxoSelectionStrategy>()).transpose() {
+        Ok(strategy) => SendOptions { utxo_strategy: strategy.unwrap_or_default(), ..Default::default() },
+        Err(e) => {
+            let _ = send_progress_update(serde_json::json!({"status": "error", "progress": 0, "total": 100, "error": e, "txid": null}).to_string());
+            return serde_json::json!({ "error": e }).to_string();
+        }
+    };
+
     // Prepare the address, amount, memo tuple for do_send
     let addrs = vec![(&*address, amount_u64, memo)];
 
-    println!("PROGRESS STREAM: Starting transaction preparation");
-    let _ = send_progress_update("{\"status\": \"sending\", \"progress\": 0, \"total\": 100, \"error\": null, \"txid\": null}".to_string());
-
-    // Small delay to show preparation message
-    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    // Forward the wallet's progress to the progress stream while the send runs. This runs as its own task,
+    // because building the transaction blocks the task that sends it. Once the send is over, whatever is left
+    // in the channel is forwarded too.
+    let mut progress_rx = lightclient.subscribe_send_progress();
+    let (done_tx, mut done_rx) = tokio::sync::oneshot::channel::<()>();
+    let forwarder = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                r = progress_rx.recv() => match r {
+                    Ok(p) => {
+                        let _ = send_progress_update(send_progress_stream_json(&p));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = &mut done_rx => {
+                    while let Ok(p) = progress_rx.try_recv() {
+                        let _ = send_progress_update(send_progress_stream_json(&p));
+                    }
+                    break;
+                }
+            }
+        }
+    });
 
-    println!("PROGRESS STREAM: Starting transaction build");
-    let _ = send_progress_update("{\"status\": \"sending\", \"progress\": 10, \"total\": 100, \"error\": null, \"txid\": null}".to_string());
+    // Call lightclient.do_send_raw() directly (already in async context)
+    let result = lightclient.do_send_raw(addrs, options).await;
+    let _ = done_tx.send(());
+    let _ = forwarder.await;
 
-    // Call lightclient.do_send() directly (already in async context)
-    match lightclient.do_send(addrs).await {
-        Ok(txid) => {
+    match result {
+        Ok((txid, raw_tx)) => {
             println!("PROGRESS STREAM: Transaction sent successfully");
-            let _ = send_progress_update("{\"status\": \"sending\", \"progress\": 90, \"total\": 100, \"error\": null, \"txid\": null}".to_string());
-
-            // Small delay to show broadcasting message
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-            let _ = send_progress_update(format!("{{\"status\": \"completed\", \"progress\": 100, \"total\": 100, \"error\": null, \"txid\": \"{}\"}}", txid));
 
-            // Transaction sent successfully
-            format!(r#"{{"txid": "{}"}}"#, txid)
+            // Transaction sent successfully. The signed transaction is returned too, hex encoded
+            let raw_tx = raw_tx.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            format!(r#"{{"txid": "{}", "raw_tx": "{}"}}"#, txid, raw_tx)
         }
         Err(e) => {
             println!("PROGRESS STREAM: Transaction send failed: {}", e);
-            let _ = send_progress_update(format!("{{\"status\": \"error\", \"progress\": 0, \"total\": 100, \"error\": \"{}\", \"txid\": null}}", e.replace("\"", "\\\"")));
 
             // Transaction send failed
             // Escape quotes in error message to prevent JSON issues
@@ -419,6 +520,302 @@ pub async fn send_transaction(address: String, amount: i64, memo: Option<String>
     }
 }
 
+// The progress stream's update for a change in the wallet's send progress. The progress is a percentage of the
+// whole send, most of which is proving the spends and outputs.
+fn send_progress_stream_json(p: &SendProgress) -> String {
+    let (status, percent) = match p.phase {
+        SendPhase::Idle | SendPhase::Selecting => ("sending", 5),
+        SendPhase::Proving => ("sending", 10 + 75 * p.progress / p.total.max(1)),
+        SendPhase::Signing => ("sending", 90),
+        SendPhase::Broadcasting => ("sending", 95),
+        SendPhase::Done => ("completed", 100),
+        SendPhase::Failed => ("error", 0),
+    };
+
+    serde_json::json!({
+        "status": status,
+        "phase": p.phase.to_string(),
+        "progress": percent,
+        "total": 100,
+        "error": p.last_error,
+        "txid": p.last_txid,
+    })
+    .to_string()
+}
+
+/// Send everything that can be spent to a single address. The fee is deducted from the amount and no change
+/// is created, so this empties the wallet
+pub async fn send_max_transaction(address: String, memo: Option<String>) -> String {
+    let mut to = serde_json::json!({ "address": address, "amount": "max" });
+    if let Some(memo) = memo {
+        to["memo"] = serde_json::Value::String(memo);
+    }
+
+    execute("send".to_string(), serde_json::json!([to]).to_string())
+}
+
+/// Shield the funds of each of the given t-addresses (or of every t-address, if none are given) in a
+/// separate transaction, so they aren't linked together. Without a destination, the change policy picks
+/// the z-address
+pub async fn shield_from_addresses(from_addresses: Vec<String>, to_address: Option<String>) -> String {
+    let mut args = serde_json::json!({});
+    if !from_addresses.is_empty() {
+        args["from"] = serde_json::json!(from_addresses);
+    }
+    if let Some(to) = to_address {
+        args["to"] = serde_json::Value::String(to);
+    }
+
+    execute("shield".to_string(), args.to_string())
+}
+
+/// Shield transparent funds automatically after each sync, once UTXOs with at least `min_confirmations`
+/// confirmations add up to `threshold` zats. Without an address, the change policy picks the z-address
+#[frb(sync)]
+pub fn enable_auto_shield(threshold: u64, min_confirmations: u32, address: Option<String>) -> String {
+    let mut args = format!("{} {}", threshold, min_confirmations);
+    if let Some(address) = address {
+        args.push_str(&format!(" {}", address));
+    }
+
+    execute("autoshield".to_string(), args)
+}
+
+/// Stop shielding transparent funds automatically
+#[frb(sync)]
+pub fn disable_auto_shield() -> String {
+    execute("autoshield".to_string(), "off".to_string())
+}
+
+/// Get the auto-shield policy
+#[frb(sync)]
+pub fn get_auto_shield() -> String {
+    execute("autoshield".to_string(), "".to_string())
+}
+
+/// Show how the smallest notes would be merged into fewer notes, and the fees that would cost
+pub async fn preview_consolidation(max_notes: Option<u32>, dust_threshold: Option<u64>) -> String {
+    execute("consolidate".to_string(), consolidate_args("preview", max_notes, dust_threshold))
+}
+
+/// Merge the smallest notes into fewer notes sent to this wallet
+pub async fn consolidate_notes(max_notes: Option<u32>, dust_threshold: Option<u64>) -> String {
+    execute("consolidate".to_string(), consolidate_args("run", max_notes, dust_threshold))
+}
+
+fn consolidate_args(mode: &str, max_notes: Option<u32>, dust_threshold: Option<u64>) -> String {
+    match (max_notes, dust_threshold) {
+        (None, None) => mode.to_string(),
+        (Some(n), None) => format!("{} {}", mode, n),
+        // The dust threshold comes after max_notes, and 0 notes means all of them
+        (n, Some(t)) => format!("{} {} {}", mode, n.unwrap_or(0), t),
+    }
+}
+
+/// Check a memo as it is being typed: whether it can be sent (to `address`, if given), whether it is text or
+/// "0x" hex, and how many of its 512 bytes are used and left
+#[frb(sync)]
+pub fn validate_memo(memo: String, address: Option<String>) -> String {
+    memo_report(&BITCOINZ_MAINNET, address.as_deref(), &memo).dump()
+}
+
+/// Check a seed phrase, partial or complete, as it is entered: every word that isn't in the BIP39 word list, with
+/// its position (from 1), completions and closest matches, and whether the phrase has the right length and
+/// checksum. This doesn't need a wallet
+#[frb(sync)]
+pub fn validate_seed_phrase(seed_phrase: String) -> String {
+    seed_phrase_report(&seed_phrase).dump()
+}
+
+/// Suggest seed phrase words for `word`: the words it is the start of, and the closest words if it is mistyped
+#[frb(sync)]
+pub fn seed_word_suggestions(word: String) -> String {
+    word_suggestions(&word).dump()
+}
+
+/// Parse a `bitcoinz:` payment URI (ZIP-321). Returns the payments, with amounts in zats. This doesn't need a
+/// wallet, so it can be used right after scanning a QR code
+#[frb(sync)]
+pub fn parse_payment_uri(uri: String) -> String {
+    match PaymentRequest::from_uri(&BITCOINZ_MAINNET, uri.trim()) {
+        Ok(request) => request.to_json().dump(),
+        Err(e) => serde_json::json!({ "error": e }).to_string(),
+    }
+}
+
+/// Create a `bitcoinz:` payment URI from a JSON array of {"address", "amount" (in zats), "memo", "label",
+/// "message"}, where everything but the address is optional
+#[frb(sync)]
+pub fn create_payment_uri(payments_json: String) -> String {
+    let payments: Vec<serde_json::Value> = match serde_json::from_str(&payments_json) {
+        Ok(p) => p,
+        Err(e) => return serde_json::json!({ "error": format!("Couldn't understand payments: {}", e) }).to_string(),
+    };
+
+    let text = |p: &serde_json::Value, key: &str| p[key].as_str().map(|s| s.to_string());
+    let request = PaymentRequest {
+        payments: payments
+            .iter()
+            .map(|p| Payment {
+                address: text(p, "address").unwrap_or_default(),
+                amount: p["amount"].as_u64(),
+                memo: text(p, "memo").map(|m| m.into_bytes()),
+                label: text(p, "label"),
+                message: text(p, "message"),
+            })
+            .collect(),
+    };
+
+    match request.to_uri(&BITCOINZ_MAINNET) {
+        Ok(uri) => serde_json::json!({ "uri": uri }).to_string(),
+        Err(e) => serde_json::json!({ "error": e }).to_string(),
+    }
+}
+
+/// Pay all the recipients in a CSV or JSON file of (address, amount, memo). Nothing is sent if any row is
+/// invalid. Returns the result of every row
+pub async fn send_batch(file_path: String) -> String {
+    execute("sendbatch".to_string(), file_path)
+}
+
+/// Schedule a payment to `recipients_json` (a JSON array of {"address", "amount", "memo"}), first due at
+/// `due_height` or at the unix time `due_time`, and then every `interval` blocks or seconds. Without an
+/// interval it is only paid once. Returns the id of the scheduled payment
+#[frb(sync)]
+pub fn schedule_payment(
+    recipients_json: String,
+    due_height: Option<u32>,
+    due_time: Option<u64>,
+    interval: Option<u64>,
+) -> String {
+    let recipients: serde_json::Value = match serde_json::from_str(&recipients_json) {
+        Ok(r) => r,
+        Err(e) => return serde_json::json!({ "error": format!("Couldn't understand recipients: {}", e) }).to_string(),
+    };
+
+    let mut args = serde_json::json!({ "recipients": recipients, "interval": interval.unwrap_or(0) });
+    if let Some(height) = due_height {
+        args["height"] = serde_json::json!(height);
+    }
+    if let Some(time) = due_time {
+        args["time"] = serde_json::json!(time);
+    }
+
+    execute("schedulepayment".to_string(), args.to_string())
+}
+
+/// List all the scheduled payments, with the txids that paid each of them
+#[frb(sync)]
+pub fn list_scheduled_payments() -> String {
+    execute("scheduledpayments".to_string(), "".to_string())
+}
+
+/// Remove a scheduled payment
+#[frb(sync)]
+pub fn cancel_scheduled_payment(id: u64) -> String {
+    execute("cancelpayment".to_string(), id.to_string())
+}
+
+/// List the scheduled payments that are due now
+#[frb(sync)]
+pub fn get_due_payments() -> String {
+    execute("duepayments".to_string(), "".to_string())
+}
+
+/// Pay every scheduled payment that is due. The wallet has to be unlocked
+pub async fn pay_due_payments() -> String {
+    execute("paydue".to_string(), "".to_string())
+}
+
+/// Create an unsigned transaction (works in watch-only wallets). Returns the proposal hex, to be signed by
+/// the wallet that has the spending keys
+pub async fn create_unsigned_transaction(address: String, amount: u64, memo: Option<String>) -> String {
+    let mut to = serde_json::json!({ "address": address, "amount": amount });
+    if let Some(memo) = memo {
+        to["memo"] = serde_json::Value::String(memo);
+    }
+
+    execute("propose".to_string(), serde_json::json!([to]).to_string())
+}
+
+/// Sign an unsigned transaction created by `create_unsigned_transaction`, without broadcasting it
+pub async fn sign_unsigned_transaction(proposal_hex: String) -> String {
+    execute("signproposal".to_string(), proposal_hex)
+}
+
+/// Broadcast a transaction that was signed by another (offline) wallet, or any other raw transaction hex
+pub async fn broadcast_signed_transaction(tx_hex: String) -> String {
+    execute("broadcastsigned".to_string(), tx_hex)
+}
+
+/// Load the Sapling prover ahead of the next send, so it can start proving right away
+pub async fn preload_prover() -> String {
+    execute("prover".to_string(), "load".to_string())
+}
+
+/// Free the memory used by the Sapling prover, for example when the app goes to the background. It is loaded
+/// again by the next send
+#[frb(sync)]
+pub fn release_prover() -> String {
+    execute("prover".to_string(), "release".to_string())
+}
+
+/// Decode a raw transaction hex: its inputs and outputs, expiry, value balance and which parts are this wallet's
+#[frb(sync)]
+pub fn decode_raw_transaction(tx_hex: String) -> String {
+    execute("decoderawtx".to_string(), tx_hex)
+}
+
+/// Set the extra servers every transaction is also broadcast to. An empty list only uses the wallet's server
+#[frb(sync)]
+pub fn set_broadcast_servers(servers: Vec<String>) -> String {
+    if servers.is_empty() {
+        execute("broadcastservers".to_string(), "clear".to_string())
+    } else {
+        execute("broadcastservers".to_string(), servers.join(" "))
+    }
+}
+
+/// Get the wallet's server and the extra broadcast servers
+#[frb(sync)]
+pub fn get_broadcast_servers() -> String {
+    execute("broadcastservers".to_string(), "".to_string())
+}
+
+/// Wait (up to `timeout_secs`, 30 by default) until every broadcast server has the transaction, and report
+/// what each of them said about it
+pub async fn check_propagation(txid: String, timeout_secs: Option<u64>) -> String {
+    match timeout_secs {
+        Some(timeout) => execute("checkpropagation".to_string(), format!("{} {}", txid, timeout)),
+        None => execute("checkpropagation".to_string(), txid),
+    }
+}
+
+/// Set where the change of sends goes: "same_as_input", "fresh" or one of the wallet's z-addresses
+#[frb(sync)]
+pub fn set_change_policy(policy: String) -> String {
+    execute("setoption".to_string(), format!("change_policy={}", policy))
+}
+
+/// Get the current change policy
+#[frb(sync)]
+pub fn get_change_policy() -> String {
+    execute("getoption".to_string(), "change_policy".to_string())
+}
+
+/// Set how much sends may reveal on chain: "full_privacy", "allow_revealed_amounts",
+/// "allow_revealed_recipients" or "allow_linking_transparent"
+#[frb(sync)]
+pub fn set_privacy_policy(policy: String) -> String {
+    execute("setoption".to_string(), format!("privacy_policy={}", policy))
+}
+
+/// Get the current privacy policy
+#[frb(sync)]
+pub fn get_privacy_policy() -> String {
+    execute("getoption".to_string(), "privacy_policy".to_string())
+}
+
 /// Get addresses
 #[frb(sync)]
 pub fn get_addresses() -> String {
@@ -431,6 +828,113 @@ pub fn new_address(address_type: String) -> String {
     execute("new".to_string(), address_type)
 }
 
+/// Generate a diversified address of an existing z-address's key (or of the first key). Unlike a new z-address,
+/// it doesn't add a key to scan for, so it's free to hand out one per payer
+#[frb(sync)]
+pub fn new_diversified_address(zaddr: Option<String>) -> String {
+    match zaddr {
+        Some(zaddr) => execute("new".to_string(), format!("zd {}", zaddr)),
+        None => execute("new".to_string(), "zd".to_string()),
+    }
+}
+
+/// List the accounts of the wallet, with their addresses
+#[frb(sync)]
+pub fn get_accounts() -> String {
+    execute("accounts".to_string(), "".to_string())
+}
+
+/// Create a new account, with its own z- and t-address
+#[frb(sync)]
+pub fn create_account(name: String) -> String {
+    execute("accounts".to_string(), format!("new {}", name))
+}
+
+#[frb(sync)]
+pub fn rename_account(account: u32, name: String) -> String {
+    execute("accounts".to_string(), format!("rename {} {}", account, name))
+}
+
+/// Create a new address ("z" or "t") in an account
+#[frb(sync)]
+pub fn new_account_address(account: u32, address_type: String) -> String {
+    execute("accounts".to_string(), format!("address {} {}", account, address_type))
+}
+
+#[frb(sync)]
+pub fn get_account_balance(account: u32) -> String {
+    execute("accounts".to_string(), format!("balance {}", account))
+}
+
+/// The transactions received at an account's addresses, and the sends that spent its funds
+#[frb(sync)]
+pub fn get_account_transactions(account: u32) -> String {
+    execute("accounts".to_string(), format!("list {}", account))
+}
+
+/// Send only the funds of an account. The change stays in the account
+pub async fn send_from_account(account: u32, address: String, amount: u64, memo: Option<String>) -> String {
+    let options = SendOptions {
+        account: Some(account),
+        ..Default::default()
+    };
+    send_with_options(address, amount, memo, options).await
+}
+
+/// List the notes and UTXOs that can be spent right now, for coin control. Each has an "input_id" that can be
+/// passed to `send_from_inputs`, `freeze_inputs` and `unfreeze_inputs`
+#[frb(sync)]
+pub fn get_spendable_inputs() -> String {
+    execute("coins".to_string(), "".to_string())
+}
+
+/// Send, spending exactly the notes and UTXOs with these input ids (from `get_spendable_inputs`)
+pub async fn send_from_inputs(input_ids: Vec<String>, address: String, amount: u64, memo: Option<String>) -> String {
+    let options = SendOptions {
+        inputs: Some(input_ids),
+        ..Default::default()
+    };
+    send_with_options(address, amount, memo, options).await
+}
+
+/// Send, only spending the funds that were received at `from_address`, to keep funds apart for accounting
+pub async fn send_from_address(from_address: String, address: String, amount: u64, memo: Option<String>) -> String {
+    let options = SendOptions {
+        from_address: Some(from_address),
+        ..Default::default()
+    };
+    send_with_options(address, amount, memo, options).await
+}
+
+// The send options can't be passed through `execute`, so these sends call the lightclient directly
+async fn send_with_options(address: String, amount: u64, memo: Option<String>, options: SendOptions) -> String {
+    let lightclient = LIGHTCLIENT.lock().unwrap().borrow().clone();
+    let lightclient = match lightclient {
+        Some(l) => l,
+        None => return r#"{"error": "Wallet not initialized"}"#.to_string(),
+    };
+
+    match lightclient.do_send_raw(vec![(&*address, amount, memo)], options).await {
+        Ok((txid, raw_tx)) => {
+            let raw_tx = raw_tx.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            serde_json::json!({ "txid": txid, "raw_tx": raw_tx }).to_string()
+        }
+        Err(e) => serde_json::json!({ "error": e }).to_string(),
+    }
+}
+
+/// Freeze notes / UTXOs (by input id) so they are never spent
+#[frb(sync)]
+pub fn freeze_inputs(input_ids: Vec<String>) -> String {
+    execute("freeze".to_string(), input_ids.join(" "))
+}
+
+/// Unfreeze previously frozen notes / UTXOs
+#[frb(sync)]
+pub fn unfreeze_inputs(input_ids: Vec<String>) -> String {
+    execute("unfreeze".to_string(), input_ids.join(" "))
+}
+
 /// Get wallet height
 #[frb(sync)]
 pub fn get_height() -> u32 {
@@ -514,6 +1018,13 @@ pub fn get_send_progress() -> String {
     execute("sendprogress".to_string(), "".to_string())
 }
 
+/// Get the sent transactions that aren't mined yet, with their expiry height and how many times they were
+/// rebroadcast, and the ones that expired without being mined
+#[frb(sync)]
+pub fn get_pending_sends() -> String {
+    execute("pendingsends".to_string(), "".to_string())
+}
+
 /// Initialize progress stream
 pub fn init_progress_stream() -> String {
     let (tx, _rx) = broadcast::channel(100);
@@ -596,45 +1107,3 @@ pub fn send_progress_update(progress_data: String) -> String {
         "Error: Failed to lock sender".to_string()
     }
 }
-
-/// Export C-compatible function for zecwalletlitelib to call
-/// This allows the fallback progress system to emit stream events
-#[no_mangle]
-pub extern "C" fn emit_progress_update(progress: u32, total: u32) {
-    // Clamp progress to not exceed total to prevent "3 of 2" issues
-    let clamped_progress = progress.min(total);
-
-    // Convert to percentage for consistent display
-    // Map note processing (which happens 50-90% of transaction) to that range
-    let progress_percent = if total > 0 {
-        let base_percent = (clamped_progress as f64 / total as f64) * 100.0;
-
-        // Map 0-100% note progress to 50-90% transaction progress
-        // This gives note processing 40% of the total progress bar
-        let mapped_percent = 50.0 + (base_percent * 0.4);
-        mapped_percent.min(90.0) as u32
-    } else {
-        50 // Start of note processing
-    };
-
-    let progress_json = format!(
-        r#"{{"status": "sending", "progress": {}, "total": 100, "error": null, "txid": null}}"#,
-        progress_percent
-    );
-    println!("PROGRESS STREAM: C bridge emitting: {}% (from note {}/{})", progress_percent, clamped_progress, total);
-    let _ = send_progress_update(progress_json);
-}
-
-/// Initialization function to set up global progress sender for C bridge
-#[no_mangle]
-pub extern "C" fn init_progress_bridge() {
-    unsafe {
-        if let Ok(sender_guard) = PROGRESS_SENDER.lock() {
-            if let Some(sender) = sender_guard.as_ref() {
-                // Create a new sender for the global static
-                GLOBAL_PROGRESS_SENDER = Some(sender.clone());
-                println!("PROGRESS STREAM: Global progress sender initialized");
-            }
-        }
-    }
-}
\ No newline at end of file
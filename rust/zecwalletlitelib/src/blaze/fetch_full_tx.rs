@@ -107,9 +107,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
                         last_progress.store(progress, Ordering::SeqCst);
                     }
 
-                    Self::scan_full_tx(config, tx, height, false, block_time, keys, wallet_txns, None).await;
-
-                    Ok::<_, String>(())
+                    Self::scan_full_tx(config, tx, height, false, block_time, keys, wallet_txns, None).await
                 }));
             }
 
@@ -132,17 +130,20 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
         let h2: JoinHandle<Result<(), String>> = tokio::spawn(async move {
             let bsync_data = bsync_data.clone();
 
+            // Keep receiving after an error, so the senders don't fail, and report the first error at the end
+            let mut result = Ok(());
             while let Some((tx, height)) = tx_rx.recv().await {
                 let config = config.clone();
                 let keys = keys.clone();
                 let wallet_txns = wallet_txns.clone();
 
                 let block_time = bsync_data.read().await.block_data.get_block_timestamp(&height).await;
-                Self::scan_full_tx(config, tx, height, false, block_time, keys, wallet_txns, None).await;
+                let r = Self::scan_full_tx(config, tx, height, false, block_time, keys, wallet_txns, None).await;
+                result = result.and(r);
             }
 
             //info!("Finished full_tx scanning all txns");
-            Ok(())
+            result
         });
 
         let h = tokio::spawn(async move {
@@ -173,11 +174,14 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
         keys: Arc<RwLock<Keys<P>>>,
         wallet_txns: Arc<RwLock<WalletTxns>>,
         price: Option<f64>,
-    ) {
+    ) -> Result<(), String> {
         // Collect our t-addresses for easy checking
         let taddrs = keys.read().await.get_all_taddrs();
         let taddrs_set: HashSet<_> = taddrs.iter().map(|t| t.clone()).collect();
 
+        // HD addresses that couldn't be added are reported once the rest of the Tx has been scanned
+        let mut hd_result = Ok(());
+
         // Step 1: Scan all transparent outputs to see if we recieved any money
         if let Some(t_bundle) = tx.transparent_bundle() {
             for (n, vout) in t_bundle.vout.iter().enumerate() {
@@ -197,7 +201,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
                             );
 
                             // Ensure that we add any new HD addresses
-                            keys.write().await.ensure_hd_taddresses(&output_taddr);
+                            if let Err(e) = keys.write().await.ensure_hd_taddresses(&output_taddr) {
+                                hd_result = Err(format!("Couldn't add the next HD t-addresses: {}", e));
+                            }
                         }
                     }
                     _ => {}
@@ -379,7 +385,13 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
         // Step 4b: Scan the orchard part of the bundle to see if there are any memos. We'll also scan the orchard outputs
         // with our OutgoingViewingKey, to see if we can decrypt outgoing metadata
         let o_ivks = keys.read().await.get_all_orchard_ivks();
-        let o_ovk = keys.read().await.okeys[0].fvk().to_ovk(orchard::keys::Scope::External);
+        // Wallets without orchard keys, like watch-only ones, have no ovk to recover outgoing orchard notes with
+        let o_ovk = keys
+            .read()
+            .await
+            .okeys
+            .get(0)
+            .map(|ok| ok.fvk().to_ovk(orchard::keys::Scope::External));
         if let Some(o_bundle) = tx.orchard_bundle() {
             // let orchard_actions = o_bundle
             //     .actions()
@@ -405,9 +417,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
                 }
 
                 // Also attempt output recovery using ovk to see if this wan an outgoing tx.
-                if let Some((note, ua_address, memo_bytes)) =
-                    try_output_recovery_with_ovk(&domain, &o_ovk, oa, oa.cv_net(), &oa.encrypted_note().out_ciphertext)
-                {
+                if let Some((note, ua_address, memo_bytes)) = o_ovk.as_ref().and_then(|o_ovk| {
+                    try_output_recovery_with_ovk(&domain, o_ovk, oa, oa.cv_net(), &oa.encrypted_note().out_ciphertext)
+                }) {
                     is_outgoing_tx = true;
                     let address = LightWallet::<P>::orchard_ua_address(&config, &ua_address);
                     let memo = Memo::from_bytes(&memo_bytes).unwrap_or(Memo::default());
@@ -472,5 +484,6 @@ impl<P: consensus::Parameters + Send + Sync + 'static> FetchFullTxns<P> {
         }
 
         //info!("Finished Fetching full tx {}", tx.txid());
+        hd_result
     }
 }
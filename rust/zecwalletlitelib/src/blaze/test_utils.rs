@@ -200,6 +200,11 @@ impl FakeTransaction {
         note
     }
 
+    // Add a new tx into the block, paying the given address (which can be any diversified address) the amount.
+    pub fn add_tx_paying_address(&mut self, to: &PaymentAddress, value: u64) -> Note {
+        self.add_sapling_output(value, None, to)
+    }
+
     // Add a t output which will be paid to the given PubKey
     pub fn add_t_output(&mut self, pk: &PublicKey, taddr: String, value: u64) {
         let mut hash160 = ripemd160::Ripemd160::new();
@@ -468,6 +473,15 @@ impl FakeCompactBlockList {
         (tx, height, note)
     }
 
+    pub fn add_tx_paying_address(&mut self, to: &PaymentAddress, value: u64) -> (Transaction, u64, Note) {
+        let mut ftx = FakeTransaction::new();
+        let note = ftx.add_tx_paying_address(to, value);
+
+        let (tx, height) = self.add_ftx(ftx);
+
+        (tx, height, note)
+    }
+
     pub fn add_empty_block(&mut self) -> &'_ mut FakeCompactBlock {
         let newblk = FakeCompactBlock::new(self.next_height, self.prev_hash);
         self.next_height += 1;
@@ -518,6 +532,7 @@ impl FakeCompactBlockList {
     }
 }
 
+#[derive(Clone)]
 pub struct FakeTxProver {}
 
 impl TxProver for FakeTxProver {
@@ -237,6 +237,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> TrialDecryptions<P> {
                                 let keys = keys.read().await;
                                 let extfvk = keys.zkeys[ivk_num].extfvk();
                                 let have_spending_key = keys.have_sapling_spending_key(extfvk);
+                                let watch_only = keys.is_watch_only();
                                 let uri = bsync_data.read().await.uri().clone();
 
                                 // Get the witness for the note
@@ -259,6 +260,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> TrialDecryptions<P> {
                                     to,
                                     &extfvk,
                                     have_spending_key,
+                                    watch_only,
                                     witness,
                                 );
 
@@ -1,5 +1,10 @@
 use crate::lightwallet::keys::Keys;
-use crate::lightwallet::MemoDownloadOption;
+use crate::lightwallet::schedule::{DueAt, ScheduledRecipient};
+use crate::lightwallet::seed_phrase;
+use crate::lightwallet::zip321::PaymentRequest;
+use crate::lightwallet::{
+    AutoShieldPolicy, ChangePolicy, MemoDownloadOption, PrivacyPolicy, SendOptions, UtxoSelectionStrategy,
+};
 use crate::{lightclient::LightClient, lightwallet::utils};
 use json::object;
 use lazy_static::lazy_static;
@@ -563,10 +568,17 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ShieldComm
         h.push("Shield all your transparent funds");
         h.push("Usage:");
         h.push("shield [optional address]");
+        h.push("OR");
+        h.push("shield '{'from': [<t-address>, ...], 'to': <optional z-address>}'");
         h.push("");
         h.push("NOTE: The fee required to send this transaction (currently ZEC 0.0001) is additionally deducted from your balance.");
+        h.push("The funds of each t-address in 'from' (or of every t-address, if 'from' is left out) are shielded");
+        h.push("in a separate transaction, so the t-addresses aren't linked together. Each of these transactions");
+        h.push("pays a fee. The result of every t-address is shown, including the ones with nothing to shield.");
+        h.push("Without a destination, the change policy picks the z-address.");
         h.push("Example:");
         h.push("shield");
+        h.push("shield '{\"from\": [\"t1NoS6ZgaUTpmjkge2cVpXGcySasdYDrXqh\"]}'");
         h.push("");
 
         h.join("\n")
@@ -576,6 +588,44 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ShieldComm
         "Shield your transparent ZEC into a sapling address".to_string()
     }
     fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() > 1 {
+            return Command::<P>::help(self);
+        }
+
+        // Shielding from some t-addresses, one at a time
+        if args.len() == 1 && args[0].trim_start().starts_with("{") {
+            let json_args = match json::parse(args[0]) {
+                Ok(j) => j,
+                Err(e) => return format!("Couldn't understand JSON: {}\n{}", e, Command::<P>::help(self)),
+            };
+
+            let from = if json_args.has_key("from") {
+                if !json_args["from"].is_array() {
+                    return format!(
+                        "'from' needs to be an array of t-addresses\n{}",
+                        Command::<P>::help(self)
+                    );
+                }
+                Some(
+                    json_args["from"]
+                        .members()
+                        .filter_map(|a| a.as_str().map(|s| s.to_string()))
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                None
+            };
+            let to = json_args["to"].as_str().map(|s| s.to_string());
+
+            return RT.block_on(async move {
+                match lightclient.do_shield_from(from, to).await {
+                    Ok(j) => j,
+                    Err(e) => object! { "error" => e },
+                }
+                .pretty(2)
+            });
+        }
+
         // Parse the address or amount
         let address = if args.len() > 0 {
             Some(args[0].to_string())
@@ -584,12 +634,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ShieldComm
         };
         RT.block_on(async move {
             match lightclient.do_shield(address).await {
-                Ok(txid) => {
-                    object! { "txid" => txid }
-                }
-                Err(e) => {
-                    object! { "error" => e }
-                }
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
             }
             .pretty(2)
         })
@@ -670,162 +716,1118 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for EncryptMes
     }
 }
 
-struct DecryptMessageCommand {}
+struct DecryptMessageCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for DecryptMessageCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Attempt to decrypt a message with all the view keys in the wallet.");
+        h.push("Usage:");
+        h.push("decryptmessage \"encrypted_message_base64\"");
+        h.push("");
+        h.push("Example:");
+        h.push("decryptmessage RW5jb2RlIGFyYml0cmFyeSBvY3RldHMgYXMgYmFzZTY0LiBSZXR1cm5zIGEgU3RyaW5nLg==");
+        h.push("");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Attempt to decrypt a message with all the view keys in the wallet.".to_string()
+    }
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move { lightclient.do_decrypt_message(args[0].to_string()).await.pretty(2) })
+    }
+}
+
+struct SendCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SendCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Send ZEC to a given address(es)");
+        h.push("Usage:");
+        h.push("send <address> <amount in zatoshis || \"entire-verified-zbalance\" || \"max\"> \"optional_memo\" [optional utxo_strategy || options]");
+        h.push("OR");
+        h.push("send '[{'address': <address>, 'amount': <amount in zatoshis>, 'memo': <optional memo>}, ...]' [optional utxo_strategy || options]");
+        h.push("OR");
+        h.push("send <bitcoinz: payment URI>");
+        h.push("");
+        h.push("NOTE: The fee required to send this transaction (currently ZEC 0.0001) is additionally deducted from your balance.");
+        h.push("The options are a JSON object with the optional keys:");
+        h.push("  'utxo_strategy': <strategy>   - See below");
+        h.push("  'inputs': [<input_id>, ...]   - Spend exactly these notes and UTXOs. Use the 'coins' command to list them");
+        h.push("  'from': <address>             - Only spend funds received at this address");
+        h.push("  'account': <account id>       - Only spend the funds of this account, and keep the change in it. See 'accounts'");
+        h.push("  'send_max': true              - Same as an amount of \"max\"");
+        h.push("An amount of \"max\" sends everything that can be spent (after the fee) to a single address, with no change.");
+        h.push("Combine it with 'inputs' or 'from' to sweep only those funds.");
+        h.push("Returns the txid, and the signed transaction hex as 'raw_tx' (see 'decoderawtx').");
+        h.push("Shielded notes are always spent first. If transparent funds are needed, the utxo_strategy decides which UTXOs are spent:");
+        h.push("  avoid_mixing (default) - Spend from a single t-address if possible, only mixing addresses if needed");
+        h.push("  single_address         - Only spend from a single t-address");
+        h.push("  minimal                - Spend the fewest UTXOs possible");
+        h.push("  oldest_first           - Spend the oldest UTXOs first");
+        h.push("  all                    - Spend all UTXOs");
+        h.push("Example:");
+        h.push("send ztestsapling1x65nq4dgp0qfywgxcwk9n0fvm4fysmapgr2q00p85ju252h6l7mmxu2jg9cqqhtvzd69jwhgv8d 200000 \"Hello from the command line\"");
+        h.push("send t1... 200000 \"\" minimal");
+        h.push("send '[{\"address\": \"ztestsapling1x65nq4dgp0qfywgxcwk9n0fvm4fysmapgr2q00p85ju252h6l7mmxu2jg9cqqhtvzd69jwhgv8d\", \"amount\": 200000}]' single_address");
+        h.push("send '[{\"address\": \"ztestsapling1x65nq4dgp0qfywgxcwk9n0fvm4fysmapgr2q00p85ju252h6l7mmxu2jg9cqqhtvzd69jwhgv8d\", \"amount\": 200000}]' '{\"from\": \"t1...\"}'");
+        h.push("");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Send ZEC to the given address".to_string()
+    }
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        // Parse the args. There are two argument types.
+        // 1 - A set of 2(+2 optional) arguments for a single address send representing address, value, memo?, and
+        //     the UTXO selection strategy or a JSON object with the send options. An empty memo is no memo
+        // 2 - A single argument in the form of a JSON string that is "[{address: address, value: value, memo: memo},...]",
+        //     optionally followed by the UTXO selection strategy or a JSON object with the send options
+        // 3 - A single ZIP-321 payment URI, every payment of which needs an amount
+        if args.len() < 1 || args.len() > 4 {
+            return Command::<P>::help(self);
+        }
+
+        let is_json = args[0].trim_start().starts_with("[");
+        let options_arg = match (is_json, args.len()) {
+            (true, 2) => Some(args[1]),
+            (false, 4) => Some(args[3]),
+            _ => None,
+        };
+        let mut options = match options_arg {
+            Some(arg) => match parse_send_options(arg) {
+                Ok(o) => o,
+                Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+            },
+            None => SendOptions::default(),
+        };
+
+        RT.block_on(async move {
+            // Check for a JSON argument
+            let send_args = if is_json {
+                if args.len() > 2 {
+                    return Command::<P>::help(self);
+                }
+
+                let arg_list = args[0];
+
+                let json_args = match json::parse(&arg_list) {
+                    Ok(j) => j,
+                    Err(e) => {
+                        let es = format!("Couldn't understand JSON: {}", e);
+                        return format!("{}\n{}", es, Command::<P>::help(self));
+                    }
+                };
+
+                if !json_args.is_array() {
+                    return format!("Couldn't parse argument as array\n{}", Command::<P>::help(self));
+                }
+
+                let fee = u64::from(DEFAULT_FEE);
+                let all_zbalance = lightclient.wallet.verified_zbalance(None).await.checked_sub(fee);
+
+                let maybe_send_args = json_args
+                    .members()
+                    .map(|j| {
+                        if !j.has_key("address") || !j.has_key("amount") {
+                            Err(format!("Need 'address' and 'amount'\n"))
+                        } else {
+                            let amount = match j["amount"].as_str() {
+                                Some("entire-verified-zbalance") => all_zbalance,
+                                // The actual amount is calculated when the transaction is built
+                                Some("max") => Some(0),
+                                _ => Some(j["amount"].as_u64().unwrap()),
+                            };
+
+                            match amount {
+                                Some(amt) => Ok((
+                                    j["address"].as_str().unwrap().to_string().clone(),
+                                    amt,
+                                    j["memo"].as_str().map(|s| s.to_string().clone()),
+                                )),
+                                None => Err(format!("Not enough in wallet to pay transaction fee of {}", fee)),
+                            }
+                        }
+                    })
+                    .collect::<Result<Vec<(String, u64, Option<String>)>, String>>();
+
+                if json_args.members().any(|j| j["amount"].as_str() == Some("max")) {
+                    options.send_max = true;
+                }
+
+                match maybe_send_args {
+                    Ok(a) => a.clone(),
+                    Err(s) => {
+                        return format!("Error: {}\n{}", s, Command::<P>::help(self));
+                    }
+                }
+            } else if args.len() == 1 && PaymentRequest::is_payment_uri(args[0]) {
+                let request = match PaymentRequest::from_uri(&lightclient.config.get_params(), args[0]) {
+                    Ok(r) => r,
+                    Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+                };
+
+                let maybe_send_args = request
+                    .payments
+                    .iter()
+                    .map(|p| match p.amount {
+                        Some(amount) => Ok((p.address.clone(), amount, p.memo_string())),
+                        None => Err(format!("The payment URI has no amount for {}", p.address)),
+                    })
+                    .collect::<Result<Vec<(String, u64, Option<String>)>, String>>();
+
+                match maybe_send_args {
+                    Ok(a) => a,
+                    Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+                }
+            } else if args.len() >= 2 {
+                let address = args[0].to_string();
+
+                // Make sure we can parse the amount
+                let value = match args[1].parse::<u64>() {
+                    Ok(amt) => amt,
+                    Err(e) => {
+                        if args[1] == "max" {
+                            options.send_max = true;
+                            0
+                        } else if args[1] == "entire-verified-zbalance" {
+                            let fee = u64::from(DEFAULT_FEE);
+                            match lightclient.wallet.verified_zbalance(None).await.checked_sub(fee) {
+                                Some(amt) => amt,
+                                None => return format!("Not enough in wallet to pay transaction fee of {}", fee),
+                            }
+                        } else {
+                            return format!("Couldn't parse amount: {}", e);
+                        }
+                    }
+                };
+
+                let memo = if args.len() >= 3 && !args[2].is_empty() {
+                    Some(args[2].to_string())
+                } else {
+                    None
+                };
+
+                // Memo has to be None if not sending to a shileded address
+                if memo.is_some() && !Keys::is_shielded_address(&address, &lightclient.config) {
+                    return format!("Can't send a memo to the non-shielded address {}", address);
+                }
+
+                vec![(args[0].to_string(), value, memo)]
+            } else {
+                return Command::<P>::help(self);
+            };
+
+            // Convert to the right format. String -> &str.
+            let tos = send_args
+                .iter()
+                .map(|(a, v, m)| (a.as_str(), *v, m.clone()))
+                .collect::<Vec<_>>();
+            match lightclient.do_send_raw(tos, options).await {
+                Ok((txid, raw_tx)) => {
+                    object! { "txid" => txid, "raw_tx" => hex::encode(raw_tx) }
+                }
+                Err(e) => {
+                    object! { "error" => e }
+                }
+            }
+            .pretty(2)
+        })
+    }
+}
+
+// Parse the send options, which are either just the name of the UTXO selection strategy, or a JSON object
+// like {"utxo_strategy": <strategy>, "inputs": [<input_id>, ...], "from": <address>, "account": <id>}
+fn parse_send_options(arg: &str) -> Result<SendOptions, String> {
+    if !arg.trim_start().starts_with("{") {
+        return Ok(SendOptions {
+            utxo_strategy: arg.parse::<UtxoSelectionStrategy>()?,
+            ..Default::default()
+        });
+    }
+
+    let j = json::parse(arg).map_err(|e| format!("Couldn't understand JSON: {}", e))?;
+
+    let utxo_strategy = match j["utxo_strategy"].as_str() {
+        Some(s) => s.parse::<UtxoSelectionStrategy>()?,
+        None => UtxoSelectionStrategy::default(),
+    };
+
+    let inputs = if j.has_key("inputs") {
+        if !j["inputs"].is_array() {
+            return Err("'inputs' needs to be an array of input ids".to_string());
+        }
+
+        Some(
+            j["inputs"]
+                .members()
+                .map(|i| {
+                    i.as_str()
+                        .map(|s| s.to_string())
+                        .ok_or(format!("Invalid input id {}", i))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    } else {
+        None
+    };
+
+    let account = match &j["account"] {
+        json::JsonValue::Null => None,
+        a => Some(a.as_u32().ok_or(format!("Invalid account {}", a))?),
+    };
+
+    Ok(SendOptions {
+        utxo_strategy,
+        inputs,
+        from_address: j["from"].as_str().map(|s| s.to_string()),
+        account,
+        send_max: j["send_max"].as_bool().unwrap_or(false),
+        ..Default::default()
+    })
+}
+
+struct CoinsCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for CoinsCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("List the notes and UTXOs that can be spent right now");
+        h.push("Usage:");
+        h.push("coins");
+        h.push("");
+        h.push("Each entry has an 'input_id' that can be passed in the 'inputs' option of the 'send' command,");
+        h.push("to spend exactly those notes and UTXOs");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "List the spendable notes and UTXOs, for coin control".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 0 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move { format!("{}", lightclient.do_list_spendable_inputs().await.pretty(2)) })
+    }
+}
+
+fn exec_freeze<P: consensus::Parameters + Send + Sync + 'static>(
+    args: &[&str],
+    lightclient: &LightClient<P>,
+    frozen: bool,
+) -> String {
+    let input_ids = args.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+    RT.block_on(async move {
+        match lightclient.do_freeze_inputs(input_ids, frozen).await {
+            Ok(j) => j,
+            Err(e) => object! { "error" => e },
+        }
+        .pretty(2)
+    })
+}
+
+struct FreezeCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for FreezeCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Freeze notes or UTXOs, so they are never spent");
+        h.push("Usage:");
+        h.push("freeze <input_id> [<input_id> ...]");
+        h.push("");
+        h.push("The input ids are listed by the 'notes' command. Frozen notes and UTXOs are not picked for sends");
+        h.push("or shielding, and are not counted in the spendable balance, until they are unfrozen");
+        h.push("Example:");
+        h.push("freeze 6a3f...e1:0");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Freeze notes or UTXOs so they are never spent".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() == 0 {
+            return Command::<P>::help(self);
+        }
+
+        exec_freeze(args, lightclient, true)
+    }
+}
+
+struct UnfreezeCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for UnfreezeCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Unfreeze notes or UTXOs that were previously frozen, so they can be spent again");
+        h.push("Usage:");
+        h.push("unfreeze <input_id> [<input_id> ...]");
+        h.push("");
+        h.push("The input ids are listed by the 'notes' command");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Unfreeze previously frozen notes or UTXOs".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() == 0 {
+            return Command::<P>::help(self);
+        }
+
+        exec_freeze(args, lightclient, false)
+    }
+}
+
+struct AutoShieldCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for AutoShieldCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Show or change the auto-shield policy, which shields transparent funds after every sync");
+        h.push("Usage:");
+        h.push("autoshield");
+        h.push("autoshield off");
+        h.push("autoshield <threshold> [min_confirmations] [z-address]");
+        h.push("");
+        h.push("Once the UTXOs with at least 'min_confirmations' confirmations (default 1) add up to 'threshold'");
+        h.push("zats, they are shielded to the z-address, or to the one picked by the change policy if none is given.");
+        h.push("Each t-address is shielded in its own transaction, so the addresses aren't linked together.");
+        h.push("It waits while a send is in progress or the wallet is locked, and backs off after a failure.");
+        h.push("Auto-shield transactions are marked with 'auto_shield' in the transaction list.");
+        h.push("Example:");
+        h.push("autoshield 1000000 10");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Automatically shield transparent funds after syncing".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() > 3 {
+            return Command::<P>::help(self);
+        }
+
+        let policy = if args.len() == 0 {
+            None
+        } else if args[0] == "off" {
+            if args.len() != 1 {
+                return Command::<P>::help(self);
+            }
+            Some(None)
+        } else {
+            let threshold = match args[0].parse::<u64>() {
+                Ok(t) => t,
+                Err(e) => return format!("Couldn't parse threshold: {}", e),
+            };
+            let min_confirmations = match args.get(1).map(|a| a.parse::<u32>()) {
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return format!("Couldn't parse min_confirmations: {}", e),
+                None => 1,
+            };
+
+            Some(Some(AutoShieldPolicy {
+                threshold,
+                min_confirmations,
+                to_address: args.get(2).map(|a| a.to_string()),
+            }))
+        };
+
+        RT.block_on(async move {
+            if let Some(policy) = policy {
+                if let Err(e) = lightclient.wallet.set_auto_shield(policy).await {
+                    return object! { "error" => e }.pretty(2);
+                }
+                if let Err(e) = lightclient.do_save(true).await {
+                    return object! { "error" => e }.pretty(2);
+                }
+            }
+
+            match lightclient.wallet.wallet_options.read().await.auto_shield.as_ref() {
+                Some(p) => object! {
+                    "auto_shield"       => true,
+                    "threshold"         => p.threshold,
+                    "min_confirmations" => p.min_confirmations,
+                    "to_address"        => p.to_address.clone(),
+                },
+                None => object! { "auto_shield" => false },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct SendBatchCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SendBatchCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Pay all the recipients in a CSV or JSON file");
+        h.push("Usage:");
+        h.push("sendbatch <file>");
+        h.push("");
+        h.push("A CSV file has one 'address,amount,memo' line per payment, with an optional header line. The memo");
+        h.push("can be left out, and needs double quotes if it has commas. A JSON file has an array of");
+        h.push("{\"address\": <address>, \"amount\": <zats>, \"memo\": <memo>} objects. Amounts are in zats.");
+        h.push("");
+        h.push("Every row is checked before anything is sent, and nothing is sent if any row is invalid. Large");
+        h.push("batches are split into several transactions, each of which needs its own confirmed funds.");
+        h.push("The result has the status, and the txid or error, of every row.");
+        h.push("Example:");
+        h.push("sendbatch /home/user/payouts.csv");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Pay all the recipients in a CSV or JSON file".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            match lightclient.do_send_batch(args[0]).await {
+                Ok(report) => report.pretty(2),
+                Err(e) => object! { "error" => e }.pretty(2),
+            }
+        })
+    }
+}
+
+struct SchedulePaymentCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SchedulePaymentCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Schedule a one-time or recurring payment, which is saved in the wallet file");
+        h.push("Usage:");
+        h.push("schedulepayment '{'recipients': [{'address': <address>, 'amount': <zats>, 'memo': <memo>}, ...],");
+        h.push("                  'height': <first due height> | 'time': <first due unix time>, 'interval': <n>}'");
+        h.push("");
+        h.push("The payment repeats every 'interval' blocks (if it is due at a height) or seconds (if it is due");
+        h.push("at a time). Leave 'interval' out, or set it to 0, to pay only once.");
+        h.push("Use 'duepayments' to see what is due, and 'paydue' to pay it.");
+        h.push("Example:");
+        h.push("schedulepayment '{\"recipients\": [{\"address\": \"zs1...\", \"amount\": 100000}], \"height\": 1500000, \"interval\": 8064}'");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Schedule a one-time or recurring payment".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        let json_args = match json::parse(args[0]) {
+            Ok(j) => j,
+            Err(e) => return format!("Couldn't understand JSON: {}\n{}", e, Command::<P>::help(self)),
+        };
+
+        if !json_args["recipients"].is_array() {
+            return format!("Need a 'recipients' array\n{}", Command::<P>::help(self));
+        }
+
+        let recipients = json_args["recipients"]
+            .members()
+            .map(|j| match (j["address"].as_str(), j["amount"].as_u64()) {
+                (Some(address), Some(value)) => Ok(ScheduledRecipient {
+                    address: address.to_string(),
+                    value,
+                    memo: j["memo"].as_str().map(|s| s.to_string()),
+                }),
+                _ => Err(format!("Each recipient needs an 'address' and an 'amount' in zats")),
+            })
+            .collect::<Result<Vec<_>, String>>();
+        let recipients = match recipients {
+            Ok(r) => r,
+            Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+        };
+
+        let next_due = match (json_args["height"].as_u32(), json_args["time"].as_u64()) {
+            (Some(height), None) => DueAt::Height(height),
+            (None, Some(time)) => DueAt::Time(time),
+            _ => return format!("Need either a 'height' or a 'time'\n{}", Command::<P>::help(self)),
+        };
+        let interval = json_args["interval"].as_u64().unwrap_or(0);
+
+        RT.block_on(async move {
+            let id = match lightclient
+                .wallet
+                .add_scheduled_payment(recipients, next_due, interval)
+                .await
+            {
+                Ok(id) => id,
+                Err(e) => return object! { "error" => e }.pretty(2),
+            };
+
+            match lightclient.do_save(true).await {
+                Ok(_) => object! { "id" => id }.pretty(2),
+                Err(e) => object! { "error" => e }.pretty(2),
+            }
+        })
+    }
+}
+
+struct ScheduledPaymentsCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ScheduledPaymentsCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("List all the scheduled payments, with the txids that paid them so far");
+        h.push("Usage:");
+        h.push("scheduledpayments");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "List the scheduled payments".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 0 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            let payments = lightclient.wallet.scheduled_payments.read().await;
+            json::JsonValue::Array(
+                payments
+                    .payments
+                    .iter()
+                    .map(LightClient::<P>::scheduled_payment_json)
+                    .collect(),
+            )
+            .pretty(2)
+        })
+    }
+}
+
+struct CancelPaymentCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for CancelPaymentCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Remove a scheduled payment, so it isn't paid again");
+        h.push("Usage:");
+        h.push("cancelpayment <id>");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Remove a scheduled payment".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        let id = match args[0].parse::<u64>() {
+            Ok(id) => id,
+            Err(e) => return format!("Couldn't parse id: {}", e),
+        };
+
+        RT.block_on(async move {
+            if let Err(e) = lightclient.wallet.remove_scheduled_payment(id).await {
+                return object! { "error" => e }.pretty(2);
+            }
+
+            match lightclient.do_save(true).await {
+                Ok(_) => object! { "result" => "success" }.pretty(2),
+                Err(e) => object! { "error" => e }.pretty(2),
+            }
+        })
+    }
+}
+
+struct DuePaymentsCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for DuePaymentsCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("List the scheduled payments that are due at the next block, or at the current time");
+        h.push("Usage:");
+        h.push("duepayments");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "List the scheduled payments that are due".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 0 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move { lightclient.do_due_payments().await.pretty(2) })
+    }
+}
+
+struct PayDueCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for PayDueCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Pay all the scheduled payments that are due, in one transaction per scheduled payment");
+        h.push("The wallet needs to be unlocked. Each payment that was paid moves on to its next due date.");
+        h.push("Usage:");
+        h.push("paydue");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Pay the scheduled payments that are due".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 0 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            let results = match lightclient.do_pay_due_payments().await {
+                Ok(r) => r,
+                Err(e) => return object! { "error" => e }.pretty(2),
+            };
+
+            match lightclient.do_save(true).await {
+                Ok(_) => results.pretty(2),
+                Err(e) => object! { "error" => e }.pretty(2),
+            }
+        })
+    }
+}
+
+struct ConsolidateCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ConsolidateCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Merge many small notes into fewer, bigger notes sent to yourself");
+        h.push("Usage:");
+        h.push("consolidate preview [max_notes] [dust_threshold]");
+        h.push("consolidate run [max_notes] [dust_threshold]");
+        h.push("");
+        h.push("The smallest 'max_notes' notes (default or 0: all of them) are merged, in batches of at most 25 notes per transaction");
+        h.push("so creating each transaction doesn't take too long. Every batch pays one fee.");
+        h.push("Batches that would leave a note worth less than 'dust_threshold' zats after the fee are skipped");
+        h.push("(default: the fee). 'preview' shows the batches and the total fee without sending anything.");
+        h.push("Example:");
+        h.push("consolidate preview 100");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Merge small notes into fewer notes".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() < 1 || args.len() > 3 {
+            return Command::<P>::help(self);
+        }
+
+        let preview = match args[0] {
+            "preview" => true,
+            "run" => false,
+            _ => return Command::<P>::help(self),
+        };
+
+        let max_notes = match args.get(1).map(|a| a.parse::<usize>()) {
+            Some(Ok(n)) => Some(n),
+            Some(Err(e)) => return format!("Couldn't parse max_notes: {}", e),
+            None => None,
+        };
+
+        let dust_threshold = match args.get(2).map(|a| a.parse::<u64>()) {
+            Some(Ok(t)) => Some(t),
+            Some(Err(e)) => return format!("Couldn't parse dust_threshold: {}", e),
+            None => None,
+        };
+
+        RT.block_on(async move {
+            match lightclient.do_consolidate(max_notes, dust_threshold, preview).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct ProposeCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ProposeCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Create an unsigned transaction, to be signed by an offline wallet that has the spending keys");
+        h.push("Usage:");
+        h.push("propose '[{'address': <address>, 'amount': <amount in zatoshis || \"max\">, 'memo': <optional memo>}, ...]' [optional utxo_strategy || options]");
+        h.push("");
+        h.push("This works in watch-only wallets. The options are the same as for the 'send' command.");
+        h.push("The returned 'proposal' hex is signed with the 'signproposal' command in the offline wallet, and the");
+        h.push("signed transaction is then broadcast with the 'broadcastsigned' command in this wallet.");
+        h.push("Example:");
+        h.push("propose '[{\"address\": \"ztestsapling1x65nq4dgp0qfywgxcwk9n0fvm4fysmapgr2q00p85ju252h6l7mmxu2jg9cqqhtvzd69jwhgv8d\", \"amount\": 200000}]'");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Create an unsigned transaction for offline signing".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() < 1 || args.len() > 2 {
+            return Command::<P>::help(self);
+        }
+
+        let mut options = if args.len() == 2 {
+            match parse_send_options(args[1]) {
+                Ok(o) => o,
+                Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+            }
+        } else {
+            SendOptions::default()
+        };
+
+        let json_args = match json::parse(args[0]) {
+            Ok(j) if j.is_array() => j,
+            Ok(_) => return format!("Couldn't parse argument as array\n{}", Command::<P>::help(self)),
+            Err(e) => return format!("Couldn't understand JSON: {}\n{}", e, Command::<P>::help(self)),
+        };
+
+        let maybe_send_args = json_args
+            .members()
+            .map(|j| {
+                let address = j["address"].as_str().ok_or("Need 'address' and 'amount'".to_string())?;
+                let amount = match (j["amount"].as_str(), j["amount"].as_u64()) {
+                    // The actual amount is calculated when the proposal is created
+                    (Some("max"), _) => 0,
+                    (_, Some(amt)) => amt,
+                    _ => return Err("Need 'address' and 'amount'".to_string()),
+                };
+
+                Ok((address.to_string(), amount, j["memo"].as_str().map(|s| s.to_string())))
+            })
+            .collect::<Result<Vec<(String, u64, Option<String>)>, String>>();
+
+        let send_args = match maybe_send_args {
+            Ok(a) => a,
+            Err(e) => return format!("Error: {}\n{}", e, Command::<P>::help(self)),
+        };
+
+        if json_args.members().any(|j| j["amount"].as_str() == Some("max")) {
+            options.send_max = true;
+        }
+
+        RT.block_on(async move {
+            let tos = send_args
+                .iter()
+                .map(|(a, v, m)| (a.as_str(), *v, m.clone()))
+                .collect::<Vec<_>>();
+
+            match lightclient.do_create_unsigned_proposal(tos, options).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct SignProposalCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SignProposalCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Sign an unsigned transaction created by the 'propose' command");
+        h.push("Usage:");
+        h.push("signproposal <proposal hex>");
+        h.push("");
+        h.push("The wallet needs the spending keys for all the inputs of the proposal. It doesn't need to be synced,");
+        h.push("and the signed transaction is not broadcast. Use the 'broadcastsigned' command in an online wallet for that.");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Sign an unsigned transaction without broadcasting it".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            match lightclient.do_sign_proposal(args[0]).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct BroadcastSignedCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for BroadcastSignedCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Broadcast a transaction that was signed by an offline wallet");
+        h.push("Usage:");
+        h.push("broadcastsigned <signed tx hex>");
+        h.push("");
+        h.push("The transaction is tracked as a pending send, and its inputs are not spent again until it is mined or expires");
+        h.push("Any raw transaction can be broadcast, like the 'raw_tx' that 'send' returned on another computer");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Broadcast a transaction signed by an offline wallet".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() != 1 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            match lightclient.do_broadcast_signed_tx(args[0]).await {
+                Ok(txid) => object! { "txid" => txid },
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct DecodeRawTxCommand {}
 
-impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for DecryptMessageCommand {
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for DecodeRawTxCommand {
     fn help(&self) -> String {
         let mut h = vec![];
-        h.push("Attempt to decrypt a message with all the view keys in the wallet.");
+        h.push("Decode a raw transaction");
         h.push("Usage:");
-        h.push("decryptmessage \"encrypted_message_base64\"");
-        h.push("");
-        h.push("Example:");
-        h.push("decryptmessage RW5jb2RlIGFyYml0cmFyeSBvY3RldHMgYXMgYmFzZTY0LiBSZXR1cm5zIGEgU3RyaW5nLg==");
+        h.push("decoderawtx <tx hex>");
         h.push("");
+        h.push("Shows the inputs and outputs of the transaction, its expiry height and value balance, and which");
+        h.push("inputs and outputs belong to this wallet. The values of outputs paid to this wallet, or sent by it,");
+        h.push("can be decrypted. The fee is only shown if the values of all the transparent inputs are known.");
 
         h.join("\n")
     }
 
     fn short_help(&self) -> String {
-        "Attempt to decrypt a message with all the view keys in the wallet.".to_string()
+        "Decode a raw transaction".to_string()
     }
+
     fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
         if args.len() != 1 {
             return Command::<P>::help(self);
         }
 
-        RT.block_on(async move { lightclient.do_decrypt_message(args[0].to_string()).await.pretty(2) })
+        RT.block_on(async move {
+            match lightclient.do_decode_tx(args[0]).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
     }
 }
 
-struct SendCommand {}
+struct BroadcastServersCommand {}
 
-impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SendCommand {
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for BroadcastServersCommand {
     fn help(&self) -> String {
         let mut h = vec![];
-        h.push("Send ZEC to a given address(es)");
+        h.push("Show or set the extra servers that every transaction is also broadcast to");
         h.push("Usage:");
-        h.push("send <address> <amount in zatoshis || \"entire-verified-zbalance\"> \"optional_memo\"");
-        h.push("OR");
-        h.push("send '[{'address': <address>, 'amount': <amount in zatoshis>, 'memo': <optional memo>}, ...]'");
+        h.push("broadcastservers");
+        h.push("broadcastservers clear");
+        h.push("broadcastservers <server> [<server> ...]");
         h.push("");
-        h.push("NOTE: The fee required to send this transaction (currently ZEC 0.0001) is additionally deducted from your balance.");
+        h.push("Transactions are sent to the wallet's own server and to all of these servers but the last one at the");
+        h.push("same time, and are sent if any of them accepts them. The last server is never sent transactions, so");
+        h.push("'checkpropagation' can look for them there to check they reached the network.");
         h.push("Example:");
-        h.push("send ztestsapling1x65nq4dgp0qfywgxcwk9n0fvm4fysmapgr2q00p85ju252h6l7mmxu2jg9cqqhtvzd69jwhgv8d 200000 \"Hello from the command line\"");
+        h.push("broadcastservers https://lightd2.btcz.rocks:9067");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Show or set the extra servers transactions are broadcast to".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        let servers = match args {
+            [] => None,
+            ["clear"] => Some(vec![]),
+            _ => Some(args.iter().map(|a| a.to_string()).collect()),
+        };
+
+        RT.block_on(async move {
+            if let Some(servers) = servers {
+                if let Err(e) = lightclient.wallet.set_broadcast_servers(servers).await {
+                    return object! { "error" => e }.pretty(2);
+                }
+                if let Err(e) = lightclient.do_save(true).await {
+                    return object! { "error" => e }.pretty(2);
+                }
+            }
+
+            object! {
+                "server"            => lightclient.get_server_uri().to_string(),
+                "broadcast_servers" => lightclient.wallet.get_broadcast_servers().await,
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct CheckPropagationCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for CheckPropagationCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Check that a transaction reached the network, by looking for it on the broadcast servers");
+        h.push("Usage:");
+        h.push("checkpropagation <txid> [timeout_secs]");
         h.push("");
+        h.push("Every broadcast server is asked for the transaction, again every few seconds, until all of them have");
+        h.push("it or until the timeout (default 30 secs). The transaction has propagated once every server has it.");
+        h.push("Shows, for every server, whether it accepted the broadcast and whether it has the transaction.");
 
         h.join("\n")
     }
 
     fn short_help(&self) -> String {
-        "Send ZEC to the given address".to_string()
+        "Check that a transaction reached all the broadcast servers".to_string()
     }
+
     fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
-        // Parse the args. There are two argument types.
-        // 1 - A set of 2(+1 optional) arguments for a single address send representing address, value, memo?
-        // 2 - A single argument in the form of a JSON string that is "[{address: address, value: value, memo: memo},...]"
-        if args.len() < 1 || args.len() > 3 {
+        if args.is_empty() || args.len() > 2 {
             return Command::<P>::help(self);
         }
 
+        let timeout_secs = match args.get(1).map(|a| a.parse::<u64>()) {
+            Some(Ok(t)) => t,
+            Some(Err(e)) => return format!("Couldn't parse timeout_secs: {}", e),
+            None => 30,
+        };
+
         RT.block_on(async move {
-            // Check for a single argument that can be parsed as JSON
-            let send_args = if args.len() == 1 {
-                let arg_list = args[0];
+            match lightclient.do_check_propagation(args[0], timeout_secs).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
 
-                let json_args = match json::parse(&arg_list) {
-                    Ok(j) => j,
-                    Err(e) => {
-                        let es = format!("Couldn't understand JSON: {}", e);
-                        return format!("{}\n{}", es, Command::<P>::help(self));
-                    }
-                };
+struct PendingSendsCommand {}
 
-                if !json_args.is_array() {
-                    return format!("Couldn't parse argument as array\n{}", Command::<P>::help(self));
-                }
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for PendingSendsCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Show the sent transactions that aren't mined yet, and the ones that expired");
+        h.push("Usage:");
+        h.push("pendingsends");
+        h.push("");
+        h.push("Pending transactions are rebroadcast to all the broadcast servers once for every new block, until");
+        h.push("they are mined or reach their expiry height. Shows, for each, the expiry height, how many times it");
+        h.push("was rebroadcast and the error if no server took the latest rebroadcast. Transactions that expired");
+        h.push("without being mined are dropped from the wallet, and the funds they spent can be spent again.");
 
-                let fee = u64::from(DEFAULT_FEE);
-                let all_zbalance = lightclient.wallet.verified_zbalance(None).await.checked_sub(fee);
+        h.join("\n")
+    }
 
-                let maybe_send_args = json_args
-                    .members()
-                    .map(|j| {
-                        if !j.has_key("address") || !j.has_key("amount") {
-                            Err(format!("Need 'address' and 'amount'\n"))
-                        } else {
-                            let amount = match j["amount"].as_str() {
-                                Some("entire-verified-zbalance") => all_zbalance,
-                                _ => Some(j["amount"].as_u64().unwrap()),
-                            };
+    fn short_help(&self) -> String {
+        "Show the pending and expired sent transactions".to_string()
+    }
 
-                            match amount {
-                                Some(amt) => Ok((
-                                    j["address"].as_str().unwrap().to_string().clone(),
-                                    amt,
-                                    j["memo"].as_str().map(|s| s.to_string().clone()),
-                                )),
-                                None => Err(format!("Not enough in wallet to pay transaction fee of {}", fee)),
-                            }
-                        }
-                    })
-                    .collect::<Result<Vec<(String, u64, Option<String>)>, String>>();
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if !args.is_empty() {
+            return Command::<P>::help(self);
+        }
 
-                match maybe_send_args {
-                    Ok(a) => a.clone(),
-                    Err(s) => {
-                        return format!("Error: {}\n{}", s, Command::<P>::help(self));
-                    }
-                }
-            } else if args.len() == 2 || args.len() == 3 {
-                let address = args[0].to_string();
+        RT.block_on(async move { lightclient.do_pending_sends().await.pretty(2) })
+    }
+}
 
-                // Make sure we can parse the amount
-                let value = match args[1].parse::<u64>() {
-                    Ok(amt) => amt,
-                    Err(e) => {
-                        if args[1] == "entire-verified-zbalance" {
-                            let fee = u64::from(DEFAULT_FEE);
-                            match lightclient.wallet.verified_zbalance(None).await.checked_sub(fee) {
-                                Some(amt) => amt,
-                                None => return format!("Not enough in wallet to pay transaction fee of {}", fee),
-                            }
-                        } else {
-                            return format!("Couldn't parse amount: {}", e);
-                        }
-                    }
-                };
+struct ProverCommand {}
 
-                let memo = if args.len() == 3 {
-                    Some(args[2].to_string())
-                } else {
-                    None
-                };
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for ProverCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Show whether the Sapling prover is loaded, or load or release it");
+        h.push("Usage:");
+        h.push("prover [load | release]");
+        h.push("");
+        h.push("The prover is loaded from the Sapling params by the first send, and kept for the sends after it.");
+        h.push("'load' loads it ahead of time, so the next send starts proving right away. 'release' frees the");
+        h.push("memory it uses, and it is loaded again when it is next needed.");
 
-                // Memo has to be None if not sending to a shileded address
-                if memo.is_some() && !Keys::is_shielded_address(&address, &lightclient.config) {
-                    return format!("Can't send a memo to the non-shielded address {}", address);
-                }
+        h.join("\n")
+    }
 
-                vec![(args[0].to_string(), value, memo)]
-            } else {
-                return Command::<P>::help(self);
-            };
+    fn short_help(&self) -> String {
+        "Load or release the Sapling prover".to_string()
+    }
 
-            // Convert to the right format. String -> &str.
-            let tos = send_args
-                .iter()
-                .map(|(a, v, m)| (a.as_str(), *v, m.clone()))
-                .collect::<Vec<_>>();
-            match lightclient.do_send(tos).await {
-                Ok(txid) => {
-                    object! { "txid" => txid }
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        RT.block_on(async move {
+            match args {
+                [] => {}
+                ["load"] => {
+                    if let Err(e) = lightclient.do_load_prover().await {
+                        return object! { "error" => e }.pretty(2);
+                    }
                 }
-                Err(e) => {
-                    object! { "error" => e }
+                ["release"] => {
+                    lightclient.do_release_prover().await;
                 }
+                _ => return Command::<P>::help(self),
             }
-            .pretty(2)
+
+            object! { "loaded" => lightclient.is_prover_loaded().await }.pretty(2)
         })
     }
 }
@@ -877,6 +1879,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SeedComman
         h.push("seed");
         h.push("");
         h.push("Your wallet is entirely recoverable from the seed phrase. Please save it carefully and don't share it with anyone");
+        h.push("If the wallet has a passphrase ('has_passphrase'), restoring it needs the passphrase too.");
 
         h.join("\n")
     }
@@ -895,6 +1898,73 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SeedComman
     }
 }
 
+struct PassphraseCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for PassphraseCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Enter the BIP39 passphrase of a wallet that isn't encrypted");
+        h.push("Usage:");
+        h.push("passphrase <passphrase>");
+        h.push("");
+        h.push("The passphrase of an unencrypted wallet is not saved, so it has to be entered again before");
+        h.push("the wallet can derive new addresses. Encrypted wallets save it encrypted, and get it back");
+        h.push("when they are unlocked.");
+        h.push("Words in the passphrase are separated by single spaces.");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Enter the wallet's BIP39 passphrase".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        if args.len() == 0 {
+            return Command::<P>::help(self);
+        }
+
+        RT.block_on(async move {
+            match lightclient.do_set_passphrase(args.join(" ")).await {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct CheckSeedCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for CheckSeedCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("Check a seed phrase, or suggest words for a partly typed or mistyped word");
+        h.push("Usage:");
+        h.push("checkseed <seed phrase>");
+        h.push("checkseed <word>");
+        h.push("");
+        h.push("For a phrase, lists every word that isn't a seed phrase word, with suggestions,");
+        h.push("and says whether the phrase has the right length and checksum to be restored.");
+        h.push("For a single word, lists the words it completes to and the closest words.");
+        h.push("This doesn't need a wallet.");
+
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "Check a seed phrase before restoring it".to_string()
+    }
+
+    fn exec(&self, args: &[&str], _lightclient: &LightClient<P>) -> String {
+        match args.len() {
+            0 => Command::<P>::help(self),
+            1 => seed_phrase::word_suggestions(args[0]).pretty(2),
+            _ => seed_phrase::seed_phrase_report(&args.join(" ")).pretty(2),
+        }
+    }
+}
+
 struct TransactionsCommand {}
 
 impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for TransactionsCommand {
@@ -945,6 +2015,17 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SetOptionC
         h.push("setoption <optionname>=<optionvalue>");
         h.push("List of available options:");
         h.push("download_memos : none | wallet | all");
+        h.push("change_policy  : same_as_input | fresh | <one of your z-addresses>");
+        h.push("  same_as_input - Change goes back to the z-address of the spent notes (default)");
+        h.push("  fresh         - Change goes to a new z-address for every transaction");
+        h.push("  <z-address>   - Change always goes to this z-address");
+        h.push("The change policy also picks the z-address that 'shield' sends to, if none is given");
+        h.push("privacy_policy : full_privacy | allow_revealed_amounts | allow_revealed_recipients | allow_linking_transparent");
+        h.push("  full_privacy              - Only spend shielded notes, and only send to shielded addresses");
+        h.push("  allow_revealed_amounts    - Also spend UTXOs, which reveals the amounts being shielded");
+        h.push("  allow_revealed_recipients - Also send to t-addresses, revealing the recipients and amounts");
+        h.push("  allow_linking_transparent - Also spend UTXOs of several t-addresses together (default)");
+        h.push("Sends that would reveal more than the privacy policy allows fail with an error saying why");
 
         h.join("\n")
     }
@@ -984,6 +2065,19 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for SetOptionC
                     let threshold = option_value.parse::<i64>().unwrap();
                     lightclient.wallet.set_spam_filter_threshold(threshold).await
                 }
+                "change_policy" => {
+                    let policy = match option_value.parse::<ChangePolicy>() {
+                        Ok(p) => p,
+                        Err(e) => return format!("Error: {}", e),
+                    };
+                    if let Err(e) = lightclient.wallet.set_change_policy(policy).await {
+                        return format!("Error: {}", e);
+                    }
+                }
+                "privacy_policy" => match option_value.parse::<PrivacyPolicy>() {
+                    Ok(p) => lightclient.wallet.set_privacy_policy(p).await,
+                    Err(e) => return format!("Error: {}", e),
+                },
                 _ => return format!("Error: Couldn't understand {}", option_name),
             }
 
@@ -1033,6 +2127,14 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for GetOptionC
                     .await
                     .spam_threshold
                     .to_string(),
+                "change_policy" => lightclient.wallet.wallet_options.read().await.change_policy.to_string(),
+                "privacy_policy" => lightclient
+                    .wallet
+                    .wallet_options
+                    .read()
+                    .await
+                    .privacy_policy
+                    .to_string(),
                 _ => return format!("Error: Couldn't understand {}", option_name),
             };
 
@@ -1210,6 +2312,10 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for NewAddress
         h.push("Create a new address in this wallet");
         h.push("Usage:");
         h.push("new [u | z | t]");
+        h.push("new zd [zaddress]");
+        h.push("");
+        h.push("'zd' creates a diversified address of the key of zaddress (or of the first key), not a new key.");
+        h.push("Diversified addresses are found with their key's viewing key, so they don't slow down the sync.");
         h.push("");
         h.push("Example:");
         h.push("To create a new z address:");
@@ -1221,12 +2327,88 @@ impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for NewAddress
         "Create a new address in this wallet".to_string()
     }
     fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
-        if args.len() != 1 {
+        if args.len() != 1 && !(args.len() == 2 && args[0] == "zd") {
             return format!("No address type specified\n{}", Command::<P>::help(self));
         }
 
         RT.block_on(async move {
-            match lightclient.do_new_address(args[0]).await {
+            let r = match args[0] {
+                "zd" => lightclient.do_new_diversified_address(args.get(1).copied()).await,
+                addr_type => lightclient.do_new_address(addr_type).await,
+            };
+            match r {
+                Ok(j) => j,
+                Err(e) => object! { "error" => e },
+            }
+            .pretty(2)
+        })
+    }
+}
+
+struct AccountsCommand {}
+
+impl<P: consensus::Parameters + Send + Sync + 'static> Command<P> for AccountsCommand {
+    fn help(&self) -> String {
+        let mut h = vec![];
+        h.push("List and manage the accounts of this wallet");
+        h.push("Usage:");
+        h.push("accounts");
+        h.push("accounts new <name>");
+        h.push("accounts rename <account id> <name>");
+        h.push("accounts balance <account id>");
+        h.push("accounts list <account id>");
+        h.push("accounts address <account id> [z | t]");
+        h.push("");
+        h.push("Every account has its own addresses, derived from the seed. Its balance and list only include the");
+        h.push("funds received at them, and sends with the 'account' option only spend those funds.");
+        h.push("The addresses the wallet had before there were accounts belong to the default account, 0. Sends");
+        h.push("without the 'account' option spend from the account of their 'from' address or inputs, or else");
+        h.push("from the default account. A wallet restored from its seed finds the accounts that received funds.");
+        h.push("");
+        h.push("Example:");
+        h.push("accounts new Savings");
+        h.push("accounts balance 1");
+        h.join("\n")
+    }
+
+    fn short_help(&self) -> String {
+        "List and manage the accounts of this wallet".to_string()
+    }
+
+    fn exec(&self, args: &[&str], lightclient: &LightClient<P>) -> String {
+        let account = |i: usize| match args.get(i).map(|a| a.parse::<u32>()) {
+            Some(Ok(id)) => Ok(id),
+            _ => Err(format!("Need an account id\n{}", Command::<P>::help(self))),
+        };
+
+        RT.block_on(async move {
+            let r = match args.get(0).copied() {
+                None => Ok(lightclient.do_accounts().await),
+                Some("new") if args.len() >= 2 => lightclient.do_new_account(&args[1..].join(" ")).await,
+                Some("rename") if args.len() >= 3 => match account(1) {
+                    Ok(id) => lightclient.do_rename_account(id, &args[2..].join(" ")).await,
+                    Err(e) => Err(e),
+                },
+                Some("balance") if args.len() == 2 => match account(1) {
+                    Ok(id) => lightclient.do_account_balance(id).await,
+                    Err(e) => Err(e),
+                },
+                Some("list") if args.len() == 2 => match account(1) {
+                    Ok(id) => lightclient.do_list_account_transactions(id, false).await,
+                    Err(e) => Err(e),
+                },
+                Some("address") if args.len() == 2 || args.len() == 3 => match account(1) {
+                    Ok(id) => {
+                        lightclient
+                            .do_new_account_address(id, args.get(2).copied().unwrap_or("z"))
+                            .await
+                    }
+                    Err(e) => Err(e),
+                },
+                _ => return Command::<P>::help(self),
+            };
+
+            match r {
                 Ok(j) => j,
                 Err(e) => object! { "error" => e },
             }
@@ -1328,9 +2510,31 @@ pub fn get_commands<P: consensus::Parameters + Send + Sync + 'static>() -> Box<H
     map.insert("quit".to_string(), Box::new(QuitCommand {}));
     map.insert("list".to_string(), Box::new(TransactionsCommand {}));
     map.insert("notes".to_string(), Box::new(NotesCommand {}));
+    map.insert("coins".to_string(), Box::new(CoinsCommand {}));
+    map.insert("freeze".to_string(), Box::new(FreezeCommand {}));
+    map.insert("unfreeze".to_string(), Box::new(UnfreezeCommand {}));
+    map.insert("autoshield".to_string(), Box::new(AutoShieldCommand {}));
+    map.insert("consolidate".to_string(), Box::new(ConsolidateCommand {}));
+    map.insert("sendbatch".to_string(), Box::new(SendBatchCommand {}));
+    map.insert("schedulepayment".to_string(), Box::new(SchedulePaymentCommand {}));
+    map.insert("scheduledpayments".to_string(), Box::new(ScheduledPaymentsCommand {}));
+    map.insert("cancelpayment".to_string(), Box::new(CancelPaymentCommand {}));
+    map.insert("duepayments".to_string(), Box::new(DuePaymentsCommand {}));
+    map.insert("paydue".to_string(), Box::new(PayDueCommand {}));
+    map.insert("propose".to_string(), Box::new(ProposeCommand {}));
+    map.insert("signproposal".to_string(), Box::new(SignProposalCommand {}));
+    map.insert("broadcastsigned".to_string(), Box::new(BroadcastSignedCommand {}));
+    map.insert("broadcastservers".to_string(), Box::new(BroadcastServersCommand {}));
+    map.insert("decoderawtx".to_string(), Box::new(DecodeRawTxCommand {}));
+    map.insert("prover".to_string(), Box::new(ProverCommand {}));
+    map.insert("checkpropagation".to_string(), Box::new(CheckPropagationCommand {}));
+    map.insert("pendingsends".to_string(), Box::new(PendingSendsCommand {}));
     map.insert("new".to_string(), Box::new(NewAddressCommand {}));
+    map.insert("accounts".to_string(), Box::new(AccountsCommand {}));
     map.insert("defaultfee".to_string(), Box::new(DefaultFeeCommand {}));
     map.insert("seed".to_string(), Box::new(SeedCommand {}));
+    map.insert("passphrase".to_string(), Box::new(PassphraseCommand {}));
+    map.insert("checkseed".to_string(), Box::new(CheckSeedCommand {}));
     map.insert("encrypt".to_string(), Box::new(EncryptCommand {}));
     map.insert("decrypt".to_string(), Box::new(DecryptCommand {}));
     map.insert("unlock".to_string(), Box::new(UnlockCommand {}));
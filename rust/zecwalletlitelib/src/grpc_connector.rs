@@ -448,6 +448,41 @@ impl GrpcConnector {
         Ok(response.into_inner())
     }
 
+    // Fetch a Tx by its txid. Servers also return Txs that are only in their mempool, so this tells if a server
+    // has seen a Tx that was just broadcast.
+    pub async fn get_transaction(uri: http::Uri, txid: &TxId) -> Result<RawTransaction, String> {
+        let client = Arc::new(GrpcConnector::new(uri));
+        let mut client = client
+            .get_client()
+            .await
+            .map_err(|e| format!("Error getting client: {:?}", e))?;
+
+        let request = Request::new(TxFilter {
+            block: None,
+            index: 0,
+            hash: txid.as_ref().to_vec(),
+        });
+
+        let response = client.get_transaction(request).await.map_err(|e| format!("{}", e))?;
+        Ok(response.into_inner())
+    }
+
+    // Send a Tx to all the servers at once, and return what each one of them said
+    pub async fn broadcast_transaction(
+        uris: Vec<http::Uri>,
+        tx_bytes: Box<[u8]>,
+    ) -> Vec<(http::Uri, Result<String, String>)> {
+        let sends = uris.into_iter().map(|uri| {
+            let tx_bytes = tx_bytes.clone();
+            async move {
+                let result = Self::send_transaction(uri.clone(), tx_bytes).await;
+                (uri, result)
+            }
+        });
+
+        futures::future::join_all(sends).await
+    }
+
     pub async fn send_transaction(uri: http::Uri, tx_bytes: Box<[u8]>) -> Result<String, String> {
         info!("Sending transaction to lightwalletd server: {}", uri);
         let client = Arc::new(GrpcConnector::new(uri));
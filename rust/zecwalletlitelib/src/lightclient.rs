@@ -1,4 +1,7 @@
-use self::lightclient_config::LightClientConfig;
+use self::{
+    lightclient_config::LightClientConfig,
+    prover::{ProverCache, SharedTxProver},
+};
 use crate::{
     blaze::{
         block_witness_data::BlockAndWitnessData, fetch_compact_blocks::FetchCompactBlocks,
@@ -7,17 +10,27 @@ use crate::{
     },
     compact_formats::RawTransaction,
     grpc_connector::GrpcConnector,
-    lightclient::lightclient_config::MAX_REORG,
-    lightwallet::{self, data::WalletTx, message::Message, now, LightWallet, MAX_CHECKPOINTS, MERKLE_DEPTH},
+    lightclient::lightclient_config::{GAP_RULE_UNUSED_ADDRESSES, MAX_REORG},
+    lightwallet::{
+        self,
+        data::WalletTx,
+        message::Message,
+        now,
+        proposal::UnsignedProposal,
+        walletaccount::{WalletAccount, DEFAULT_ACCOUNT},
+        ChangePolicy, ConsolidationBatch, LightWallet, SendOptions, SendProgress, UtxoSelectionStrategy,
+        MAX_CHECKPOINTS, MERKLE_DEPTH,
+    },
 };
-use futures::{stream::FuturesUnordered, StreamExt};
+use futures::{stream::FuturesUnordered, Future, StreamExt};
 use incrementalmerkletree::bridgetree::BridgeTree;
 use json::{array, object, JsonValue};
 use log::{error, info, warn};
 use orchard::tree::MerkleHashOrchard;
 use std::{
     cmp,
-    collections::HashSet,
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryFrom,
     fs::File,
     io::{self, BufReader, Error, ErrorKind, Read, Write},
     path::Path,
@@ -37,12 +50,30 @@ use zcash_primitives::{
     consensus::{self, BlockHeight, BranchId},
     memo::{Memo, MemoBytes},
     merkle_tree::CommitmentTree,
+    sapling::{
+        note_encryption::{try_sapling_note_decryption, try_sapling_output_recovery},
+        prover::TxProver,
+    },
     transaction::{components::amount::DEFAULT_FEE, Transaction, TxId},
 };
-use zcash_proofs::prover::LocalTxProver;
 
 pub(crate) mod checkpoints;
 pub mod lightclient_config;
+pub(crate) mod prover;
+
+// How many of the latest broadcasts the results are kept for, for checking their propagation
+const MAX_BROADCAST_RECORDS: usize = 100;
+
+// What happened to the Txs we sent that aren't mined yet, in this session
+#[derive(Default)]
+struct PendingSends {
+    // The latest block the pending Txs were rebroadcast at. They are rebroadcast at most once per new block
+    rebroadcast_height: u64,
+    // How many times each pending Tx was rebroadcast, and the error if no server took the latest one
+    rebroadcasts: HashMap<String, (u32, Option<String>)>,
+    // The Txs we sent that expired without being mined, with the height they expired at, oldest first
+    expired: Vec<(String, u64)>,
+}
 
 #[derive(Clone, Debug)]
 pub struct WalletStatus {
@@ -70,6 +101,15 @@ pub struct LightClient<P> {
     sync_lock: Mutex<()>,
 
     bsync_data: Arc<RwLock<BlazeSyncData>>,
+
+    // What each server said when the latest Txs sent in this session were broadcast, by txid, oldest first
+    broadcasts: Arc<RwLock<Vec<(String, Vec<(String, Result<String, String>)>)>>>,
+
+    // The rebroadcasts and expiries of the Txs we sent that aren't mined yet
+    pending_sends: Arc<RwLock<PendingSends>>,
+
+    // The Sapling prover, loaded by the first send that needs it
+    prover: ProverCache<SharedTxProver>,
 }
 
 impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
@@ -84,9 +124,12 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         }
 
         let l = LightClient {
-            wallet: LightWallet::new(config.clone(), seed_phrase, height, 1, 1)?,
+            wallet: LightWallet::new(config.clone(), seed_phrase, None, height, 1, 1)?,
             config: config.clone(),
             mempool_monitor: std::sync::RwLock::new(None),
+            broadcasts: Arc::new(RwLock::new(vec![])),
+            pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+            prover: ProverCache::new(),
             bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
             sync_lock: Mutex::new(()),
         };
@@ -110,7 +153,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
     }
 
     #[cfg(feature = "embed_params")]
-    fn read_sapling_params(&self) -> Result<(Vec<u8>, Vec<u8>), String> {
+    fn read_sapling_params(_config: &LightClientConfig<P>) -> Result<(Vec<u8>, Vec<u8>), String> {
         // Read Sapling Params
         use crate::SaplingParams;
         let mut sapling_output = vec![];
@@ -123,8 +166,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
     }
 
     #[cfg(not(feature = "embed_params"))]
-    fn read_sapling_params(&self) -> Result<(Vec<u8>, Vec<u8>), String> {
-        let path = self.config.get_zcash_params_path().map_err(|e| e.to_string())?;
+    fn read_sapling_params(config: &LightClientConfig<P>) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let path = config.get_zcash_params_path().map_err(|e| e.to_string())?;
 
         let mut path_buf = path.to_path_buf();
         path_buf.push("sapling-output.params");
@@ -141,6 +184,36 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         Ok((sapling_output, sapling_spend))
     }
 
+    // The Sapling prover. The params are only read and parsed the first time it is needed, after that every send
+    // uses the same prover until it is released.
+    async fn sapling_prover(&self) -> Result<SharedTxProver, String> {
+        let config = self.config.clone();
+        self.prover
+            .get_or_load(move || {
+                let (sapling_output, sapling_spend) = Self::read_sapling_params(&config)?;
+                let p = SharedTxProver::from_bytes(&sapling_spend, &sapling_output);
+                info!("Loaded the Sapling prover");
+
+                Ok(p)
+            })
+            .await
+    }
+
+    /// Load the Sapling prover now, so the next send can start proving right away
+    pub async fn do_load_prover(&self) -> Result<(), String> {
+        self.sapling_prover().await.map(|_| ())
+    }
+
+    /// Drop the Sapling prover to free its memory. It is loaded again by the next send. Returns whether it was
+    /// loaded. A send that is already running keeps its own reference, so it is not affected.
+    pub async fn do_release_prover(&self) -> bool {
+        self.prover.release().await
+    }
+
+    pub async fn is_prover_loaded(&self) -> bool {
+        self.prover.is_loaded().await
+    }
+
     pub fn set_sapling_params(&mut self, sapling_output: &[u8], sapling_spend: &[u8]) -> Result<(), String> {
         use sha2::{Digest, Sha256};
 
@@ -214,15 +287,19 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
 
     fn new_wallet(
         config: &LightClientConfig<P>,
+        passphrase: Option<String>,
         latest_block: u64,
         num_zaddrs: u32,
         num_oaddrs: u32,
     ) -> io::Result<Self> {
         Runtime::new().unwrap().block_on(async move {
             let l = LightClient {
-                wallet: LightWallet::new(config.clone(), None, latest_block, num_zaddrs, num_oaddrs)?,
+                wallet: LightWallet::new(config.clone(), None, passphrase, latest_block, num_zaddrs, num_oaddrs)?,
                 config: config.clone(),
                 mempool_monitor: std::sync::RwLock::new(None),
+                broadcasts: Arc::new(RwLock::new(vec![])),
+                pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+                prover: ProverCache::new(),
                 sync_lock: Mutex::new(()),
                 bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
             };
@@ -244,6 +321,16 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
     /// Create a brand new wallet with a new seed phrase. Will fail if a wallet file
     /// already exists on disk
     pub fn new(config: &LightClientConfig<P>, latest_block: u64) -> io::Result<Self> {
+        Self::new_with_passphrase(config, None, latest_block)
+    }
+
+    /// Create a brand new wallet with a new seed phrase, protected by a BIP39 passphrase. The same passphrase
+    /// is needed to restore the wallet from the seed phrase.
+    pub fn new_with_passphrase(
+        config: &LightClientConfig<P>,
+        passphrase: Option<String>,
+        latest_block: u64,
+    ) -> io::Result<Self> {
         #[cfg(all(not(target_os = "ios"), not(target_os = "android")))]
         {
             if config.wallet_exists() {
@@ -254,7 +341,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
             }
         }
 
-        Self::new_wallet(config, latest_block, 1, 1)
+        Self::new_wallet(config, passphrase, latest_block, 1, 1)
     }
 
     pub fn new_from_phrase(
@@ -262,6 +349,17 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         config: &LightClientConfig<P>,
         birthday: u64,
         overwrite: bool,
+    ) -> io::Result<Self> {
+        Self::new_from_phrase_with_passphrase(seed_phrase, None, config, birthday, overwrite)
+    }
+
+    /// Restore a wallet from its seed phrase and the BIP39 passphrase it was created with
+    pub fn new_from_phrase_with_passphrase(
+        seed_phrase: String,
+        passphrase: Option<String>,
+        config: &LightClientConfig<P>,
+        birthday: u64,
+        overwrite: bool,
     ) -> io::Result<Self> {
         #[cfg(all(not(target_os = "ios"), not(target_os = "android")))]
         {
@@ -276,7 +374,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         let lr = if seed_phrase.starts_with(config.hrp_sapling_private_key())
             || seed_phrase.starts_with(config.hrp_sapling_viewing_key())
         {
-            let lc = Self::new_wallet(config, birthday, 0, 0)?;
+            let lc = Self::new_wallet(config, None, birthday, 0, 0)?;
             Runtime::new().unwrap().block_on(async move {
                 lc.do_import_key(seed_phrase, birthday)
                     .await
@@ -288,18 +386,29 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
             })
         } else {
             Runtime::new().unwrap().block_on(async move {
-                // Start with a reasonable number of addresses to check during restoration
-                // We'll discover more during sync if needed
-                const INITIAL_ADDR_COUNT: u32 = 20;  // Check first 20 addresses of each type
+                // Start with a full gap of addresses of each type. The syncs after the restore discover the rest,
+                // see `sync_and_discover`.
+                const INITIAL_ADDR_COUNT: u32 = GAP_RULE_UNUSED_ADDRESSES as u32;
 
                 let l = LightClient {
-                    wallet: LightWallet::new(config.clone(), Some(seed_phrase), birthday, INITIAL_ADDR_COUNT, 1)?,
+                    wallet: LightWallet::new(
+                        config.clone(),
+                        Some(seed_phrase),
+                        passphrase,
+                        birthday,
+                        INITIAL_ADDR_COUNT,
+                        1,
+                    )?,
                     config: config.clone(),
                     mempool_monitor: std::sync::RwLock::new(None),
+                    broadcasts: Arc::new(RwLock::new(vec![])),
+                    pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+                    prover: ProverCache::new(),
                     sync_lock: Mutex::new(()),
                     bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
                 };
 
+                l.wallet.set_hd_discovery_pending(true);
                 l.set_wallet_initial_state(birthday).await;
                 l.do_save(true)
                     .await
@@ -316,6 +425,56 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         lr
     }
 
+    /// Create a watch-only wallet, which has no seed. It follows the funds and history of the given Sapling
+    /// viewing keys and t-addresses, but can't spend them.
+    pub fn new_watch_only(
+        config: &LightClientConfig<P>,
+        viewing_keys: Vec<String>,
+        taddrs: Vec<String>,
+        birthday: u64,
+        overwrite: bool,
+    ) -> io::Result<Self> {
+        #[cfg(all(not(target_os = "ios"), not(target_os = "android")))]
+        {
+            if !overwrite && config.wallet_exists() {
+                return Err(Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("Cannot create a watch-only wallet, because a wallet already exists"),
+                ));
+            }
+        }
+
+        let lr = Runtime::new().unwrap().block_on(async move {
+            let l = LightClient {
+                wallet: LightWallet::new_watch_only(config.clone(), &viewing_keys, &taddrs, birthday)?,
+                config: config.clone(),
+                mempool_monitor: std::sync::RwLock::new(None),
+                broadcasts: Arc::new(RwLock::new(vec![])),
+                pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+                prover: ProverCache::new(),
+                sync_lock: Mutex::new(()),
+                bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
+            };
+
+            l.set_wallet_initial_state(birthday).await;
+            l.do_save(true)
+                .await
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            info!(
+                "Created watch-only wallet with {} viewing keys and {} t-addresses",
+                viewing_keys.len(),
+                taddrs.len()
+            );
+
+            Ok(l)
+        });
+
+        info!("Created LightClient to {}", &config.server);
+
+        lr
+    }
+
     pub fn read_from_buffer<R: Read>(config: &LightClientConfig<P>, mut reader: R) -> io::Result<Self> {
         let l = Runtime::new().unwrap().block_on(async move {
             let wallet = LightWallet::read(&mut reader, config).await?;
@@ -324,6 +483,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                 wallet,
                 config: config.clone(),
                 mempool_monitor: std::sync::RwLock::new(None),
+                broadcasts: Arc::new(RwLock::new(vec![])),
+                pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+                prover: ProverCache::new(),
                 sync_lock: Mutex::new(()),
                 bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
             };
@@ -356,6 +518,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                 wallet: wallet,
                 config: config.clone(),
                 mempool_monitor: std::sync::RwLock::new(None),
+                broadcasts: Arc::new(RwLock::new(vec![])),
+                pending_sends: Arc::new(RwLock::new(PendingSends::default())),
+                prover: ProverCache::new(),
                 sync_lock: Mutex::new(()),
                 bsync_data: Arc::new(RwLock::new(BlazeSyncData::new(&config))),
             };
@@ -589,6 +754,129 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         self.config.server.clone()
     }
 
+    // Every server a Tx is broadcast to: the wallet's own server first, then the extra broadcast servers
+    pub async fn get_broadcast_servers(&self) -> Vec<http::Uri> {
+        let mut servers = vec![self.get_server_uri()];
+        for server in self.wallet.get_broadcast_servers().await {
+            match server.parse::<http::Uri>() {
+                Ok(uri) if !servers.contains(&uri) => servers.push(uri),
+                Ok(_) => {}
+                Err(e) => warn!("Ignoring broadcast server {}: {}", server, e),
+            }
+        }
+
+        servers
+    }
+
+    // Broadcast a Tx to all the broadcast servers at once. The Tx is sent if any of them accepted it, and what
+    // each server said is kept for `do_check_propagation`.
+    async fn broadcast_tx(&self, tx_bytes: Box<[u8]>) -> Result<String, String> {
+        let servers = self.get_broadcast_servers().await;
+
+        let results = GrpcConnector::broadcast_transaction(servers, tx_bytes).await;
+        for (uri, result) in &results {
+            if let Err(e) = result {
+                warn!("Server {} didn't accept the tx: {}", uri, e);
+            }
+        }
+
+        match results.iter().find_map(|(_, r)| r.as_ref().ok().cloned()) {
+            Some(txid) => {
+                let results = results.into_iter().map(|(uri, r)| (uri.to_string(), r)).collect();
+
+                let mut broadcasts = self.broadcasts.write().await;
+                broadcasts.push((txid.clone(), results));
+                if broadcasts.len() > MAX_BROADCAST_RECORDS {
+                    broadcasts.remove(0);
+                }
+
+                Ok(txid)
+            }
+            None if results.len() == 1 => Err(results.into_iter().next().unwrap().1.unwrap_err()),
+            None => Err(format!(
+                "No server accepted the transaction. {}",
+                results
+                    .into_iter()
+                    .map(|(uri, r)| format!("{}: {}", uri, r.unwrap_err()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Check that a Tx we sent has reached the network, by asking every broadcast server for it with
+    /// GetTransaction until all of them have it or `timeout_secs` have passed. A server accepting the broadcast
+    /// doesn't mean it still has the Tx, so only what the servers return now is counted.
+    pub async fn do_check_propagation(&self, txid: &str, timeout_secs: u64) -> Result<JsonValue, String> {
+        let servers = self.get_broadcast_servers().await;
+
+        // Txids are shown with their bytes reversed
+        let txid = txid.trim();
+        let txid_hex = hex::decode(txid).map_err(|e| format!("Invalid txid {}: {}", txid, e))?;
+        if txid_hex.len() != 32 {
+            return Err(format!("Invalid txid {}", txid));
+        }
+        let mut txid_bytes = [0u8; 32];
+        txid_bytes.copy_from_slice(&txid_hex);
+        txid_bytes.reverse();
+        let tx_id = TxId::from_bytes(txid_bytes);
+
+        let mut seen = vec![false; servers.len()];
+        let start = now();
+        loop {
+            let checks = servers
+                .iter()
+                .zip(seen.iter())
+                .map(|(uri, seen)| {
+                    let uri = uri.clone();
+                    let seen = *seen;
+                    async move { seen || GrpcConnector::get_transaction(uri, &tx_id).await.is_ok() }
+                })
+                .collect::<Vec<_>>();
+            seen = futures::future::join_all(checks).await;
+
+            if !seen.contains(&false) || now() >= start + timeout_secs {
+                break;
+            }
+            sleep(Duration::from_secs(2)).await;
+        }
+
+        // What each server said when the Tx was broadcast, if it was sent in this session
+        let broadcasts = self
+            .broadcasts
+            .read()
+            .await
+            .iter()
+            .find(|(t, _)| t == txid)
+            .map(|(_, results)| results.clone())
+            .unwrap_or_default();
+        let propagated = !seen.contains(&false);
+        let seen_by = seen.iter().filter(|s| **s).count();
+        let servers = servers
+            .iter()
+            .zip(seen.iter())
+            .map(|(uri, seen)| {
+                let broadcast = match broadcasts.iter().find(|(server, _)| *server == uri.to_string()) {
+                    Some((_, Ok(_))) => "accepted".to_string(),
+                    Some((_, Err(e))) => e.clone(),
+                    None => "unknown".to_string(),
+                };
+                object! {
+                    "server"    => uri.to_string(),
+                    "broadcast" => broadcast,
+                    "seen"      => *seen,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(object! {
+            "txid"       => txid,
+            "propagated" => propagated,
+            "seen_by"    => seen_by,
+            "servers"    => servers,
+        })
+    }
+
     pub async fn do_zec_price(&self) -> String {
         let mut price = self.wallet.price.read().await.clone();
 
@@ -636,14 +924,25 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
     pub async fn do_send_progress(&self) -> Result<JsonValue, String> {
         let progress = self.wallet.get_send_progress().await;
 
-        Ok(object! {
+        Ok(Self::send_progress_json(&progress))
+    }
+
+    /// Get every update to the send progress from now on, as it happens: the phase the send is in, and while
+    /// proving, how many of the spends and outputs have been proven
+    pub fn subscribe_send_progress(&self) -> tokio::sync::broadcast::Receiver<SendProgress> {
+        self.wallet.subscribe_send_progress()
+    }
+
+    pub fn send_progress_json(progress: &SendProgress) -> JsonValue {
+        object! {
             "id" => progress.id,
             "sending" => progress.is_send_in_progress,
+            "phase" => progress.phase.to_string(),
             "progress" => progress.progress,
             "total" => progress.total,
-            "txid" => progress.last_txid,
-            "error" => progress.last_error,
-        })
+            "txid" => progress.last_txid.clone(),
+            "error" => progress.last_error.clone(),
+        }
     }
 
     pub fn do_seed_phrase_sync(&self) -> Result<JsonValue, &str> {
@@ -653,17 +952,30 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
     }
 
     pub async fn do_seed_phrase(&self) -> Result<JsonValue, &str> {
+        if self.wallet.keys().read().await.is_watch_only() {
+            return Err("This is a watch-only wallet, it has no seed phrase");
+        }
         if !self.wallet.is_unlocked_for_spending().await {
             error!("Wallet is locked");
             return Err("Wallet is locked");
         }
 
+        // The passphrase is never shown, it is the second factor protecting the seed phrase
         Ok(object! {
             "seed"     => self.wallet.keys().read().await.get_seed_phrase(),
-            "birthday" => self.wallet.get_birthday().await
+            "birthday" => self.wallet.get_birthday().await,
+            "has_passphrase" => self.wallet.keys().read().await.has_passphrase(),
         })
     }
 
+    // Enter the BIP39 passphrase of a wallet that isn't encrypted, which doesn't save it. It is only needed to
+    // derive new addresses.
+    pub async fn do_set_passphrase(&self, passphrase: String) -> Result<JsonValue, String> {
+        self.wallet.keys().write().await.set_passphrase(passphrase)?;
+
+        Ok(object! { "result" => "success" })
+    }
+
     // Return a list of all notes, spent and unspent
     pub async fn do_list_notes(&self, all_notes: bool) -> JsonValue {
         let mut unspent_notes: Vec<JsonValue> = vec![];
@@ -743,7 +1055,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                             let address = LightWallet::<P>::sapling_note_address(self.config.hrp_sapling_address(), nd);
                             let spendable = address.is_some() &&
                                                     spendable_address.contains(&address.clone().unwrap()) &&
-                                                    wtx.block <= anchor_height && nd.spent.is_none() && nd.unconfirmed_spent.is_none();
+                                                    wtx.block <= anchor_height && nd.spent.is_none() && nd.unconfirmed_spent.is_none() &&
+                                                    !nd.frozen;
 
                             let created_block:u32 = wtx.block.into();
                             Some(object!{
@@ -755,6 +1068,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                                 "is_change"          => nd.is_change,
                                 "address"            => address,
                                 "spendable"          => spendable,
+                                "input_id"           => nd.input_id(),
+                                "frozen"             => nd.frozen,
                                 "spent"              => nd.spent.map(|(spent_txid, _)| format!("{}", spent_txid)),
                                 "spent_at_height"    => nd.spent.map(|(_, h)| h),
                                 "unconfirmed_spent"  => nd.unconfirmed_spent.map(|(spent_txid, _)| format!("{}", spent_txid)),
@@ -794,6 +1109,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                                 "scriptkey"          => hex::encode(utxo.script.clone()),
                                 "is_change"          => false, // TODO: Identify notes as change if we send change to our own taddrs
                                 "address"            => utxo.address.clone(),
+                                "input_id"           => utxo.input_id(),
+                                "frozen"             => utxo.frozen,
                                 "spent_at_height"    => utxo.spent_at_height,
                                 "spent"              => utxo.spent.map(|spent_txid| format!("{}", spent_txid)),
                                 "unconfirmed_spent"  => utxo.unconfirmed_spent.map(|(spent_txid, _)| format!("{}", spent_txid)),
@@ -827,6 +1144,57 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         res
     }
 
+    /// List the notes and UTXOs that can be spent right now. The "input_id" of each entry can be passed in
+    /// `SendOptions::inputs` to spend exactly those inputs.
+    pub async fn do_list_spendable_inputs(&self) -> JsonValue {
+        let (notes, utxos) = self.wallet.get_spendable_inputs().await;
+
+        let notes = notes
+            .into_iter()
+            .map(|(sn, address)| {
+                object! {
+                    "input_id"        => sn.input_id(),
+                    "type"            => "sapling",
+                    "created_in_txid" => format!("{}", sn.txid),
+                    "address"         => address,
+                    "value"           => sn.note.value,
+                }
+            })
+            .collect::<Vec<JsonValue>>();
+
+        let utxos = utxos
+            .into_iter()
+            .map(|utxo| {
+                object! {
+                    "input_id"         => utxo.input_id(),
+                    "type"             => "transparent",
+                    "created_in_txid"  => format!("{}", utxo.txid),
+                    "created_in_block" => utxo.height,
+                    "address"          => utxo.address.clone(),
+                    "value"            => utxo.value,
+                }
+            })
+            .collect::<Vec<JsonValue>>();
+
+        object! {
+            "notes" => notes,
+            "utxos" => utxos,
+        }
+    }
+
+    /// Freeze or unfreeze the notes and UTXOs with the given input ids. Frozen inputs are never spent, until they
+    /// are unfrozen again. The flag is saved in the wallet file.
+    pub async fn do_freeze_inputs(&self, input_ids: Vec<String>, frozen: bool) -> Result<JsonValue, String> {
+        let changed = self.wallet.set_inputs_frozen(&input_ids, frozen).await?;
+        self.do_save(true).await?;
+
+        Ok(object! {
+            "result"  => "success",
+            "frozen"  => frozen,
+            "changed" => changed,
+        })
+    }
+
     pub fn do_encrypt_message(&self, to_address_str: String, memo: Memo) -> JsonValue {
         let to = match decode_payment_address(self.config.hrp_sapling_address(), &to_address_str) {
             Ok(Some(to)) => to,
@@ -866,8 +1234,10 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
 
     pub async fn do_encryption_status(&self) -> JsonValue {
         object! {
-            "encrypted" => self.wallet.is_encrypted().await,
-            "locked"    => !self.wallet.is_unlocked_for_spending().await
+            "encrypted"  => self.wallet.is_encrypted().await,
+            "locked"     => !self.wallet.is_unlocked_for_spending().await,
+            "watch_only" => self.wallet.keys().read().await.is_watch_only(),
+            "needs_passphrase" => self.wallet.keys().read().await.needs_passphrase(),
         }
     }
 
@@ -927,6 +1297,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                         "txid"         => format!("{}", v.txid),
                         "zec_price"    => v.zec_price.map(|p| (p * 100.0).round() / 100.0),
                         "amount"       => total_change as i64 - v.total_funds_spent() as i64,
+                        "auto_shield"  => v.auto_shield,
                         "outgoing_metadata" => outgoing_json,
                     });
                 }
@@ -1046,13 +1417,14 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                 }
             };
 
-            if addr.starts_with("Error") {
-                let e = format!("Error creating new address: {}", addr);
-                error!("{}", e);
-                return Err(e);
+            match addr {
+                Ok(addr) => addr,
+                Err(e) => {
+                    let e = format!("Error creating new address: {}", e);
+                    error!("{}", e);
+                    return Err(e);
+                }
             }
-
-            addr
         };
 
         self.do_save(true).await?;
@@ -1060,6 +1432,184 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         Ok(array![new_address])
     }
 
+    // Create a new diversified address for the key of `zaddr` (or the wallet's first HD key). This only needs
+    // the viewing key, so it works on a locked wallet too.
+    pub async fn do_new_diversified_address(&self, zaddr: Option<&str>) -> Result<JsonValue, String> {
+        let new_address = self.wallet.keys().write().await.add_diversified_zaddr(zaddr)?;
+
+        self.do_save(true).await?;
+
+        Ok(array![new_address])
+    }
+
+    fn account_json(account: &WalletAccount, zaddrs: Vec<String>, taddrs: Vec<String>) -> JsonValue {
+        object! {
+            "id"          => account.id,
+            "name"        => account.name.clone(),
+            "z_addresses" => zaddrs,
+            "t_addresses" => taddrs,
+        }
+    }
+
+    pub async fn do_accounts(&self) -> JsonValue {
+        let keys = self.wallet.keys().read().await;
+
+        let accounts = keys
+            .get_accounts()
+            .iter()
+            .map(|a| {
+                let (zaddrs, taddrs) = keys.get_account_addresses(a.id);
+                Self::account_json(a, zaddrs, taddrs)
+            })
+            .collect::<Vec<_>>();
+
+        JsonValue::Array(accounts)
+    }
+
+    // Create a new account, with a fresh z and t address derived from the seed
+    pub async fn do_new_account(&self, name: &str) -> Result<JsonValue, String> {
+        if !self.wallet.is_unlocked_for_spending().await {
+            error!("Wallet is locked");
+            return Err("Wallet is locked".to_string());
+        }
+
+        let account = {
+            let mut keys = self.wallet.keys().write().await;
+            let id = keys.add_account(name)?;
+            let (zaddrs, taddrs) = keys.get_account_addresses(id);
+            Self::account_json(keys.get_account(id).unwrap(), zaddrs, taddrs)
+        };
+
+        self.do_save(true).await?;
+
+        Ok(account)
+    }
+
+    pub async fn do_rename_account(&self, account: u32, name: &str) -> Result<JsonValue, String> {
+        self.wallet.keys().write().await.rename_account(account, name)?;
+
+        self.do_save(true).await?;
+
+        Ok(object! { "result" => "success" })
+    }
+
+    // Create a new z or t address in an account
+    pub async fn do_new_account_address(&self, account: u32, addr_type: &str) -> Result<JsonValue, String> {
+        if !self.wallet.is_unlocked_for_spending().await {
+            error!("Wallet is locked");
+            return Err("Wallet is locked".to_string());
+        }
+
+        let addr = match addr_type {
+            "z" => self.wallet.keys().write().await.add_zaddr_to_account(account),
+            "t" => self.wallet.keys().write().await.add_taddr_to_account(account),
+            _ => return Err(format!("Unrecognized address type: {}", addr_type)),
+        }
+        .map_err(|e| format!("Error creating new address: {}", e))?;
+
+        self.do_save(true).await?;
+
+        Ok(array![addr])
+    }
+
+    async fn account_addresses(&self, account: u32) -> Result<(Vec<String>, Vec<String>), String> {
+        let keys = self.wallet.keys().read().await;
+        match keys.get_account(account) {
+            Some(_) => Ok(keys.get_account_addresses(account)),
+            None => Err(format!("No account {}", account)),
+        }
+    }
+
+    // The balance of an account, which is the sum of the balances of its addresses
+    pub async fn do_account_balance(&self, account: u32) -> Result<JsonValue, String> {
+        let (zaddrs, taddrs) = self.account_addresses(account).await?;
+
+        let mut zbalance = 0;
+        let mut verified_zbalance = 0;
+        let mut spendable_zbalance = 0;
+        let mut unverified_zbalance = 0;
+        for zaddr in zaddrs {
+            zbalance += self.wallet.zbalance(Some(zaddr.clone())).await;
+            verified_zbalance += self.wallet.verified_zbalance(Some(zaddr.clone())).await;
+            spendable_zbalance += self.wallet.spendable_zbalance(Some(zaddr.clone())).await;
+            unverified_zbalance += self.wallet.unverified_zbalance(Some(zaddr)).await;
+        }
+
+        let mut tbalance = 0;
+        let mut spendable_tbalance = 0;
+        for taddr in taddrs {
+            tbalance += self.wallet.tbalance(Some(taddr.clone())).await;
+            spendable_tbalance += self.wallet.spendable_tbalance(Some(taddr)).await;
+        }
+
+        Ok(object! {
+            "account"            => account,
+            "zbalance"           => zbalance,
+            "verified_zbalance"  => verified_zbalance,
+            "spendable_zbalance" => spendable_zbalance,
+            "unverified_zbalance" => unverified_zbalance,
+            "tbalance"           => tbalance,
+            "spendable_tbalance" => spendable_tbalance,
+        })
+    }
+
+    // The transactions of an account: the ones received at its addresses, and the sends that spent its funds
+    pub async fn do_list_account_transactions(
+        &self,
+        account: u32,
+        include_memo_hex: bool,
+    ) -> Result<JsonValue, String> {
+        let (zaddrs, taddrs) = self.account_addresses(account).await?;
+        let addresses = zaddrs.into_iter().chain(taddrs).collect::<HashSet<_>>();
+
+        // The txids that spent a note or UTXO of the account
+        let spending_txids = {
+            let txns = self.wallet.txns.read().await;
+            let mut spending_txids = HashSet::new();
+            for wtx in txns.current.values() {
+                for nd in wtx.s_notes.iter() {
+                    let address = LightWallet::<P>::sapling_note_address(self.config.hrp_sapling_address(), nd);
+                    if address.map_or(false, |a| addresses.contains(&a)) {
+                        spending_txids.extend(
+                            nd.spent
+                                .iter()
+                                .chain(nd.unconfirmed_spent.iter())
+                                .map(|(txid, _)| *txid),
+                        );
+                    }
+                }
+                for utxo in wtx.utxos.iter().filter(|u| addresses.contains(&u.address)) {
+                    spending_txids.extend(
+                        utxo.spent
+                            .iter()
+                            .copied()
+                            .chain(utxo.unconfirmed_spent.iter().map(|(txid, _)| *txid)),
+                    );
+                }
+            }
+            spending_txids
+                .into_iter()
+                .map(|txid| format!("{}", txid))
+                .collect::<HashSet<_>>()
+        };
+
+        let tx_list = match self.do_list_transactions(include_memo_hex).await {
+            JsonValue::Array(tx_list) => tx_list,
+            _ => vec![],
+        };
+
+        Ok(JsonValue::Array(
+            tx_list
+                .into_iter()
+                .filter(|tx| match tx["address"].as_str() {
+                    // Transparent receives list all the addresses of the Tx, separated by commas
+                    Some(address) => address.split(',').any(|a| addresses.contains(a)),
+                    None => tx["txid"].as_str().map_or(false, |txid| spending_txids.contains(txid)),
+                })
+                .collect(),
+        ))
+    }
+
     /// Convinence function to determine what type of key this is and import it
     pub async fn do_import_key(&self, key: String, birthday: u64) -> Result<JsonValue, String> {
         if key.starts_with(self.config.hrp_sapling_private_key()) {
@@ -1078,6 +1628,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
 
     /// Import a new transparent private key
     pub async fn do_import_tk(&self, sk: String) -> Result<JsonValue, String> {
+        if self.wallet.keys().read().await.is_watch_only() {
+            return Err("Can't import a spending key into a watch-only wallet".to_string());
+        }
         if !self.wallet.is_unlocked_for_spending().await {
             error!("Wallet is locked");
             return Err("Wallet is locked".to_string());
@@ -1096,6 +1649,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
 
     /// Import a new z-address private key
     pub async fn do_import_sk(&self, sk: String, birthday: u64) -> Result<JsonValue, String> {
+        if self.wallet.keys().read().await.is_watch_only() {
+            return Err("Can't import a spending key into a watch-only wallet".to_string());
+        }
         if !self.wallet.is_unlocked_for_spending().await {
             error!("Wallet is locked");
             return Err("Wallet is locked".to_string());
@@ -1279,7 +1835,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                             let price = price.read().await.clone();
                             //info!("Mempool attempting to scan {}", tx.txid());
 
-                            FetchFullTxns::<P>::scan_full_tx(
+                            // The sync reports this again once the Tx is mined
+                            if let Err(e) = FetchFullTxns::<P>::scan_full_tx(
                                 config.clone(),
                                 tx,
                                 // For unconfirmed mempool transactions, use height 0 to ensure they're excluded from spendable balance
@@ -1290,7 +1847,10 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                                 wallet_txns.clone(),
                                 WalletTx::get_price(now(), &price),
                             )
-                            .await;
+                            .await
+                            {
+                                warn!("{}", e);
+                            }
                         }
                     }
                 });
@@ -1321,7 +1881,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         let prev_sync_id = self.bsync_data.read().await.sync_status.read().await.sync_id;
 
         // Start the sync
-        let r_fut = self.start_sync();
+        let r_fut = self.sync_and_discover();
 
         // If printing updates, start a new task to print updates every 2 seconds.
         let sync_result = if print_updates {
@@ -1359,6 +1919,17 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         // Mark the sync data as finished, which should clear everything
         self.bsync_data.read().await.finish().await;
 
+        // Shield the transparent funds, if the auto-shield policy says so
+        let sync_result = match sync_result {
+            Ok(mut j) => {
+                if let Some(shield_result) = self.do_auto_shield().await {
+                    j["auto_shield"] = shield_result;
+                }
+                Ok(j)
+            }
+            Err(e) => Err(e),
+        };
+
         // Save wallet after sync completes to persist state
         if let Err(e) = self.do_save(true).await {
             error!("Failed to save wallet after sync: {}", e);
@@ -1367,6 +1938,35 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         sync_result
     }
 
+    // Sync, and while a restored wallet is still discovering its accounts and HD addresses, add them until the last
+    // GAP_RULE_UNUSED_ACCOUNTS accounts and the last GAP_RULE_UNUSED_ADDRESSES addresses of each type are unused.
+    // Addresses added after a sync missed the blocks it scanned, and any of them could have received in any block
+    // since the birthday, so whenever any were added, the wallet is rescanned from its birthday. This goes on until
+    // a sync finds nothing new, however many rescans that takes.
+    async fn sync_and_discover(&self) -> Result<JsonValue, String> {
+        let mut pass = 0;
+        loop {
+            pass += 1;
+
+            let r = self.start_sync().await;
+            if r.is_err() || !self.wallet.is_hd_discovery_pending() {
+                return r;
+            }
+
+            // If the new addresses can't be derived yet, discovery stays pending until the passphrase is entered
+            let added = self.wallet.discover_hd_addresses().await?;
+
+            if added == 0 {
+                info!("HD address discovery finished after {} syncs", pass);
+                self.wallet.finish_hd_discovery().await;
+                return r;
+            }
+
+            info!("Found {} more HD addresses, rescanning from the birthday", added);
+            self.clear_state().await;
+        }
+    }
+
     /// Start syncing in batches with the max size, so we don't consume memory more than
     // wha twe can handle.
     async fn start_sync(&self) -> Result<JsonValue, String> {
@@ -1532,7 +2132,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
                 self.wallet.get_blocks().await,
                 self.wallet.verified_tree.read().await.clone(),
                 self.wallet.orchard_witnesses.clone(),
-                *self.wallet.wallet_options.read().await,
+                self.wallet.wallet_options.read().await.clone(),
             )
             .await;
 
@@ -1676,7 +2276,11 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
         // 5. Remove expired mempool transactions, if any
         self.wallet.txns().write().await.clear_expired_mempool(latest_block);
 
-        // 6. Set the heighest verified tree
+        // 6. Drop the txns we sent that expired without being mined, which releases the funds they spent,
+        // and rebroadcast the ones that are still pending
+        let tx_state_changes = self.update_pending_sends(latest_block).await;
+
+        // 7. Set the heighest verified tree
         if heighest_tree.is_some() {
             *self.wallet.verified_tree.write().await = heighest_tree;
         }
@@ -1685,86 +2289,861 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightClient<P> {
             "result" => "success",
             "latest_block" => latest_block,
             "total_blocks_synced" => start_block - end_block + 1,
+            "tx_state_changes" => tx_state_changes,
         })
     }
 
-    pub async fn do_shield(&self, address: Option<String>) -> Result<String, String> {
-        let fee = u64::from(DEFAULT_FEE);
-        let tbal = self.wallet.tbalance(None).await;
+    async fn update_pending_sends(&self, latest_block: u64) -> Vec<JsonValue> {
+        let expired = self.wallet.txns().write().await.remove_expired_sends(latest_block);
+        let pending = self.wallet.txns().read().await.get_pending_broadcasts();
 
-        // Make sure there is a balance, and it is greated than the amount
-        if tbal <= fee {
-            return Err(format!(
-                "Not enough transparent balance to shield. Have {} zats, need more than {} zats to cover tx fee",
-                tbal, fee
-            ));
+        let rebroadcast = {
+            let mut state = self.pending_sends.write().await;
+            for txid in &expired {
+                state.expired.push((txid.to_string(), latest_block));
+            }
+            if state.expired.len() > MAX_BROADCAST_RECORDS {
+                let n = state.expired.len() - MAX_BROADCAST_RECORDS;
+                state.expired.drain(..n);
+            }
+            state
+                .rebroadcasts
+                .retain(|txid, _| pending.iter().any(|(t, _, _)| t.to_string() == *txid));
+
+            // Only rebroadcast once per new block, so a sync that finds no new blocks doesn't resend everything
+            if latest_block > state.rebroadcast_height {
+                state.rebroadcast_height = latest_block;
+                true
+            } else {
+                false
+            }
+        };
+
+        if rebroadcast && !pending.is_empty() {
+            // Every pending Tx goes out at the same time, each to all the broadcast servers
+            let results = futures::future::join_all(
+                pending
+                    .iter()
+                    .map(|(_, raw_tx, _)| self.broadcast_tx(raw_tx.clone().into_boxed_slice())),
+            )
+            .await;
+
+            let mut state = self.pending_sends.write().await;
+            for ((txid, _, _), result) in pending.iter().zip(results) {
+                match &result {
+                    Ok(_) => info!("Rebroadcast pending tx {}", txid),
+                    // The servers might reject it because it is already in their mempool, so this is not an error
+                    Err(e) => warn!("Couldn't rebroadcast pending tx {}: {}", txid, e),
+                }
+
+                let entry = state.rebroadcasts.entry(txid.to_string()).or_insert((0, None));
+                entry.0 += 1;
+                entry.1 = result.err();
+            }
         }
 
-        let addr = address
-            .or(self
-                .wallet
-                .keys()
-                .read()
-                .await
-                .get_all_zaddresses()
-                .get(0)
-                .map(|s| s.clone()))
-            .unwrap();
+        expired
+            .into_iter()
+            .map(|txid| {
+                object! {
+                    "txid"  => format!("{}", txid),
+                    "state" => "expired",
+                }
+            })
+            .collect()
+    }
+
+    /// The Txs we sent that aren't mined yet, with their expiry height and how often they were rebroadcast, and
+    /// the ones that expired without being mined in this session
+    pub async fn do_pending_sends(&self) -> JsonValue {
+        let latest_block = self.wallet.last_scanned_height().await;
+        let pending = self.wallet.txns().read().await.get_pending_broadcasts();
+        let state = self.pending_sends.read().await;
+
+        let pending = pending
+            .into_iter()
+            .map(|(txid, _, expiry_height)| {
+                let (rebroadcasts, last_error) = state.rebroadcasts.get(&txid.to_string()).cloned().unwrap_or_default();
+                object! {
+                    "txid"                   => format!("{}", txid),
+                    "expiry_height"          => expiry_height,
+                    "blocks_until_expiry"    => (expiry_height as u64).saturating_sub(latest_block),
+                    "rebroadcasts"           => rebroadcasts,
+                    "last_rebroadcast_error" => last_error,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let expired = state
+            .expired
+            .iter()
+            .map(|(txid, height)| {
+                object! {
+                    "txid"       => txid.clone(),
+                    "expired_at" => *height,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        object! {
+            "last_rebroadcast_height" => state.rebroadcast_height,
+            "pending"                 => pending,
+            "expired"                 => expired,
+        }
+    }
+
+    /// Shield the transparent funds of every t-address, each in its own transaction, to `address` or else to the
+    /// z-address picked by the change policy. Returns the result for every t-address.
+    pub async fn do_shield(&self, address: Option<String>) -> Result<JsonValue, String> {
+        self.do_shield_from(None, address).await
+    }
+
+    pub async fn do_send(
+        &self,
+        addrs: Vec<(&str, u64, Option<String>)>,
+        options: SendOptions,
+    ) -> Result<String, String> {
+        self.do_send_raw(addrs, options).await.map(|(txid, _)| txid)
+    }
+
+    /// Same as `do_send`, but also returns the raw bytes of the signed transaction that was broadcast
+    pub async fn do_send_raw(
+        &self,
+        addrs: Vec<(&str, u64, Option<String>)>,
+        options: SendOptions,
+    ) -> Result<(String, Vec<u8>), String> {
+        let prover = self.sapling_prover().await?;
+        self.send_with(prover, addrs, options).await
+    }
+
+    // Send with any prover. Everything that sends from this client goes through here, so the tests run the same
+    // code with a fake prover.
+    async fn send_with<PR: TxProver>(
+        &self,
+        prover: PR,
+        addrs: Vec<(&str, u64, Option<String>)>,
+        options: SendOptions,
+    ) -> Result<(String, Vec<u8>), String> {
+        info!("Creating transaction");
 
         let result = {
             let _lock = self.sync_lock.lock().await;
-            let (sapling_output, sapling_spend) = self.read_sapling_params()?;
-
-            let prover = LocalTxProver::from_bytes(&sapling_spend, &sapling_output);
 
             self.wallet
-                .send_to_address(prover, true, vec![(&addr, tbal - fee, None)], |txbytes| {
-                    GrpcConnector::send_transaction(self.get_server_uri(), txbytes)
-                })
+                .send_to_address(prover, false, options, addrs, |txbytes| self.broadcast_tx(txbytes))
                 .await
         };
 
-        result.map(|(txid, _)| txid)
+        self.save_fresh_change_address().await;
+        result
     }
 
-    pub async fn do_send(&self, addrs: Vec<(&str, u64, Option<String>)>) -> Result<String, String> {
-        info!("Creating transaction");
+    /// Merge the smallest `max_notes` notes (or all of them) into one note to self per batch, so later sends
+    /// don't have to spend lots of tiny notes. With `preview`, only show the batches and what they'd cost.
+    /// Batches that would leave less than `dust_threshold` (the fee, by default) after the fee are skipped.
+    pub async fn do_consolidate(
+        &self,
+        max_notes: Option<usize>,
+        dust_threshold: Option<u64>,
+        preview: bool,
+    ) -> Result<JsonValue, String> {
+        let dust_threshold = dust_threshold.unwrap_or(u64::from(DEFAULT_FEE));
+
+        // Only load the prover if there is something to send
+        let (batches, skipped) = self.wallet.plan_consolidation(max_notes, dust_threshold).await?;
+        if preview || batches.is_empty() {
+            return Ok(Self::consolidation_result(&batches, &skipped, vec![], None));
+        }
 
-        // println!("BranchID {:x}", branch_id);
+        let prover = self.sapling_prover().await?;
+        self.consolidate_with(prover, max_notes, dust_threshold).await
+    }
 
-        let result = {
-            let _lock = self.sync_lock.lock().await;
-            let (sapling_output, sapling_spend) = self.read_sapling_params()?;
+    async fn consolidate_with<PR: TxProver + Clone>(
+        &self,
+        prover: PR,
+        max_notes: Option<usize>,
+        dust_threshold: u64,
+    ) -> Result<JsonValue, String> {
+        let (batches, skipped) = self.wallet.plan_consolidation(max_notes, dust_threshold).await?;
+
+        let mut txids = vec![];
+        let mut error = None;
+        for batch in batches.iter() {
+            match self
+                .send_with(
+                    prover.clone(),
+                    vec![(batch.to.as_str(), 0, None)],
+                    Self::consolidation_options(batch),
+                )
+                .await
+            {
+                Ok((txid, _)) => txids.push(txid),
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
 
-            let prover = LocalTxProver::from_bytes(&sapling_spend, &sapling_output);
+        Ok(Self::consolidation_result(&batches, &skipped, txids, error))
+    }
 
-            self.wallet
-                .send_to_address(prover, false, addrs, |txbytes| {
-                    GrpcConnector::send_transaction(self.get_server_uri(), txbytes)
-                })
+    // A consolidation batch is sent as a send-max of exactly its notes, so there is no change
+    fn consolidation_options(batch: &ConsolidationBatch) -> SendOptions {
+        SendOptions {
+            inputs: Some(batch.input_ids.clone()),
+            send_max: true,
+            ..Default::default()
+        }
+    }
+
+    fn consolidation_result(
+        batches: &Vec<ConsolidationBatch>,
+        skipped: &Vec<ConsolidationBatch>,
+        txids: Vec<String>,
+        error: Option<String>,
+    ) -> JsonValue {
+        let batch_json = |b: &ConsolidationBatch| {
+            object! {
+                "notes"              => b.input_ids.len(),
+                "value"              => b.value,
+                "fee"                => u64::from(DEFAULT_FEE),
+                "consolidated_value" => b.consolidated_value(),
+                "to"                 => b.to.clone(),
+            }
+        };
+
+        let mut result = object! {
+            "batches"          => batches.iter().map(batch_json).collect::<Vec<_>>(),
+            "skipped"          => skipped.iter().map(batch_json).collect::<Vec<_>>(),
+            "notes_to_merge"   => batches.iter().map(|b| b.input_ids.len()).sum::<usize>(),
+            "resulting_notes"  => batches.len(),
+            "total_fee"        => batches.len() as u64 * u64::from(DEFAULT_FEE),
+            "txids"            => txids,
+        };
+        if let Some(e) = error {
+            result["error"] = e.into();
+        }
+
+        result
+    }
+
+    /// The scheduled payments that are due now, i.e., at the next block or at the current time
+    pub async fn do_due_payments(&self) -> JsonValue {
+        let due = self.wallet.due_payments(now()).await;
+        JsonValue::Array(due.iter().map(Self::scheduled_payment_json).collect())
+    }
+
+    /// Pay every scheduled payment that is due, one Tx per scheduled payment. Each one that was paid is moved on to
+    /// its next due date, and remembers the txid that paid it.
+    pub async fn do_pay_due_payments(&self) -> Result<JsonValue, String> {
+        // Only load the prover if there is something to pay
+        if self.wallet.due_payments(now()).await.is_empty() {
+            return Ok(JsonValue::Array(vec![]));
+        }
+
+        let prover = self.sapling_prover().await?;
+        self.pay_due_payments_with(prover).await
+    }
+
+    async fn pay_due_payments_with<PR: TxProver + Clone>(&self, prover: PR) -> Result<JsonValue, String> {
+        if !self.wallet.is_unlocked_for_spending().await {
+            error!("Wallet is locked");
+            return Err("Wallet is locked".to_string());
+        }
+
+        let mut results = vec![];
+        for payment in self.wallet.due_payments(now()).await {
+            let result = self
+                .send_with(
+                    prover.clone(),
+                    Self::scheduled_payment_tos(&payment),
+                    SendOptions::default(),
+                )
                 .await
+                .map(|(txid, _)| txid);
+            results.push(self.scheduled_payment_result(&payment, result).await);
+        }
+
+        Ok(JsonValue::Array(results))
+    }
+
+    fn scheduled_payment_tos(payment: &lightwallet::schedule::ScheduledPayment) -> Vec<(&str, u64, Option<String>)> {
+        payment
+            .recipients
+            .iter()
+            .map(|r| (r.address.as_str(), r.value, r.memo.clone()))
+            .collect()
+    }
+
+    async fn scheduled_payment_result(
+        &self,
+        payment: &lightwallet::schedule::ScheduledPayment,
+        result: Result<String, String>,
+    ) -> JsonValue {
+        match result {
+            Ok(txid) => {
+                self.wallet
+                    .record_scheduled_payment(payment.id, txid.clone(), now())
+                    .await;
+                object! { "id" => payment.id, "txid" => txid }
+            }
+            Err(e) => object! { "id" => payment.id, "error" => e },
+        }
+    }
+
+    pub fn scheduled_payment_json(payment: &lightwallet::schedule::ScheduledPayment) -> JsonValue {
+        let due_json = |due: &lightwallet::schedule::DueAt| match due {
+            lightwallet::schedule::DueAt::Height(h) => object! { "height" => *h },
+            lightwallet::schedule::DueAt::Time(t) => object! { "time" => *t },
         };
 
-        result.map(|(txid, _)| txid)
+        object! {
+            "id"         => payment.id,
+            "recipients" => payment.recipients.iter().map(|r| object! {
+                "address" => r.address.clone(),
+                "amount"  => r.value,
+                "memo"    => r.memo.clone(),
+            }).collect::<Vec<_>>(),
+            "total"      => payment.total_value(),
+            "next_due"   => due_json(&payment.next_due),
+            "interval"   => payment.interval,
+            "finished"   => payment.finished,
+            "history"    => payment.history.iter().map(|h| object! {
+                "due"  => due_json(&h.due),
+                "txid" => h.txid.clone(),
+            }).collect::<Vec<_>>(),
+        }
     }
 
-    #[cfg(test)]
-    pub async fn test_do_send(&self, addrs: Vec<(&str, u64, Option<String>)>) -> Result<String, String> {
-        info!("Creating transaction");
+    /// Pay every row of a batch file (CSV or JSON), in as many Txs as it takes to keep each one under
+    /// `MAX_BATCH_OUTPUTS` outputs. All the rows are checked first, and nothing is sent if any of them is invalid or
+    /// the wallet can't pay all of them. Rows paid by an earlier run of the same file are skipped, so a batch that
+    /// stopped part way can be run again. Returns a report with the result of every row.
+    pub async fn do_send_batch(&self, path: &str) -> Result<JsonValue, String> {
+        let prover = self.sapling_prover().await?;
+        self.send_batch_with(prover, path).await
+    }
+
+    async fn send_batch_with<PR: TxProver + Clone>(&self, prover: PR, path: &str) -> Result<JsonValue, String> {
+        let (batch_id, mut rows) = self.read_batch_file(path)?;
+
+        for (row, txid) in self.wallet.batch_records.read().await.sent_rows(&batch_id) {
+            if let Some(r) = rows.iter_mut().find(|r| r.row == row) {
+                r.txid = Some(txid);
+                r.already_sent = true;
+            }
+        }
+
+        if rows.iter().any(|r| r.error.is_some()) {
+            return Ok(Self::batch_report(&rows));
+        }
+
+        let unsent = (0..rows.len()).filter(|i| rows[*i].txid.is_none()).collect::<Vec<_>>();
+        let chunks = unsent.chunks(lightwallet::batch::MAX_BATCH_OUTPUTS).collect::<Vec<_>>();
+
+        // Check the whole batch can be paid before paying any of it
+        let total = unsent.iter().map(|i| rows[*i].value).sum::<u64>();
+        let fees = chunks.len() as u64 * u64::from(DEFAULT_FEE);
+        let spendable = self.wallet.spendable_value(&SendOptions::default()).await?;
+        if total + fees > spendable {
+            return Err(format!(
+                "Not enough funds for the batch. Its {} unpaid rows need {} zats plus {} in fees, but {} can be spent",
+                unsent.len(),
+                total,
+                fees,
+                spendable
+            ));
+        }
+
+        for chunk in chunks {
+            let tos = chunk
+                .iter()
+                .map(|i| (rows[*i].address.as_str(), rows[*i].value, rows[*i].memo.clone()))
+                .collect();
+            let result = self
+                .send_with(prover.clone(), tos, SendOptions::default())
+                .await
+                .map(|(txid, _)| txid);
+            let failed = result.is_err();
+
+            for i in chunk {
+                match &result {
+                    Ok(txid) => rows[*i].txid = Some(txid.clone()),
+                    Err(e) => rows[*i].error = Some(e.clone()),
+                }
+            }
+            if failed {
+                // The rest are left unsent, for the next run
+                break;
+            }
+
+            let paid = chunk.iter().map(|i| &rows[*i]).collect::<Vec<_>>();
+            self.wallet.batch_records.write().await.record(&batch_id, &paid);
+        }
+
+        if let Err(e) = self.do_save(true).await {
+            warn!("Couldn't save the wallet after sending a batch: {}", e);
+        }
+
+        Ok(Self::batch_report(&rows))
+    }
+
+    // Read and parse a batch file, and check every row that could be parsed. Also returns the id of the batch.
+    fn read_batch_file(&self, path: &str) -> Result<(String, Vec<lightwallet::batch::BatchRow>), String> {
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| format!("Couldn't read the batch file {}: {}", path, e))?;
+
+        let mut rows = lightwallet::batch::parse_batch(&contents)?;
+        for row in rows.iter_mut().filter(|r| r.error.is_none()) {
+            if let Err(e) = self.wallet.check_recipient(&row.address, row.value, row.memo.as_ref()) {
+                row.error = Some(e);
+            }
+        }
+
+        Ok((lightwallet::batch::BatchRecords::batch_id(&contents), rows))
+    }
+
+    fn batch_report(rows: &Vec<lightwallet::batch::BatchRow>) -> JsonValue {
+        let mut txids = vec![];
+        for txid in rows.iter().filter_map(|r| r.txid.clone()) {
+            if !txids.contains(&txid) {
+                txids.push(txid);
+            }
+        }
+
+        object! {
+            "rows"         => rows.iter().map(|r| r.to_json()).collect::<Vec<_>>(),
+            "txids"        => txids,
+            "sent"         => rows.iter().filter(|r| r.txid.is_some()).count(),
+            "already_sent" => rows.iter().filter(|r| r.already_sent).count(),
+            "errors"       => rows.iter().filter(|r| r.error.is_some()).count(),
+            "total_sent"   => rows.iter().filter(|r| r.txid.is_some()).map(|r| r.value).sum::<u64>(),
+        }
+    }
+
+    // Shield the UTXOs picked by the auto-shield policy, one t-address at a time. Returns None if there was nothing
+    // to shield, else the result for every t-address, or why it was skipped for now.
+    async fn do_auto_shield(&self) -> Option<JsonValue> {
+        self.auto_shield_with(self.sapling_prover()).await
+    }
+
+    // The prover is only loaded, by awaiting `load_prover`, if there is something to shield
+    async fn auto_shield_with<PR: TxProver + Clone, F: Future<Output = Result<PR, String>>>(
+        &self,
+        load_prover: F,
+    ) -> Option<JsonValue> {
+        let utxos = self.wallet.get_auto_shield_utxos().await;
+        if utxos.is_empty() {
+            return None;
+        }
+
+        // This runs after every sync, so rather than fail on every sync, wait until it can work
+        let height = self.wallet.last_scanned_height().await;
+        let skipped = if self.wallet.get_send_progress().await.is_send_in_progress {
+            Some("A send is in progress")
+        } else if !self.wallet.is_unlocked_for_spending().await {
+            Some("The wallet is locked")
+        } else if self.wallet.is_auto_shield_backing_off(height).await {
+            Some("Waiting to try again after the last attempt failed")
+        } else {
+            None
+        };
+        if let Some(reason) = skipped {
+            info!("Not auto-shielding now: {}", reason);
+            return Some(object! { "skipped" => reason });
+        }
+
+        let prover = match load_prover.await {
+            Ok(prover) => prover,
+            Err(e) => {
+                warn!("Couldn't auto-shield transparent funds: {}", e);
+                self.wallet.auto_shield_failed(height).await;
+                return Some(object! { "error" => e });
+            }
+        };
+
+        let policy_address = self
+            .wallet
+            .wallet_options
+            .read()
+            .await
+            .auto_shield
+            .as_ref()
+            .and_then(|p| p.to_address.clone());
+
+        // Each t-address is shielded in its own Tx, so they aren't linked together. An address that can't pay the
+        // fee yet is left for later.
+        let mut by_taddr = BTreeMap::<String, Vec<lightwallet::data::Utxo>>::new();
+        for utxo in utxos.iter() {
+            by_taddr.entry(utxo.address.clone()).or_default().push(utxo.clone());
+        }
+        let groups = by_taddr
+            .into_iter()
+            .filter(|(_, utxos)| utxos.iter().map(|u| u.value).sum::<u64>() > u64::from(DEFAULT_FEE))
+            .collect::<Vec<_>>();
+
+        let results = self
+            .shield_taddrs_with(|| prover.clone(), groups, policy_address, true)
+            .await;
+        self.wallet.mark_auto_shield(&utxos).await;
+
+        match results.members().find(|r| r.has_key("error")) {
+            Some(r) => {
+                warn!("Couldn't auto-shield transparent funds: {}", r["error"]);
+                self.wallet.auto_shield_failed(height).await;
+            }
+            None => {
+                info!("Auto-shielded transparent funds in {} Txs", results.len());
+                self.wallet.auto_shield_succeeded().await;
+            }
+        }
+
+        Some(results)
+    }
+
+    // Shield exactly these UTXOs in one Tx, to `to` or else to the address the change policy picks. A `background`
+    // shield leaves the send progress alone.
+    async fn shield_utxos_with<PR: TxProver>(
+        &self,
+        prover: PR,
+        utxos: &[lightwallet::data::Utxo],
+        to: Option<String>,
+        background: bool,
+    ) -> Result<String, String> {
+        // The funds stay in the account of the t-address they were received at
+        let account = match utxos.first() {
+            Some(u) => self.wallet.keys().read().await.get_address_account(&u.address),
+            None => None,
+        };
+        let to = match to {
+            Some(address) => address,
+            None => encode_payment_address(
+                self.config.hrp_sapling_address(),
+                &self
+                    .wallet
+                    .get_change_address(None, true, account.unwrap_or(DEFAULT_ACCOUNT))
+                    .await?,
+            ),
+        };
+
+        let options = SendOptions {
+            inputs: Some(utxos.iter().map(|u| u.input_id()).collect()),
+            account,
+            send_max: true,
+            background,
+            ..Default::default()
+        };
 
         let result = {
             let _lock = self.sync_lock.lock().await;
-            let prover = crate::blaze::test_utils::FakeTxProver {};
-
             self.wallet
-                .send_to_address(prover, false, addrs, |txbytes| {
-                    GrpcConnector::send_transaction(self.get_server_uri(), txbytes)
+                .send_to_address(prover, true, options, vec![(to.as_str(), 0, None)], |txbytes| {
+                    self.broadcast_tx(txbytes)
                 })
                 .await
         };
 
+        self.save_fresh_change_address().await;
         result.map(|(txid, _)| txid)
     }
+
+    /// Shield the transparent funds of each t-address in `from` (or of every t-address) in a separate
+    /// transaction, so the addresses are never linked together on chain. The funds go to `to`, or to the
+    /// z-address picked by the change policy. Returns the result for every t-address.
+    pub async fn do_shield_from(&self, from: Option<Vec<String>>, to: Option<String>) -> Result<JsonValue, String> {
+        self.shield_from_with(self.sapling_prover(), from, to).await
+    }
+
+    async fn shield_from_with<PR: TxProver + Clone, F: Future<Output = Result<PR, String>>>(
+        &self,
+        load_prover: F,
+        from: Option<Vec<String>>,
+        to: Option<String>,
+    ) -> Result<JsonValue, String> {
+        let groups = self.wallet.get_utxos_by_taddr(&from).await?;
+        if groups.is_empty() {
+            return Err("There are no transparent funds to shield".to_string());
+        }
+
+        let prover = load_prover.await?;
+        Ok(self.shield_taddrs_with(|| prover.clone(), groups, to, false).await)
+    }
+
+    async fn shield_taddrs_with<PR: TxProver, F: Fn() -> PR>(
+        &self,
+        make_prover: F,
+        groups: Vec<(String, Vec<lightwallet::data::Utxo>)>,
+        to: Option<String>,
+        background: bool,
+    ) -> JsonValue {
+        let fee = u64::from(DEFAULT_FEE);
+
+        let mut results = vec![];
+        for (taddr, utxos) in groups {
+            let value = utxos.iter().map(|u| u.value).sum::<u64>();
+
+            let result = if utxos.is_empty() {
+                Err("Nothing to shield, there are no spendable funds at this address".to_string())
+            } else if value <= fee {
+                Err(format!(
+                    "Not enough to shield. Have {} zats, need more than {} zats to cover tx fee",
+                    value, fee
+                ))
+            } else {
+                self.shield_utxos_with(make_prover(), &utxos, to.clone(), background)
+                    .await
+            };
+
+            results.push(match result {
+                Ok(txid) => object! {
+                    "from"  => taddr,
+                    "value" => value - fee,
+                    "txid"  => txid,
+                },
+                Err(e) => object! {
+                    "from"  => taddr,
+                    "value" => value,
+                    "error" => e,
+                },
+            });
+        }
+
+        JsonValue::Array(results)
+    }
+
+    // With the "fresh" change policy a send creates a new address, so save the wallet right away. Otherwise the
+    // change would not be found again if the app is closed before the next save.
+    async fn save_fresh_change_address(&self) {
+        if self.wallet.wallet_options.read().await.change_policy == ChangePolicy::FreshAddress {
+            if let Err(e) = self.do_save(true).await {
+                warn!("Couldn't save the wallet after creating a change address: {}", e);
+            }
+        }
+    }
+
+    /// Put together an unsigned transaction, which can be created by a watch-only wallet, and return it hex encoded
+    /// along with a summary of what it spends. It has to be signed by the wallet that has the spending keys.
+    pub async fn do_create_unsigned_proposal(
+        &self,
+        addrs: Vec<(&str, u64, Option<String>)>,
+        options: SendOptions,
+    ) -> Result<JsonValue, String> {
+        let proposal = self.wallet.create_unsigned_proposal(addrs, &options).await?;
+
+        let mut proposal_bytes = vec![];
+        proposal.write(&mut proposal_bytes).map_err(|e| e.to_string())?;
+
+        let mut j = Self::proposal_summary(&proposal);
+        j["proposal"] = hex::encode(proposal_bytes).into();
+        Ok(j)
+    }
+
+    // What a proposal spends and where it sends it, for checking it both before it is signed and after
+    fn proposal_summary(proposal: &UnsignedProposal) -> JsonValue {
+        let outputs = proposal
+            .outputs
+            .iter()
+            .map(|o| {
+                object! {
+                    "address" => o.address.clone(),
+                    "value"   => o.value,
+                    "memo"    => o.memo.clone(),
+                }
+            })
+            .collect::<Vec<JsonValue>>();
+
+        object! {
+            "target_height"  => proposal.target_height,
+            "sapling_inputs" => proposal.sapling_spends.len(),
+            "utxo_inputs"    => proposal.utxos.len(),
+            "total_input"    => proposal.total_input_value(),
+            "outputs"        => outputs,
+            "fee"            => proposal.fee,
+            "change"         => proposal.change_value(),
+            "change_address" => proposal.change_address.clone(),
+        }
+    }
+
+    /// Prove and sign a hex encoded unsigned proposal with this wallet's spending keys. The signed transaction is
+    /// returned hex encoded, with the recipients, fee and change it pays, and is not broadcast.
+    pub async fn do_sign_proposal(&self, proposal_hex: &str) -> Result<JsonValue, String> {
+        let prover = self.sapling_prover().await?;
+
+        self.sign_proposal_with(prover, proposal_hex).await
+    }
+
+    #[cfg(test)]
+    pub async fn test_do_sign_proposal(&self, proposal_hex: &str) -> Result<JsonValue, String> {
+        self.sign_proposal_with(crate::blaze::test_utils::FakeTxProver {}, proposal_hex)
+            .await
+    }
+
+    async fn sign_proposal_with<PR: TxProver>(&self, prover: PR, proposal_hex: &str) -> Result<JsonValue, String> {
+        let proposal_bytes = hex::decode(proposal_hex.trim()).map_err(|e| format!("Invalid proposal hex: {}", e))?;
+        let proposal =
+            UnsignedProposal::read(&proposal_bytes[..]).map_err(|e| format!("Couldn't read the proposal: {}", e))?;
+
+        let (txid, raw_tx) = self.wallet.sign_proposal(prover, &proposal).await?;
+
+        // Show what was signed, so the offline wallet can check it before the signed Tx goes back online
+        let mut j = Self::proposal_summary(&proposal);
+        j["txid"] = format!("{}", txid).into();
+        j["signed_tx"] = hex::encode(raw_tx).into();
+        Ok(j)
+    }
+
+    /// Decode a hex encoded transaction, showing its inputs and outputs and which of them are this wallet's
+    pub async fn do_decode_tx(&self, tx_hex: &str) -> Result<JsonValue, String> {
+        let raw_tx = hex::decode(tx_hex.trim()).map_err(|e| format!("Invalid transaction hex: {}", e))?;
+
+        let height = BlockHeight::from_u32(self.wallet.last_scanned_height().await as u32 + 1);
+        let params = self.config.get_params();
+        let tx = Transaction::read(&raw_tx[..], BranchId::for_height(&params, height))
+            .map_err(|e| format!("Couldn't read the transaction: {}", e))?;
+
+        let keys = self.wallet.keys();
+        let keys = keys.read().await;
+        let txns = self.wallet.txns.read().await;
+        let taddrs = keys.get_all_taddrs();
+
+        // The fee can only be worked out if the values of all the transparent inputs are known
+        let mut t_in = Some(0u64);
+        let mut t_out = 0u64;
+        let mut vin = vec![];
+        let mut vout = vec![];
+        if let Some(t_bundle) = tx.transparent_bundle() {
+            for input in t_bundle.vin.iter() {
+                let prev_txid = TxId::from_bytes(*input.prevout.hash());
+                let prev_n = input.prevout.n() as u64;
+                let utxo = txns
+                    .current
+                    .get(&prev_txid)
+                    .and_then(|wtx| wtx.utxos.iter().find(|u| u.output_index == prev_n));
+
+                t_in = t_in.and_then(|v| utxo.map(|u| v + u.value));
+                vin.push(object! {
+                    "prev_txid"  => format!("{}", prev_txid),
+                    "prev_index" => prev_n,
+                    "ours"       => utxo.is_some(),
+                    "address"    => utxo.map(|u| u.address.clone()),
+                    "value"      => utxo.map(|u| u.value),
+                });
+            }
+
+            for (i, output) in t_bundle.vout.iter().enumerate() {
+                let address = keys.address_from_pubkeyhash(output.script_pubkey.address());
+                t_out += u64::from(output.value);
+                vout.push(object! {
+                    "index"   => i,
+                    "ours"    => address.as_ref().map(|a| taddrs.contains(a)).unwrap_or(false),
+                    "address" => address,
+                    "value"   => u64::from(output.value),
+                });
+            }
+        }
+
+        // Outputs to this wallet are decrypted with its incoming viewing keys, and outputs this wallet sent
+        // elsewhere are recovered with its outgoing viewing keys
+        let extfvks = keys.get_all_extfvks();
+        let ivks: Vec<_> = extfvks.iter().map(|k| k.fvk.vk.ivk()).collect();
+        let ovks: Vec<_> = extfvks.iter().map(|k| k.fvk.ovk).collect();
+
+        let hrp = self.config.hrp_sapling_address();
+        let mut value_balance = 0i64;
+        let mut spends = vec![];
+        let mut outputs = vec![];
+        if let Some(s_bundle) = tx.sapling_bundle() {
+            value_balance = i64::from(s_bundle.value_balance);
+
+            for spend in s_bundle.shielded_spends.iter() {
+                let note = txns
+                    .current
+                    .values()
+                    .flat_map(|wtx| wtx.s_notes.iter())
+                    .find(|nd| nd.nullifier == spend.nullifier);
+
+                spends.push(object! {
+                    "nullifier" => hex::encode(spend.nullifier.0),
+                    "ours"      => note.is_some(),
+                    "address"   => note.and_then(|nd| LightWallet::<P>::sapling_note_address(hrp, nd)),
+                    "value"     => note.map(|nd| nd.note.value),
+                });
+            }
+
+            for (i, output) in s_bundle.shielded_outputs.iter().enumerate() {
+                let received = ivks
+                    .iter()
+                    .find_map(|ivk| try_sapling_note_decryption(&params, height, ivk, output));
+                let ours = received.is_some();
+                let decrypted = match received {
+                    Some(r) => Some(("received", r)),
+                    None => ovks
+                        .iter()
+                        .find_map(|ovk| try_sapling_output_recovery(&params, height, ovk, output))
+                        .map(|r| ("sent", r)),
+                };
+
+                let mut j = object! {
+                    "index" => i,
+                    "ours"  => ours,
+                };
+                if let Some((direction, (note, to, memo_bytes))) = decrypted {
+                    j["direction"] = direction.into();
+                    j["address"] = encode_payment_address(hrp, &to).into();
+                    j["value"] = note.value.into();
+                    j["memo"] = LightWallet::<P>::memo_str(Memo::try_from(memo_bytes).ok()).into();
+                }
+                outputs.push(j);
+            }
+        }
+
+        let mut j = object! {
+            "txid"                => format!("{}", tx.txid()),
+            "version"             => format!("{:?}", tx.version()),
+            "size"                => raw_tx.len(),
+            "lock_time"           => tx.lock_time(),
+            "expiry_height"       => u32::from(tx.expiry_height()),
+            "in_wallet"           => txns.current.contains_key(&tx.txid()),
+            "transparent_inputs"  => vin,
+            "transparent_outputs" => vout,
+            "sapling_spends"      => spends,
+            "sapling_outputs"     => outputs,
+            "value_balance"       => value_balance,
+        };
+        if let Some(t_in) = t_in {
+            j["fee"] = (t_in as i64 + value_balance - t_out as i64).into();
+        }
+
+        Ok(j)
+    }
+
+    /// Broadcast a hex encoded transaction that was signed by another wallet, and track it as a pending send
+    pub async fn do_broadcast_signed_tx(&self, tx_hex: &str) -> Result<String, String> {
+        let raw_tx = hex::decode(tx_hex.trim()).map_err(|e| format!("Invalid transaction hex: {}", e))?;
+
+        let _lock = self.sync_lock.lock().await;
+        self.wallet
+            .broadcast_signed_tx(raw_tx, |txbytes| self.broadcast_tx(txbytes))
+            .await
+    }
+
+    #[cfg(test)]
+    pub async fn test_do_send(&self, addrs: Vec<(&str, u64, Option<String>)>) -> Result<String, String> {
+        self.test_do_send_with_options(addrs, SendOptions::default()).await
+    }
+
+    #[cfg(test)]
+    pub async fn test_do_send_with_options(
+        &self,
+        addrs: Vec<(&str, u64, Option<String>)>,
+        options: SendOptions,
+    ) -> Result<String, String> {
+        self.send_with(crate::blaze::test_utils::FakeTxProver {}, addrs, options)
+            .await
+            .map(|(txid, _)| txid)
+    }
 }
 
 #[cfg(test)]
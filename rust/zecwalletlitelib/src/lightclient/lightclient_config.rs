@@ -31,6 +31,8 @@ pub const MAX_REORG: usize = 100;
 // BIP44 standard gap limit for HD wallet address discovery
 // This ensures we find all addresses with transactions during wallet restoration
 pub const GAP_RULE_UNUSED_ADDRESSES: usize = 20;
+// As in BIP44, account discovery stops at the first account that hasn't received anything
+pub const GAP_RULE_UNUSED_ACCOUNTS: usize = 1;
 
 // Marker struct for the production network.
 #[derive(PartialEq, Copy, Clone, Debug)]
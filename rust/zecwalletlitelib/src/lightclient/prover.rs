@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use zcash_primitives::{
+    merkle_tree::MerklePath,
+    sapling::{
+        prover::TxProver,
+        redjubjub::{PublicKey, Signature},
+        Diversifier, Node, PaymentAddress, ProofGenerationKey, Rseed,
+    },
+    transaction::components::{Amount, GROTH_PROOF_SIZE},
+};
+use zcash_proofs::{prover::LocalTxProver, sapling::SaplingProvingContext};
+
+// A Sapling prover that is created once from the params and shared by all the sends of a LightClient. Parsing the
+// params takes seconds and a lot of memory, so they are only parsed again after the prover is released.
+#[derive(Clone)]
+pub struct SharedTxProver(pub(crate) Arc<LocalTxProver>);
+
+impl SharedTxProver {
+    pub fn from_bytes(sapling_spend: &[u8], sapling_output: &[u8]) -> Self {
+        Self(Arc::new(LocalTxProver::from_bytes(sapling_spend, sapling_output)))
+    }
+}
+
+impl TxProver for SharedTxProver {
+    type SaplingProvingContext = SaplingProvingContext;
+
+    fn new_sapling_proving_context(&self) -> Self::SaplingProvingContext {
+        self.0.new_sapling_proving_context()
+    }
+
+    fn spend_proof(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        proof_generation_key: ProofGenerationKey,
+        diversifier: Diversifier,
+        rseed: Rseed,
+        ar: jubjub::Fr,
+        value: u64,
+        anchor: bls12_381::Scalar,
+        merkle_path: MerklePath<Node>,
+    ) -> Result<([u8; GROTH_PROOF_SIZE], jubjub::ExtendedPoint, PublicKey), ()> {
+        self.0.spend_proof(
+            ctx,
+            proof_generation_key,
+            diversifier,
+            rseed,
+            ar,
+            value,
+            anchor,
+            merkle_path,
+        )
+    }
+
+    fn output_proof(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        esk: jubjub::Fr,
+        payment_address: PaymentAddress,
+        rcm: jubjub::Fr,
+        value: u64,
+    ) -> ([u8; GROTH_PROOF_SIZE], jubjub::ExtendedPoint) {
+        self.0.output_proof(ctx, esk, payment_address, rcm, value)
+    }
+
+    fn binding_sig(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        value_balance: Amount,
+        sighash: &[u8; 32],
+    ) -> Result<Signature, ()> {
+        self.0.binding_sig(ctx, value_balance, sighash)
+    }
+}
+
+// Holds the prover once it is loaded, until it is released. Loading it takes seconds, so it is done on a blocking
+// thread instead of holding up the async runtime, and callers that need it meanwhile wait for that load instead of
+// starting another one.
+pub struct ProverCache<T> {
+    prover: Mutex<Option<T>>,
+}
+
+impl<T: Clone + Send + 'static> ProverCache<T> {
+    pub fn new() -> Self {
+        Self {
+            prover: Mutex::new(None),
+        }
+    }
+
+    // The loaded prover, or the one `load` returns if there isn't one yet
+    pub async fn get_or_load<F>(&self, load: F) -> Result<T, String>
+    where
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+    {
+        let mut prover = self.prover.lock().await;
+        if let Some(p) = prover.as_ref() {
+            return Ok(p.clone());
+        }
+
+        let p = tokio::task::spawn_blocking(load)
+            .await
+            .map_err(|e| format!("Couldn't load the prover: {}", e))??;
+
+        *prover = Some(p.clone());
+        Ok(p)
+    }
+
+    // Drop the prover. Returns whether it was loaded.
+    pub async fn release(&self) -> bool {
+        self.prover.lock().await.take().is_some()
+    }
+
+    pub async fn is_loaded(&self) -> bool {
+        self.prover.lock().await.is_some()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::ProverCache;
+
+    #[tokio::test]
+    async fn prover_cache() {
+        let cache = ProverCache::<u32>::new();
+        let loads = Arc::new(AtomicUsize::new(0));
+        let load = |loads: &Arc<AtomicUsize>| {
+            let loads = loads.clone();
+            move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                Ok(loads.fetch_add(1, Ordering::SeqCst) as u32 + 1)
+            }
+        };
+        assert!(!cache.is_loaded().await);
+
+        // Callers that need it at the same time share one load
+        let (a, b) = tokio::join!(cache.get_or_load(load(&loads)), cache.get_or_load(load(&loads)));
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 1));
+        assert_eq!(cache.get_or_load(load(&loads)).await.unwrap(), 1);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(cache.is_loaded().await);
+
+        // After it is released, it is loaded again when it is next needed
+        assert!(cache.release().await);
+        assert!(!cache.release().await);
+        assert!(!cache.is_loaded().await);
+        assert_eq!(cache.get_or_load(load(&loads)).await.unwrap(), 2);
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+
+        // A failed load leaves nothing behind, so the next caller tries again
+        assert!(cache.release().await);
+        assert!(cache.get_or_load(|| Err("No params".to_string())).await.is_err());
+        assert!(!cache.is_loaded().await);
+    }
+}
@@ -298,8 +298,22 @@ impl<P: consensus::Parameters + Send + Sync + 'static> CompactTxStreamer for Tes
         Self::wait_random().await;
 
         let txid = WalletTx::new_txid(&request.into_inner().hash);
-        match self.data.read().await.txns.get(&txid) {
-            Some((_taddrs, tx)) => Ok(Response::new(tx.clone())),
+        let data = self.data.read().await;
+        if let Some((_taddrs, tx)) = data.txns.get(&txid) {
+            return Ok(Response::new(tx.clone()));
+        }
+
+        // Like a real node, Txs that are still in the mempool are returned too
+        let sent = data.sent_txns.iter().find(|rtx| {
+            Transaction::read(
+                &rtx.data[..],
+                BranchId::for_height(&UnitTestNetwork, BlockHeight::from_u32(rtx.height as u32)),
+            )
+            .map(|tx| tx.txid() == txid)
+            .unwrap_or(false)
+        });
+        match sent {
+            Some(rtx) => Ok(Response::new(rtx.clone())),
             None => Err(Status::invalid_argument(format!("Can't find txid {}", txid))),
         }
     }
@@ -313,7 +327,11 @@ impl<P: consensus::Parameters + Send + Sync + 'static> CompactTxStreamer for Tes
         .unwrap()
         .txid();
 
-        self.data.write().await.sent_txns.push(rtx);
+        // Like a real node, ignore txns that were already sent
+        let mut data = self.data.write().await;
+        if !data.sent_txns.iter().any(|t| t.data == rtx.data) {
+            data.sent_txns.push(rtx);
+        }
         Ok(Response::new(SendResponse {
             error_message: txid.to_string(),
             error_code: 0,
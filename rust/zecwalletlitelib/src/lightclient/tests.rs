@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::Path;
 
+use bip39::{Language, Mnemonic, Seed};
 use ff::{Field, PrimeField};
 use group::GroupEncoding;
 use json::JsonValue;
@@ -31,17 +32,26 @@ use zcash_primitives::transaction::{Transaction, TransactionData};
 use zcash_primitives::zip32::{ExtendedFullViewingKey, ExtendedSpendingKey};
 
 use crate::blaze::fetch_full_tx::FetchFullTxns;
-use crate::blaze::test_utils::{FakeCompactBlockList, FakeTransaction};
+use crate::blaze::test_utils::{FakeCompactBlockList, FakeTransaction, FakeTxProver};
 use crate::compact_formats::compact_tx_streamer_client::CompactTxStreamerClient;
 
 use crate::compact_formats::{CompactSaplingOutput, CompactTx, Empty};
 use crate::lightclient::faketx::new_transactiondata;
 use crate::lightclient::test_server::{create_test_server, mine_pending_blocks, mine_random_blocks};
 use crate::lightclient::LightClient;
+use crate::lightwallet::batch::MAX_BATCH_OUTPUTS;
 use crate::lightwallet::data::WalletTx;
+use crate::lightwallet::keys::Keys;
+use crate::lightwallet::proposal::UnsignedProposal;
+use crate::lightwallet::schedule::{DueAt, ScheduledRecipient};
+use crate::lightwallet::walletaccount::{account_zip32_index, DEFAULT_ACCOUNT};
+use crate::lightwallet::wallettkey::WalletTKey;
+use crate::lightwallet::{
+    now, AutoShieldPolicy, ChangePolicy, LightWallet, PrivacyPolicy, SendOptions, SendPhase, SendProgress,
+};
 
 use super::checkpoints;
-use super::lightclient_config::{LightClientConfig, UnitTestNetwork};
+use super::lightclient_config::{LightClientConfig, UnitTestNetwork, GAP_RULE_UNUSED_ADDRESSES};
 
 #[test]
 fn new_wallet_from_phrase() {
@@ -73,6 +83,78 @@ fn new_wallet_from_phrase() {
     });
 }
 
+#[test]
+fn new_wallet_from_phrase_with_passphrase() {
+    let temp_dir = TempDir::new("test").unwrap();
+    let data_dir = temp_dir
+        .into_path()
+        .canonicalize()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let config = LightClientConfig::create_unconnected(UnitTestNetwork, Some(data_dir));
+    let lc = LightClient::new_from_phrase_with_passphrase(
+        TEST_SEED.to_string(),
+        Some("secret".to_string()),
+        &config,
+        0,
+        true,
+    )
+    .unwrap();
+    let lc2 = LightClient::new_from_phrase_with_passphrase(
+        TEST_SEED.to_string(),
+        Some("secret".to_string()),
+        &config,
+        0,
+        true,
+    )
+    .unwrap();
+
+    Runtime::new().unwrap().block_on(async move {
+        // The passphrase derives the keys of a different wallet
+        let addresses = lc.do_address().await;
+        assert_ne!(
+            "zs1q6xk3q783t5k92kjqt2rkuuww8pdw2euzy5rk6jytw97enx8fhpazdv3th4xe7vsk6e9sfpawfg".to_string(),
+            addresses["z_addresses"][0]
+        );
+        assert_ne!(
+            "t1eQ63fwkQ4n4Eo5uCrPGaAV8FWB2tmx7ui".to_string(),
+            addresses["t_addresses"][0]
+        );
+        assert_eq!(lc.do_seed_phrase().await.unwrap()["has_passphrase"], true);
+
+        // Restoring with the same passphrase gives the same wallet
+        assert_eq!(addresses, lc2.do_address().await);
+
+        // An unencrypted wallet doesn't save the passphrase, so it has to be entered before deriving new keys
+        let mut wallet_bytes = vec![];
+        lc.wallet.write(&mut wallet_bytes).await.unwrap();
+        let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+        assert!(lw.keys().read().await.needs_passphrase());
+        assert_eq!(
+            lw.keys().write().await.add_zaddr(),
+            Err("The wallet's passphrase needs to be entered first".to_string())
+        );
+        assert!(lw.keys().write().await.add_account("Savings").is_err());
+        assert_eq!(lw.keys().read().await.get_accounts().len(), 1);
+
+        assert!(lw.keys().write().await.set_passphrase("wrong".to_string()).is_err());
+        lw.keys().write().await.set_passphrase("secret".to_string()).unwrap();
+        let new_zaddr = lw.keys().write().await.add_zaddr().unwrap();
+        assert_eq!(new_zaddr, lc2.do_new_address("z").await.unwrap()[0]);
+
+        // An encrypted wallet keeps the passphrase, and gets it back when it is unlocked
+        lc.wallet.encrypt("password".to_string()).await.unwrap();
+        lc.wallet.lock().await.unwrap();
+        assert!(lc.wallet.keys().read().await.needs_passphrase());
+        lc.wallet.unlock("password".to_string()).await.unwrap();
+        assert!(!lc.wallet.keys().read().await.needs_passphrase());
+        assert_eq!(new_zaddr, lc.do_new_address("z").await.unwrap()[0]);
+    });
+}
+
 #[test]
 fn new_wallet_from_sk() {
     let temp_dir = TempDir::new("test").unwrap();
@@ -144,6 +226,129 @@ fn new_wallet_from_vk() {
     });
 }
 
+#[test]
+fn new_watch_only_wallet() {
+    let temp_dir = TempDir::new("test").unwrap();
+    let data_dir = temp_dir
+        .into_path()
+        .canonicalize()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let config = LightClientConfig::create_unconnected(UnitTestNetwork, Some(data_dir));
+    let vk = "zxviews1qvpa0qr8qqqqpqxn4l054nzxpxzp3a8r2djc7sekdek5upce8mc2j2z0arzps4zv9kdvg28gjzvxd47ant6jn4svln5psw3htx93cq93ahw4e7lptrtlq7he5r6p6rcm3s0z6l24ype84sgqfrmghu449htrjspfv6qg2zfx2yrvthflmm2ynl8c0506dekul0f6jkcdmh0292lpphrksyc5z3pxwws97zd5els3l2mjt2s7hntap27mlmt6w0drtfmz36vz8pgu7ecrxzsls";
+    let taddr = "t1eQ63fwkQ4n4Eo5uCrPGaAV8FWB2tmx7ui";
+
+    // Only viewing keys and t-addresses can be watched
+    assert!(LightClient::new_watch_only(&config, vec![], vec![], 0, false).is_err());
+    assert!(LightClient::new_watch_only(&config, vec![], vec![EXT_ZADDR.to_string()], 0, false).is_err());
+
+    let lc = LightClient::new_watch_only(&config, vec![vk.to_string()], vec![taddr.to_string()], 0, false).unwrap();
+
+    Runtime::new().unwrap().block_on(async move {
+        let addresses = lc.do_address().await;
+        assert_eq!(
+            "zs1q6xk3q783t5k92kjqt2rkuuww8pdw2euzy5rk6jytw97enx8fhpazdv3th4xe7vsk6e9sfpawfg".to_string(),
+            addresses["z_addresses"][0]
+        );
+        assert_eq!(addresses["t_addresses"].len(), 1);
+        assert_eq!(taddr.to_string(), addresses["t_addresses"][0]);
+
+        // There is no seed to derive new keys with, export or encrypt
+        assert!(lc.do_new_address("z").await.is_err());
+        assert!(lc.do_new_address("t").await.is_err());
+        assert!(lc.do_seed_phrase().await.is_err());
+        assert!(lc.wallet.encrypt("password".to_string()).await.is_err());
+        assert_eq!(lc.do_encryption_status().await["watch_only"], true);
+
+        // And nothing can be spent
+        let e = lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap_err();
+        assert!(e.contains("watch-only"));
+
+        // It stays watch-only after it is saved
+        let mut wallet_bytes = vec![];
+        lc.wallet.write(&mut wallet_bytes).await.unwrap();
+        let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+        assert!(lw.keys().read().await.is_watch_only());
+        assert_eq!(lw.keys().read().await.get_all_taddrs(), vec![taddr.to_string()]);
+    });
+}
+
+#[tokio::test]
+async fn watch_only_wallet_sync() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    // A watch-only wallet of a viewing key, whose spending key is kept somewhere else
+    let extsk = ExtendedSpendingKey::master(&[1u8; 32]);
+    let extfvk = ExtendedFullViewingKey::from(&extsk);
+    let vk = encode_extended_full_viewing_key(config.hrp_sapling_viewing_key(), &extfvk);
+    let zaddr = encode_payment_address(config.hrp_sapling_address(), &extfvk.default_address().1);
+
+    let mut lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    lc.wallet = LightWallet::new_watch_only(config.clone(), &[vk], &[], 0).unwrap();
+    lc.set_wallet_initial_state(0).await;
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks and receive a note
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let value = 100_000;
+    let (tx, _height, _) = fcbl.add_tx_paying(&extfvk, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 2. The note is in the balance and the history, but it can't be spent from here
+    let balance = lc.do_balance().await;
+    assert_eq!(balance["zbalance"].as_u64().unwrap(), value);
+    assert_eq!(balance["spendable_zbalance"].as_u64().unwrap(), 0);
+
+    let list = lc.do_list_transactions(false).await;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["txid"], tx.txid().to_string());
+    assert_eq!(list[0]["amount"].as_u64().unwrap(), value);
+    assert_eq!(list[0]["address"], zaddr);
+
+    // The note's witnesses are kept, so unsigned proposals can spend it
+    assert!(
+        lc.wallet.txns().read().await.current.get(&tx.txid()).unwrap().s_notes[0]
+            .witnesses
+            .len()
+            > 0
+    );
+
+    // 3. A send is refused
+    let e = lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap_err();
+    assert!(e.contains("watch-only"), "{}", e);
+
+    // 4. The note is spent by the wallet with the spending key. The viewing key finds the spend by its nullifier,
+    // and what was sent with its outgoing viewing key.
+    let (nf, _, _) = lc.wallet.txns().read().await.get_unspent_s_nullifiers()[0];
+    let pa = match RecipientAddress::decode(&config.get_params(), EXT_ZADDR) {
+        Some(RecipientAddress::Shielded(pa)) => pa,
+        _ => panic!("Couldn't parse address"),
+    };
+    let sent_value = 20_000;
+    let sent_tx = fcbl.add_tx_spending(&nf, sent_value, &extfvk.fvk.ovk, &pa);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 5. The balance and the history show the outgoing tx
+    assert_eq!(lc.do_balance().await["zbalance"].as_u64().unwrap(), 0);
+
+    let list = lc.do_list_transactions(false).await;
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[1]["txid"], sent_tx.txid().to_string());
+    assert_eq!(list[1]["amount"].as_i64().unwrap(), -(value as i64));
+    assert_eq!(list[1]["outgoing_metadata"][0]["address"], EXT_ZADDR.to_string());
+    assert_eq!(list[1]["outgoing_metadata"][0]["value"].as_u64().unwrap(), sent_value);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
 #[tokio::test]
 async fn basic_no_wallet_txns() {
     let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
@@ -647,6 +852,14 @@ async fn z_incoming_viewkey() {
     assert_eq!(list[0]["amount"].as_u64().unwrap(), value);
     assert_eq!(list[0]["address"], iaddr);
 
+    // No witnesses are kept for the notes of a viewing key imported into a regular wallet
+    assert_eq!(
+        lc.wallet.txns().read().await.current.get(&tx.txid()).unwrap().s_notes[0]
+            .witnesses
+            .len(),
+        0
+    );
+
     // 4. Also do a rescan, just for fun
     mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
     lc.do_rescan().await.unwrap();
@@ -1075,6 +1288,67 @@ async fn no_change() {
     h1.await.unwrap();
 }
 
+#[tokio::test]
+async fn send_progress_phases() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Receive a note
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    fcbl.add_tx_paying(&extfvk1, 100_000);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // The phases a subscriber saw, each once, and the last update
+    fn phases(rx: &mut tokio::sync::broadcast::Receiver<SendProgress>) -> (Vec<SendPhase>, SendProgress) {
+        let mut phases = vec![];
+        let mut last = None;
+        while let Ok(p) = rx.try_recv() {
+            if phases.last() != Some(&p.phase) {
+                phases.push(p.phase);
+            }
+            last = Some(p);
+        }
+        (phases, last.unwrap())
+    }
+
+    // 2. A send goes through every phase in order, and every spend and output is proven
+    let mut rx = lc.subscribe_send_progress();
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap();
+
+    let (seen, last) = phases(&mut rx);
+    assert_eq!(
+        seen,
+        vec![
+            SendPhase::Selecting,
+            SendPhase::Proving,
+            SendPhase::Signing,
+            SendPhase::Broadcasting,
+            SendPhase::Done
+        ]
+    );
+    assert!(last.total > 0);
+    assert_eq!(last.progress, last.total);
+    assert_eq!(last.last_txid, Some(txid));
+    assert!(!last.is_send_in_progress);
+
+    // 3. A send that can't be made fails right after selecting
+    assert!(lc.test_do_send(vec![(EXT_ZADDR, 1_000_000, None)]).await.is_err());
+
+    let (seen, last) = phases(&mut rx);
+    assert_eq!(seen, vec![SendPhase::Selecting, SendPhase::Failed]);
+    assert!(last.last_error.is_some());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
 #[tokio::test]
 async fn recover_at_checkpoint() {
     // 1. Wait for test server to start
@@ -1325,7 +1599,8 @@ async fn mempool_clearing() {
         lc.wallet.txns(),
         Some(140.5),
     )
-    .await;
+    .await
+    .unwrap();
 
     let notes_after = lc.do_list_notes(true).await;
     let txns_after = lc.do_list_transactions(false).await;
@@ -1435,6 +1710,1582 @@ async fn mempool_and_balance() {
     h1.await.unwrap();
 }
 
+#[tokio::test]
+async fn coin_control() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Send an incoming tx to fill the wallet
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let zvalue = 100_000;
+    let (_ztx, _height, _) = fcbl.add_tx_paying(&extfvk1, zvalue);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 3. Send an incoming t-address txn
+    let sk = lc.wallet.keys().read().await.tkeys[0].clone();
+    let pk = sk.pubkey().unwrap();
+    let taddr = sk.address;
+    let tvalue = 200_000;
+
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&pk, taddr.clone(), tvalue);
+    let (_ttx, _) = fcbl.add_ftx(ftx);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 4. Both the note and the utxo should be listed as spendable
+    let inputs = lc.do_list_spendable_inputs().await;
+    assert_eq!(inputs["notes"].len(), 1);
+    assert_eq!(inputs["notes"][0]["value"].as_u64().unwrap(), zvalue);
+    assert_eq!(inputs["utxos"].len(), 1);
+    assert_eq!(inputs["utxos"][0]["value"].as_u64().unwrap(), tvalue);
+    assert_eq!(inputs["utxos"][0]["address"], taddr);
+    let utxo_id = inputs["utxos"][0]["input_id"].as_str().unwrap().to_string();
+
+    // 5. Spending an unknown input should fail
+    let options = SendOptions {
+        inputs: Some(vec!["deadbeef".to_string()]),
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 10_000, None)], options)
+        .await
+        .is_err());
+
+    // 6. Sending from a z-address that has nothing should fail, even though the wallet has enough funds
+    let zaddr2 = lc.wallet.keys().write().await.add_zaddr().unwrap();
+    let options = SendOptions {
+        from_address: Some(zaddr2),
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 10_000, None)], options)
+        .await
+        .is_err());
+
+    // 7. Spend only the utxo, even though the note alone could cover the send
+    let sent_value = 50_000;
+    let options = SendOptions {
+        inputs: Some(vec![utxo_id]),
+        ..Default::default()
+    };
+    let sent_txid = lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, sent_value, None)], options)
+        .await
+        .unwrap();
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["utxos"].len(), 0);
+    assert_eq!(notes["spent_utxos"].len(), 1);
+    assert_eq!(notes["spent_utxos"][0]["spent"], sent_txid);
+    assert_eq!(notes["spent_notes"].len(), 0);
+
+    // The original note is untouched, and the change is a new note
+    assert_eq!(notes["unspent_notes"].len(), 2);
+    let mut values = notes["unspent_notes"]
+        .members()
+        .map(|n| n["value"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![tvalue - sent_value - u64::from(DEFAULT_FEE), zvalue]);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn freeze_inputs() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a note and a utxo
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let zvalue = 100_000;
+    let (_ztx, _height, _) = fcbl.add_tx_paying(&extfvk1, zvalue);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    let sk = lc.wallet.keys().read().await.tkeys[0].clone();
+    let pk = sk.pubkey().unwrap();
+    let taddr = sk.address;
+    let tvalue = 200_000;
+
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&pk, taddr.clone(), tvalue);
+    let (_ttx, _) = fcbl.add_ftx(ftx);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let notes = lc.do_list_notes(false).await;
+    let note_id = notes["unspent_notes"][0]["input_id"].as_str().unwrap().to_string();
+    assert_eq!(notes["unspent_notes"][0]["frozen"].as_bool().unwrap(), false);
+
+    // 3. Unknown ids are rejected
+    assert!(lc.do_freeze_inputs(vec!["deadbeef".to_string()], true).await.is_err());
+
+    // 4. Freeze the note. It is no longer listed as spendable, and doesn't count towards the spendable balance
+    let r = lc.do_freeze_inputs(vec![note_id.clone()], true).await.unwrap();
+    assert_eq!(r["changed"].as_usize().unwrap(), 1);
+
+    let notes = lc.do_list_notes(false).await;
+    assert_eq!(notes["unspent_notes"][0]["frozen"].as_bool().unwrap(), true);
+    assert_eq!(notes["unspent_notes"][0]["spendable"].as_bool().unwrap(), false);
+    assert_eq!(lc.do_list_spendable_inputs().await["notes"].len(), 0);
+    assert_eq!(lc.do_balance().await["spendable_zbalance"].as_u64().unwrap(), 0);
+
+    // The flag survives saving and reading the wallet
+    let buf = lc.do_save_to_buffer().await.unwrap();
+    let wallet2 = LightWallet::read(&buf[..], &config).await.unwrap();
+    assert!(wallet2
+        .txns
+        .read()
+        .await
+        .current
+        .values()
+        .any(|wtx| wtx.s_notes.iter().any(|nd| nd.frozen)));
+
+    // 5. Spending the frozen note explicitly fails
+    let options = SendOptions {
+        inputs: Some(vec![note_id.clone()]),
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 10_000, None)], options)
+        .await
+        .is_err());
+
+    // 6. A regular send has to use the utxo, leaving the frozen note alone
+    let sent_txid = lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap();
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["spent_utxos"][0]["spent"], sent_txid);
+    assert_eq!(notes["spent_notes"].len(), 0);
+
+    // 7. Unfreeze the note, and it is spendable again
+    let r = lc.do_freeze_inputs(vec![note_id.clone()], false).await.unwrap();
+    assert_eq!(r["changed"].as_usize().unwrap(), 1);
+    assert!(lc.do_list_spendable_inputs().await["notes"]
+        .members()
+        .any(|n| n["input_id"] == note_id));
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn send_max() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a note and a utxo
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let zvalue = 100_000;
+    let (_ztx, _height, _) = fcbl.add_tx_paying(&extfvk1, zvalue);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    let sk = lc.wallet.keys().read().await.tkeys[0].clone();
+    let pk = sk.pubkey().unwrap();
+    let taddr = sk.address;
+    let tvalue = 200_000;
+
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&pk, taddr.clone(), tvalue);
+    let (_ttx, _) = fcbl.add_ftx(ftx);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 3. Sending the max needs exactly one recipient
+    let options = SendOptions {
+        send_max: true,
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 0, None), (EXT_TADDR, 0, None)], options.clone())
+        .await
+        .is_err());
+
+    // 4. Send everything. The amount passed in is ignored
+    let sent_txid = lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 1, None)], options)
+        .await
+        .unwrap();
+    let expected_value = zvalue + tvalue - u64::from(DEFAULT_FEE);
+
+    let list = lc.do_list_transactions(false).await;
+    let jv = list.members().find(|jv| jv["txid"] == sent_txid).unwrap();
+    assert_eq!(jv["outgoing_metadata"][0]["address"], EXT_ZADDR.to_string());
+    assert_eq!(jv["outgoing_metadata"][0]["value"].as_u64().unwrap(), expected_value);
+
+    // 5. Mine it. Everything is spent, and there is no change
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"].len(), 0);
+    assert_eq!(notes["pending_notes"].len(), 0);
+    assert_eq!(notes["utxos"].len(), 0);
+    assert_eq!(notes["spent_notes"].len(), 1);
+    assert_eq!(notes["spent_utxos"].len(), 1);
+    assert_eq!(lc.do_balance().await["zbalance"].as_u64().unwrap(), 0);
+    assert_eq!(lc.do_balance().await["tbalance"].as_u64().unwrap(), 0);
+
+    // 6. Nothing left to pay the fee with
+    let options = SendOptions {
+        send_max: true,
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 0, None)], options)
+        .await
+        .is_err());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn rebroadcast_and_expire_sends() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a note
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let value = 100_000;
+    let (_tx, _height, _) = fcbl.add_tx_paying(&extfvk1, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 3. Send, but the tx never makes it into a block
+    let sent_txid = lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap();
+    assert_eq!(lc.wallet.zbalance(None).await, 0);
+
+    let expiry_height = {
+        let txns = lc.wallet.txns.read().await;
+        let wtx = txns
+            .current
+            .values()
+            .find(|wtx| wtx.txid.to_string() == sent_txid)
+            .unwrap();
+        assert!(wtx.unconfirmed);
+        assert!(wtx.raw_tx.is_some());
+        assert!(wtx.expiry_height > 0);
+        wtx.expiry_height as u64
+    };
+
+    // 4. The next sync rebroadcasts it
+    data.write().await.sent_txns.clear();
+    mine_random_blocks(&mut fcbl, &data, &lc, 1).await;
+    assert_eq!(data.read().await.sent_txns.len(), 1);
+
+    let status = lc.do_pending_sends().await;
+    assert_eq!(status["pending"].len(), 1);
+    assert_eq!(status["pending"][0]["txid"], sent_txid);
+    assert_eq!(status["pending"][0]["expiry_height"].as_u64().unwrap(), expiry_height);
+    assert_eq!(status["pending"][0]["rebroadcasts"].as_u32().unwrap(), 1);
+    assert_eq!(status["expired"].len(), 0);
+
+    // A sync that finds no new block doesn't rebroadcast it again
+    data.write().await.sent_txns.clear();
+    lc.do_sync(true).await.unwrap();
+    assert_eq!(data.read().await.sent_txns.len(), 0);
+    let status = lc.do_pending_sends().await;
+    assert_eq!(status["pending"][0]["rebroadcasts"].as_u32().unwrap(), 1);
+
+    // 5. Once the chain is past the expiry height, the tx is dropped and the note can be spent again
+    data.write().await.sent_txns.clear();
+    let last_height = lc.wallet.last_scanned_height().await;
+    mine_random_blocks(&mut fcbl, &data, &lc, expiry_height - last_height).await;
+
+    let status = lc.do_pending_sends().await;
+    assert_eq!(status["pending"].len(), 0);
+    assert_eq!(status["expired"].len(), 1);
+    assert_eq!(status["expired"][0]["txid"], sent_txid);
+
+    assert!(lc
+        .do_list_transactions(false)
+        .await
+        .members()
+        .find(|jv| jv["txid"] == sent_txid)
+        .is_none());
+    assert_eq!(lc.wallet.zbalance(None).await, value);
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"].len(), 1);
+    assert_eq!(notes["pending_notes"].len(), 0);
+    assert_eq!(notes["unspent_notes"][0]["value"].as_u64().unwrap(), value);
+
+    // 6. The funds can be sent again
+    lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.unwrap();
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn unsigned_proposal_round_trip() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a note
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let value = 100_000;
+    let (_tx, _height, _) = fcbl.add_tx_paying(&extfvk1, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 3. Can't propose more than the wallet has
+    assert!(lc
+        .do_create_unsigned_proposal(vec![(EXT_ZADDR, value, None)], SendOptions::default())
+        .await
+        .is_err());
+
+    // 4. Create the proposal. Nothing is spent yet
+    let sent_value = 20_000;
+    let proposal = lc
+        .do_create_unsigned_proposal(
+            vec![(EXT_ZADDR, sent_value, Some("From an offline wallet".to_string()))],
+            SendOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(proposal["sapling_inputs"].as_usize().unwrap(), 1);
+    assert_eq!(proposal["utxo_inputs"].as_usize().unwrap(), 0);
+    assert_eq!(proposal["total_input"].as_u64().unwrap(), value);
+    assert_eq!(
+        proposal["change"].as_u64().unwrap(),
+        value - sent_value - u64::from(DEFAULT_FEE)
+    );
+    assert_eq!(lc.wallet.zbalance(None).await, value);
+    assert_eq!(data.read().await.sent_txns.len(), 0);
+
+    // 5. Sign it, which doesn't broadcast it
+    let signed = lc
+        .test_do_sign_proposal(proposal["proposal"].as_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(data.read().await.sent_txns.len(), 0);
+    assert!(lc.test_do_sign_proposal("not hex").await.is_err());
+
+    // The signer shows what it signed
+    assert_eq!(signed["outputs"][0]["address"], EXT_ZADDR.to_string());
+    assert_eq!(signed["outputs"][0]["value"].as_u64().unwrap(), sent_value);
+    assert_eq!(signed["fee"].as_u64().unwrap(), u64::from(DEFAULT_FEE));
+    assert_eq!(signed["change"], proposal["change"]);
+    assert_eq!(signed["change_address"], proposal["change_address"]);
+
+    // A proposal that was changed to send the change elsewhere, or a memo to a t-address, isn't signed
+    let proposal_bytes = hex::decode(proposal["proposal"].as_str().unwrap()).unwrap();
+    let tampered_hex = |change: fn(&mut UnsignedProposal)| {
+        let mut p = UnsignedProposal::read(&proposal_bytes[..]).unwrap();
+        change(&mut p);
+        let mut bytes = vec![];
+        p.write(&mut bytes).unwrap();
+        hex::encode(bytes)
+    };
+    let e = lc
+        .test_do_sign_proposal(&tampered_hex(|p| p.change_address = EXT_ZADDR.to_string()))
+        .await
+        .unwrap_err();
+    assert!(e.contains("isn't in this wallet"), "{}", e);
+    assert!(lc
+        .test_do_sign_proposal(&tampered_hex(|p| p.outputs[0].address = EXT_TADDR.to_string()))
+        .await
+        .is_err());
+
+    // 6. Broadcast the signed tx. The note is now pending, and the change is unconfirmed
+    let txid = lc
+        .do_broadcast_signed_tx(signed["signed_tx"].as_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(txid, signed["txid"].as_str().unwrap());
+    assert_eq!(data.read().await.sent_txns.len(), 1);
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"].len(), 1);
+    assert_eq!(notes["unspent_notes"][0]["created_in_txid"], txid);
+    assert_eq!(notes["unspent_notes"][0]["unconfirmed"].as_bool().unwrap(), true);
+    assert_eq!(notes["pending_notes"].len(), 1);
+    assert_eq!(notes["pending_notes"][0]["unconfirmed_spent"], txid);
+
+    // 7. Mine it
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let list = lc.do_list_transactions(false).await;
+    let jv = list.members().find(|jv| jv["txid"] == txid).unwrap();
+    assert_eq!(jv["unconfirmed"].as_bool().unwrap(), false);
+    assert_eq!(jv["outgoing_metadata"][0]["address"], EXT_ZADDR.to_string());
+    assert_eq!(jv["outgoing_metadata"][0]["value"].as_u64().unwrap(), sent_value);
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["spent_notes"].len(), 1);
+    assert_eq!(
+        notes["unspent_notes"][0]["value"].as_u64().unwrap(),
+        value - sent_value - u64::from(DEFAULT_FEE)
+    );
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn change_policy() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a note at the first address, and create a second one
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let value = 100_000;
+    let (_tx, _height, _) = fcbl.add_tx_paying(&extfvk1, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    let zaddr1 = lc.wallet.keys().read().await.get_all_zaddresses()[0].clone();
+    let zaddr2 = lc.do_new_address("z").await.unwrap()[0].as_str().unwrap().to_string();
+
+    // 3. By default the change goes back to the address of the spent note
+    assert_eq!(
+        lc.wallet.wallet_options.read().await.change_policy,
+        ChangePolicy::SameAsInput
+    );
+    let sent_value = 10_000;
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, sent_value, None)]).await.unwrap();
+    let change = value - sent_value - u64::from(DEFAULT_FEE);
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"][0]["created_in_txid"], txid);
+    assert_eq!(notes["unspent_notes"][0]["address"], zaddr1);
+    assert_eq!(notes["unspent_notes"][0]["value"].as_u64().unwrap(), change);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 4. A designated change address has to be one of ours
+    assert!(lc
+        .wallet
+        .set_change_policy(ChangePolicy::Designated(EXT_ZADDR.to_string()))
+        .await
+        .is_err());
+    lc.wallet
+        .set_change_policy(ChangePolicy::Designated(zaddr2.clone()))
+        .await
+        .unwrap();
+
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, sent_value, None)]).await.unwrap();
+    let change = change - sent_value - u64::from(DEFAULT_FEE);
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"][0]["created_in_txid"], txid);
+    assert_eq!(notes["unspent_notes"][0]["address"], zaddr2);
+    assert_eq!(notes["unspent_notes"][0]["value"].as_u64().unwrap(), change);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 5. A fresh diversified address gets created for the change of every send, but only once a Tx is built
+    lc.wallet.set_change_policy(ChangePolicy::FreshAddress).await.unwrap();
+    let old_zaddrs = lc.wallet.keys().read().await.get_all_zaddresses();
+
+    let next = lc.wallet.get_change_address(None, true, DEFAULT_ACCOUNT).await.unwrap();
+    assert_eq!(
+        next,
+        lc.wallet.get_change_address(None, true, DEFAULT_ACCOUNT).await.unwrap()
+    );
+    assert!(lc.test_do_send(vec![(EXT_ZADDR, change * 2, None)]).await.is_err());
+    assert_eq!(lc.wallet.keys().read().await.get_all_zaddresses(), old_zaddrs);
+
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, sent_value, None)]).await.unwrap();
+    let change = change - sent_value - u64::from(DEFAULT_FEE);
+
+    // It doesn't use up a new HD key
+    let zaddrs = lc.wallet.keys().read().await.get_all_zaddresses();
+    assert_eq!(zaddrs.len(), 3);
+    assert_eq!(lc.wallet.keys().read().await.zkeys.len(), 2);
+    let fresh = zaddrs.iter().find(|a| !old_zaddrs.contains(a)).unwrap();
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["unspent_notes"][0]["created_in_txid"], txid);
+    assert_eq!(notes["unspent_notes"][0]["address"], *fresh);
+    assert_eq!(notes["unspent_notes"][0]["value"].as_u64().unwrap(), change);
+
+    // 6. The policy is saved with the wallet
+    let mut wallet_bytes = vec![];
+    lc.wallet.write(&mut wallet_bytes).await.unwrap();
+    let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    assert_eq!(lw.wallet_options.read().await.change_policy, ChangePolicy::FreshAddress);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn consolidate_notes() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a few small notes and a big one
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    for value in [20_000, 30_000, 40_000, 1_000_000] {
+        let (_tx, _height, _) = fcbl.add_tx_paying(&extfvk1, value);
+        mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    }
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 3. Preview merging the 3 smallest notes. Nothing is sent
+    let fee = u64::from(DEFAULT_FEE);
+    let preview = lc.do_consolidate(Some(3), None, true).await.unwrap();
+    assert_eq!(preview["batches"].len(), 1);
+    assert_eq!(preview["batches"][0]["notes"].as_usize().unwrap(), 3);
+    assert_eq!(preview["batches"][0]["value"].as_u64().unwrap(), 90_000);
+    assert_eq!(
+        preview["batches"][0]["consolidated_value"].as_u64().unwrap(),
+        90_000 - fee
+    );
+    assert_eq!(preview["total_fee"].as_u64().unwrap(), fee);
+    assert_eq!(preview["txids"].len(), 0);
+    assert_eq!(data.read().await.sent_txns.len(), 0);
+
+    // 4. A batch that would leave less than the dust threshold is skipped
+    let preview = lc.do_consolidate(Some(3), Some(100_000), true).await.unwrap();
+    assert_eq!(preview["batches"].len(), 0);
+    assert_eq!(preview["skipped"].len(), 1);
+
+    // 5. Merge them
+    let result = lc
+        .consolidate_with(FakeTxProver {}, Some(3), u64::from(DEFAULT_FEE))
+        .await
+        .unwrap();
+    assert_eq!(result["txids"].len(), 1);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let notes = lc.do_list_notes(true).await;
+    assert_eq!(notes["spent_notes"].len(), 3);
+    let mut values = notes["unspent_notes"]
+        .members()
+        .map(|n| n["value"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![90_000 - fee, 1_000_000]);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn auto_shield() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive a utxo, and let it get 5 confirmations
+    let sk = lc.wallet.keys().read().await.tkeys[0].clone();
+    let pk = sk.pubkey().unwrap();
+    let taddr = sk.address;
+    let tvalue = 200_000;
+
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&pk, taddr.clone(), tvalue);
+    let (_ttx, _) = fcbl.add_ftx(ftx);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 4).await;
+
+    // 3. Auto-shielding is off by default
+    assert!(lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.is_none());
+
+    // 4. Nothing to do while the UTXOs are below the threshold or don't have enough confirmations
+    let policy = AutoShieldPolicy {
+        threshold: 500_000,
+        min_confirmations: 1,
+        to_address: None,
+    };
+    lc.wallet.set_auto_shield(Some(policy.clone())).await.unwrap();
+    assert!(lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.is_none());
+
+    lc.wallet
+        .set_auto_shield(Some(AutoShieldPolicy {
+            threshold: 100_000,
+            min_confirmations: 6,
+            ..policy.clone()
+        }))
+        .await
+        .unwrap();
+    assert!(lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.is_none());
+
+    // 5. Only our own z-addresses can be auto-shielded to
+    assert!(lc
+        .wallet
+        .set_auto_shield(Some(AutoShieldPolicy {
+            to_address: Some(EXT_ZADDR.to_string()),
+            ..policy.clone()
+        }))
+        .await
+        .is_err());
+
+    // 6. Once the UTXO qualifies, a failure makes it wait before trying again, instead of failing after every sync
+    let policy = AutoShieldPolicy {
+        threshold: 100_000,
+        min_confirmations: 5,
+        ..policy
+    };
+    lc.wallet.set_auto_shield(Some(policy.clone())).await.unwrap();
+    let result = lc
+        .auto_shield_with(async { Err::<FakeTxProver, _>("No prover".to_string()) })
+        .await
+        .unwrap();
+    assert_eq!(result["error"], "No prover");
+    let result = lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.unwrap();
+    assert!(result["skipped"].is_string());
+
+    // 7. Changing the policy tries again right away, but not while the wallet is locked
+    lc.wallet.set_auto_shield(Some(policy)).await.unwrap();
+    lc.wallet.encrypt("password".to_string()).await.unwrap();
+    lc.wallet.lock().await.unwrap();
+    let result = lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.unwrap();
+    assert_eq!(result["skipped"], "The wallet is locked");
+    lc.wallet.unlock("password".to_string()).await.unwrap();
+    lc.wallet.remove_encryption("password".to_string()).await.unwrap();
+
+    // 8. Shield, without touching the progress of the user's sends
+    let progress_id = lc.wallet.get_send_progress().await.id;
+    let result = lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0]["from"], taddr);
+    let txid = result[0]["txid"].as_str().unwrap().to_string();
+    assert_eq!(lc.wallet.get_send_progress().await.id, progress_id);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 9. The tx is marked as auto-generated, and there's nothing left to shield
+    let list = lc.do_list_transactions(false).await;
+    let jv = list.members().find(|jv| jv["txid"] == txid).unwrap();
+    assert_eq!(jv["auto_shield"].as_bool().unwrap(), true);
+    assert_eq!(jv["unconfirmed"].as_bool().unwrap(), false);
+
+    assert_eq!(lc.wallet.tbalance(None).await, 0);
+    assert_eq!(lc.wallet.zbalance(None).await, tvalue - u64::from(DEFAULT_FEE));
+    assert!(lc.auto_shield_with(async { Ok(FakeTxProver {}) }).await.is_none());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn shield_from_taddrs() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+
+    // 2. Receive utxos at two t-addresses
+    lc.do_new_address("t").await.unwrap();
+    let (sk1, sk2) = {
+        let keys = lc.wallet.keys();
+        let keys = keys.read().await;
+        (keys.tkeys[0].clone(), keys.tkeys[1].clone())
+    };
+    let (taddr1, taddr2) = (sk1.address.clone(), sk2.address.clone());
+
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&sk1.pubkey().unwrap(), taddr1.clone(), 200_000);
+    ftx.add_t_output(&sk1.pubkey().unwrap(), taddr1.clone(), 300_000);
+    ftx.add_t_output(&sk2.pubkey().unwrap(), taddr2.clone(), 100_000);
+    let (_ttx, _) = fcbl.add_ftx(ftx);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 3. Sweeping both addresses together would link them, which the privacy policy doesn't allow
+    lc.wallet
+        .set_privacy_policy(PrivacyPolicy::AllowRevealedRecipients)
+        .await;
+    let zaddr = lc.wallet.keys().read().await.get_all_zaddresses()[0].clone();
+    let options = SendOptions {
+        send_max: true,
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(zaddr.as_str(), 0, None)], options)
+        .await
+        .is_err());
+
+    // Only the wallet's own t-addresses can be shielded
+    assert!(lc
+        .wallet
+        .get_utxos_by_taddr(&Some(vec![EXT_TADDR.to_string()]))
+        .await
+        .is_err());
+
+    // 4. Shield only the second address, to an explicit destination
+    let fee = u64::from(DEFAULT_FEE);
+    let results = lc
+        .shield_from_with(
+            async { Ok(FakeTxProver {}) },
+            Some(vec![taddr2.clone()]),
+            Some(zaddr.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["from"], taddr2);
+    assert_eq!(results[0]["value"].as_u64().unwrap(), 100_000 - fee);
+    assert!(results[0]["txid"].is_string());
+    assert_eq!(lc.wallet.tbalance(Some(taddr1.clone())).await, 500_000);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 5. Shield everything that's left, which is only the first address, in its own tx
+    let results = lc
+        .shield_from_with(async { Ok(FakeTxProver {}) }, None, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["from"], taddr1);
+    assert_eq!(results[0]["value"].as_u64().unwrap(), 500_000 - fee);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    assert_eq!(lc.wallet.tbalance(None).await, 0);
+    assert_eq!(lc.wallet.zbalance(None).await, 600_000 - 2 * fee);
+
+    // 6. An address with nothing to shield is reported, not left out
+    let results = lc
+        .shield_from_with(async { Ok(FakeTxProver {}) }, Some(vec![taddr2.clone()]), None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["from"], taddr2);
+    assert!(results[0]["error"].as_str().unwrap().contains("Nothing to shield"));
+    assert!(lc
+        .shield_from_with(async { Ok(FakeTxProver {}) }, None, None)
+        .await
+        .is_err());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn scheduled_payments() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Mine 10 blocks, and receive some funds that get confirmed
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    let value = 1_000_000;
+    fcbl.add_tx_paying(&extfvk1, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+    assert_eq!(lc.wallet.last_scanned_height().await, 16);
+
+    // 2. Recipients are checked when the payment is scheduled
+    let recipient = ScheduledRecipient {
+        address: EXT_ZADDR.to_string(),
+        value: 10_000,
+        memo: Some("Monthly payout".to_string()),
+    };
+    assert!(lc
+        .wallet
+        .add_scheduled_payment(
+            vec![ScheduledRecipient {
+                address: "zs1invalid".to_string(),
+                ..recipient.clone()
+            }],
+            DueAt::Height(17),
+            0
+        )
+        .await
+        .is_err());
+    assert!(lc
+        .wallet
+        .add_scheduled_payment(
+            vec![ScheduledRecipient {
+                address: EXT_TADDR.to_string(),
+                ..recipient.clone()
+            }],
+            DueAt::Height(17),
+            0
+        )
+        .await
+        .is_err());
+
+    // 3. One payment due at the next block and every 10 blocks after that, and one due in an hour
+    let recurring = lc
+        .wallet
+        .add_scheduled_payment(vec![recipient.clone()], DueAt::Height(17), 10)
+        .await
+        .unwrap();
+    let later = lc
+        .wallet
+        .add_scheduled_payment(
+            vec![ScheduledRecipient {
+                address: EXT_TADDR.to_string(),
+                value: 20_000,
+                memo: None,
+            }],
+            DueAt::Time(now() + 3600),
+            0,
+        )
+        .await
+        .unwrap();
+
+    let due = lc.do_due_payments().await;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0]["id"].as_u64().unwrap(), recurring);
+
+    // 4. Pay it. The txid is recorded, and the payment moves on to its next due height
+    let results = lc.pay_due_payments_with(FakeTxProver {}).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["id"].as_u64().unwrap(), recurring);
+    let txid = results[0]["txid"].as_str().unwrap().to_string();
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    {
+        let payments = lc.wallet.scheduled_payments.read().await;
+        let p = payments.payments.iter().find(|p| p.id == recurring).unwrap();
+        assert_eq!(p.next_due, DueAt::Height(27));
+        assert_eq!(p.history.len(), 1);
+        assert_eq!(p.history[0].due, DueAt::Height(17));
+        assert_eq!(p.history[0].txid, txid);
+    }
+
+    let list = lc.do_list_transactions(false).await;
+    let jv = list.members().find(|jv| jv["txid"] == txid).unwrap();
+    assert_eq!(jv["outgoing_metadata"][0]["address"], EXT_ZADDR.to_string());
+    assert_eq!(jv["outgoing_metadata"][0]["value"].as_u64().unwrap(), 10_000);
+    assert_eq!(jv["outgoing_metadata"][0]["memo"], "Monthly payout");
+
+    // 5. Nothing is due until block 27
+    assert_eq!(lc.do_due_payments().await.len(), 0);
+    mine_random_blocks(&mut fcbl, &data, &lc, 8).await;
+    assert_eq!(lc.do_due_payments().await.len(), 0);
+    mine_random_blocks(&mut fcbl, &data, &lc, 1).await;
+    assert_eq!(lc.do_due_payments().await.len(), 1);
+
+    // 6. The schedule is saved with the wallet
+    let mut wallet_bytes = vec![];
+    lc.wallet.write(&mut wallet_bytes).await.unwrap();
+    let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    assert_eq!(
+        lw.scheduled_payments.read().await.payments,
+        lc.wallet.scheduled_payments.read().await.payments
+    );
+
+    // 7. Cancel the later payment
+    lc.wallet.remove_scheduled_payment(later).await.unwrap();
+    assert!(lc.wallet.remove_scheduled_payment(later).await.is_err());
+    assert_eq!(lc.wallet.scheduled_payments.read().await.payments.len(), 1);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn send_batch() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Receive two notes, so two Txs can be sent before either one's change is confirmed
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    let dir = TempDir::new("batch").unwrap();
+    let path = dir.path().join("payouts.csv");
+    let path_str = path.to_str().unwrap();
+
+    // 2. Nothing is sent if any row is invalid, and every invalid row is reported
+    let csv = format!(
+        "address,amount,memo\n{},1000,Hello\nnot-an-address,1000\n{},lots\n{},1000,memo to a taddr\n",
+        EXT_ZADDR, EXT_ZADDR, EXT_TADDR
+    );
+    fs::write(&path, csv).unwrap();
+
+    let report = lc.send_batch_with(FakeTxProver {}, path_str).await.unwrap();
+    assert_eq!(report["sent"].as_usize().unwrap(), 0);
+    assert_eq!(report["errors"].as_usize().unwrap(), 3);
+    assert_eq!(report["rows"][0]["status"], "not_sent");
+    assert_eq!(report["rows"][1]["row"].as_usize().unwrap(), 3);
+    assert_eq!(report["rows"][1]["status"], "error");
+    assert_eq!(report["txids"].len(), 0);
+
+    // 3. A batch that is too big for one Tx is split
+    let count = MAX_BATCH_OUTPUTS + 1;
+    let mut csv = format!("{},2000,\"Hi, there\"\n", EXT_ZADDR);
+    for _ in 1..count {
+        csv.push_str(&format!("{},1000\n", EXT_TADDR));
+    }
+    fs::write(&path, csv).unwrap();
+
+    let report = lc.send_batch_with(FakeTxProver {}, path_str).await.unwrap();
+    assert_eq!(report["sent"].as_usize().unwrap(), count);
+    assert_eq!(report["errors"].as_usize().unwrap(), 0);
+    assert_eq!(report["total_sent"].as_u64().unwrap(), 2000 + 1000 * (count as u64 - 1));
+    assert_eq!(report["txids"].len(), 2);
+    assert_eq!(report["rows"][0]["txid"], report["txids"][0]);
+    assert_eq!(report["rows"][count - 1]["txid"], report["txids"][1]);
+
+    // Running the same file again doesn't pay anyone twice
+    let rerun = lc.send_batch_with(FakeTxProver {}, path_str).await.unwrap();
+    assert_eq!(rerun["already_sent"].as_usize().unwrap(), count);
+    assert_eq!(rerun["rows"][0]["status"], "already_sent");
+    assert_eq!(rerun["txids"], report["txids"]);
+
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let list = lc.do_list_transactions(false).await;
+    let first = report["txids"][0].as_str().unwrap();
+    let jv = list.members().find(|jv| jv["txid"] == first).unwrap();
+    assert_eq!(jv["outgoing_metadata"].len(), MAX_BATCH_OUTPUTS);
+    let memo_out = jv["outgoing_metadata"]
+        .members()
+        .find(|o| o["address"] == EXT_ZADDR)
+        .unwrap();
+    assert_eq!(memo_out["memo"], "Hi, there");
+    assert_eq!(memo_out["value"].as_u64().unwrap(), 2000);
+
+    // 4. A JSON file works the same way, once the change is confirmed
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+    let path = dir.path().join("payouts.json");
+    fs::write(&path, format!(r#"[{{"address": "{}", "amount": 3000}}]"#, EXT_TADDR)).unwrap();
+    let report = lc
+        .send_batch_with(FakeTxProver {}, path.to_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(report["rows"][0]["status"], "sent");
+
+    // 5. Nothing is sent if the wallet can't pay the whole batch
+    let txns_before = lc.do_list_transactions(false).await.len();
+    let mut csv = String::new();
+    for _ in 0..MAX_BATCH_OUTPUTS + 1 {
+        csv.push_str(&format!("{},50000\n", EXT_TADDR));
+    }
+    fs::write(&path, csv).unwrap();
+    let e = lc
+        .send_batch_with(FakeTxProver {}, path.to_str().unwrap())
+        .await
+        .unwrap_err();
+    assert!(e.contains("Not enough funds"));
+    assert_eq!(lc.do_list_transactions(false).await.len(), txns_before);
+
+    assert!(lc
+        .send_batch_with(FakeTxProver {}, "/does/not/exist.csv")
+        .await
+        .is_err());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn broadcast_servers() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+    let (data2, config2, ready_rx2, stop_tx2, h2) = create_test_server(UnitTestNetwork).await;
+    let (data3, config3, ready_rx3, stop_tx3, h3) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+    ready_rx2.await.unwrap();
+    ready_rx3.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Receive three notes, so three Txs can be sent
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 2. With only the wallet's own server, propagation is checked with it alone
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, 1000, None)]).await.unwrap();
+    let j = lc.do_check_propagation(&txid, 0).await.unwrap();
+    assert_eq!(j["propagated"].as_bool().unwrap(), true);
+    assert_eq!(j["servers"].len(), 1);
+    assert!(lc
+        .wallet
+        .set_broadcast_servers(vec!["not a server".to_string()])
+        .await
+        .is_err());
+
+    let server2 = config2.server.to_string();
+    let server3 = config3.server.to_string();
+    lc.wallet
+        .set_broadcast_servers(vec![server2.clone(), server3.clone()])
+        .await
+        .unwrap();
+    assert_eq!(lc.get_broadcast_servers().await.len(), 3);
+
+    // 3. A Tx sent before the other servers were added is only on the wallet's own server
+    let j = lc.do_check_propagation(&txid, 0).await.unwrap();
+    assert_eq!(j["propagated"].as_bool().unwrap(), false);
+    assert_eq!(j["seen_by"].as_u64().unwrap(), 1);
+    assert_eq!(j["servers"][0]["seen"].as_bool().unwrap(), true);
+    assert_eq!(j["servers"][2]["seen"].as_bool().unwrap(), false);
+
+    // Once the network passes it on to the others, it has propagated
+    let relayed = data.read().await.sent_txns[0].clone();
+    data2.write().await.sent_txns.push(relayed.clone());
+    data3.write().await.sent_txns.push(relayed);
+
+    let j = lc.do_check_propagation(&txid, 0).await.unwrap();
+    assert_eq!(j["propagated"].as_bool().unwrap(), true);
+    assert_eq!(j["seen_by"].as_u64().unwrap(), 3);
+
+    // 4. A send goes to every server
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, 1000, None)]).await.unwrap();
+    assert_eq!(data.read().await.sent_txns.len(), 2);
+    assert_eq!(data2.read().await.sent_txns.len(), 2);
+    assert_eq!(data3.read().await.sent_txns.len(), 2);
+
+    let j = lc.do_check_propagation(&txid, 0).await.unwrap();
+    assert_eq!(j["propagated"].as_bool().unwrap(), true);
+    assert_eq!(j["servers"].len(), 3);
+    assert_eq!(j["servers"][1]["server"], server2);
+    assert_eq!(j["servers"][1]["broadcast"], "accepted");
+    assert_eq!(j["servers"][2]["broadcast"], "accepted");
+    assert_eq!(j["servers"][2]["seen"].as_bool().unwrap(), true);
+
+    // 5. The broadcast servers are saved with the wallet
+    let buf = lc.do_save_to_buffer().await.unwrap();
+    let wallet2 = LightWallet::read(&buf[..], &config).await.unwrap();
+    assert_eq!(wallet2.get_broadcast_servers().await, vec![server2, server3]);
+
+    // 6. Once a server is down, a send still goes through the others, but doesn't propagate to it
+    stop_tx2.send(true).unwrap();
+    h2.await.unwrap();
+
+    let txid = lc.test_do_send(vec![(EXT_ZADDR, 1000, None)]).await.unwrap();
+    assert_eq!(data.read().await.sent_txns.len(), 3);
+    assert_eq!(data3.read().await.sent_txns.len(), 3);
+
+    let j = lc.do_check_propagation(&txid, 0).await.unwrap();
+    assert_eq!(j["propagated"].as_bool().unwrap(), false);
+    assert_eq!(j["servers"][0]["seen"].as_bool().unwrap(), true);
+    assert_ne!(j["servers"][1]["broadcast"], "accepted");
+    assert_eq!(j["servers"][1]["seen"].as_bool().unwrap(), false);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+    stop_tx3.send(true).unwrap();
+    h3.await.unwrap();
+}
+
+#[tokio::test]
+async fn decode_raw_tx() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Receive a note
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+    let extfvk1 = lc.wallet.keys().read().await.get_all_extfvks()[0].clone();
+    fcbl.add_tx_paying(&extfvk1, 1_000_000);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    // 2. Send to a z-address and a t-address, and decode the Tx that was broadcast
+    let txid = lc
+        .test_do_send(vec![
+            (EXT_ZADDR, 20_000, Some("Hello".to_string())),
+            (EXT_TADDR, 30_000, None),
+        ])
+        .await
+        .unwrap();
+    let raw_tx = data.read().await.sent_txns[0].data.clone();
+
+    let j = lc.do_decode_tx(&hex::encode(&raw_tx)).await.unwrap();
+    assert_eq!(j["txid"], txid);
+    assert_eq!(j["size"].as_usize().unwrap(), raw_tx.len());
+    assert_eq!(j["in_wallet"].as_bool().unwrap(), true);
+    assert_eq!(j["fee"].as_u64().unwrap(), u64::from(DEFAULT_FEE));
+
+    assert_eq!(j["sapling_spends"].len(), 1);
+    assert_eq!(j["sapling_spends"][0]["ours"].as_bool().unwrap(), true);
+    assert_eq!(j["sapling_spends"][0]["value"].as_u64().unwrap(), 1_000_000);
+
+    assert_eq!(j["transparent_outputs"].len(), 1);
+    assert_eq!(j["transparent_outputs"][0]["address"], EXT_TADDR);
+    assert_eq!(j["transparent_outputs"][0]["value"].as_u64().unwrap(), 30_000);
+    assert_eq!(j["transparent_outputs"][0]["ours"].as_bool().unwrap(), false);
+
+    let sent = j["sapling_outputs"]
+        .members()
+        .find(|o| o["direction"] == "sent")
+        .unwrap();
+    assert_eq!(sent["address"], EXT_ZADDR);
+    assert_eq!(sent["value"].as_u64().unwrap(), 20_000);
+    assert_eq!(sent["memo"], "Hello");
+    assert_eq!(sent["ours"].as_bool().unwrap(), false);
+
+    let change = j["sapling_outputs"]
+        .members()
+        .find(|o| o["direction"] == "received")
+        .unwrap();
+    assert_eq!(change["ours"].as_bool().unwrap(), true);
+    assert_eq!(
+        change["value"].as_u64().unwrap(),
+        1_000_000 - 20_000 - 30_000 - u64::from(DEFAULT_FEE)
+    );
+
+    // 3. Things that aren't transactions
+    assert!(lc.do_decode_tx("not hex").await.is_err());
+    assert!(lc.do_decode_tx("0400").await.is_err());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn diversified_addresses() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+
+    // 1. A diversified address is a new address, but not a new key
+    let zaddr = lc.wallet.keys().read().await.get_all_zaddresses()[0].clone();
+    let daddr1 = lc.do_new_diversified_address(None).await.unwrap()[0]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_ne!(daddr1, zaddr);
+    assert_eq!(lc.wallet.keys().read().await.zkeys.len(), 1);
+    assert_eq!(
+        lc.wallet.keys().read().await.get_all_zaddresses(),
+        vec![zaddr.clone(), daddr1.clone()]
+    );
+
+    // 2. Funds sent to it are found with the key's ivk, and are counted for the diversified address
+    let pa = match RecipientAddress::decode(&config.get_params(), &daddr1) {
+        Some(RecipientAddress::Shielded(pa)) => pa,
+        _ => panic!("Not a z-address"),
+    };
+    let value = 100_000;
+    fcbl.add_tx_paying_address(&pa, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    assert_eq!(lc.wallet.zbalance(None).await, value);
+    assert_eq!(lc.wallet.zbalance(Some(daddr1.clone())).await, value);
+    assert_eq!(lc.wallet.zbalance(Some(zaddr.clone())).await, 0);
+
+    // 3. More addresses can be made from the key of any of its addresses, but only for keys in this wallet
+    let daddr2 = lc.do_new_diversified_address(Some(&daddr1)).await.unwrap()[0]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(daddr2 != zaddr && daddr2 != daddr1);
+    assert!(lc.do_new_diversified_address(Some(EXT_ZADDR)).await.is_err());
+    assert!(lc.do_new_diversified_address(Some("zs1notanaddress")).await.is_err());
+
+    // 4. The addresses are saved with the wallet
+    let mut wallet_bytes = vec![];
+    lc.wallet.write(&mut wallet_bytes).await.unwrap();
+    let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    assert_eq!(lw.keys().read().await.get_all_zaddresses(), vec![zaddr, daddr1, daddr2]);
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn hd_address_discovery() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    // A wallet restored from its seed, which starts out with just its first addresses
+    let lc = LightClient::test_new(&config, Some(TEST_SEED.to_string()), 0)
+        .await
+        .unwrap();
+    lc.wallet.set_hd_discovery_pending(true);
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Every 15th z-address of the seed, up to the 91st, has received funds. Each is within a gap of the one
+    // before it, but can only be found once that one has been, so finding them all takes six rescans
+    let bip39_seed = Seed::new(&Mnemonic::from_phrase(TEST_SEED, Language::English).unwrap(), "");
+    fcbl.add_blocks(10);
+    for pos in (15..=90).step_by(15) {
+        let (_, extfvk, _) = Keys::get_zaddr_from_bip39seed(&config, bip39_seed.as_bytes(), pos);
+        fcbl.add_tx_paying(&extfvk, if pos == 15 { 100_000 } else { 10_000 });
+    }
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 2. The sync rescanned from the birthday until all of them were found, and left a full gap after the last one
+    assert_eq!(lc.wallet.zbalance(None).await, 150_000);
+    assert_eq!(
+        lc.wallet.keys().read().await.zkeys.len(),
+        91 + GAP_RULE_UNUSED_ADDRESSES
+    );
+    assert_eq!(lc.wallet.keys().read().await.tkeys.len(), GAP_RULE_UNUSED_ADDRESSES);
+    assert!(!lc.wallet.is_hd_discovery_pending());
+
+    // 3. Once discovery is done, syncs don't add addresses any more
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+    assert_eq!(
+        lc.wallet.keys().read().await.zkeys.len(),
+        91 + GAP_RULE_UNUSED_ADDRESSES
+    );
+
+    // 4. The notes found by the rescans can be spent together
+    lc.test_do_send(vec![(EXT_ZADDR, 120_000, None)]).await.unwrap();
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn hd_account_discovery() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    // A wallet restored from its seed only has the default account
+    let lc = LightClient::test_new(&config, Some(TEST_SEED.to_string()), 0)
+        .await
+        .unwrap();
+    lc.wallet.set_hd_discovery_pending(true);
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    // 1. Accounts 1 and 2 of the seed have received funds at their z-keys, which are derived from their ids
+    let bip39_seed = Seed::new(&Mnemonic::from_phrase(TEST_SEED, Language::English).unwrap(), "");
+    let (_, extfvk1, _) = Keys::get_zaddr_from_bip39seed(&config, bip39_seed.as_bytes(), account_zip32_index(1));
+    let (_, extfvk2, _) = Keys::get_zaddr_from_bip39seed(&config, bip39_seed.as_bytes(), account_zip32_index(2));
+
+    fcbl.add_blocks(10);
+    fcbl.add_tx_paying(&extfvk1, 100_000);
+    fcbl.add_tx_paying(&extfvk2, 50_000);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    // 2. The sync found both accounts, with their t-addresses at BIP44 account' 1 and 2, and dropped the
+    // unused account it looked at after them
+    assert!(!lc.wallet.is_hd_discovery_pending());
+    {
+        let keys = lc.wallet.keys().read().await;
+        assert_eq!(
+            keys.get_accounts().iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(keys.get_account(1).unwrap().name, "Account 1");
+
+        let (zaddrs, taddrs) = keys.get_account_addresses(1);
+        assert_eq!(
+            zaddrs,
+            vec![encode_payment_address(
+                config.hrp_sapling_address(),
+                &extfvk1.default_address().1
+            )]
+        );
+        assert_eq!(taddrs.len(), GAP_RULE_UNUSED_ADDRESSES);
+        assert_eq!(
+            taddrs[0],
+            WalletTKey::new_hdkey_for_account(&config, 1, 0, bip39_seed.as_bytes()).address
+        );
+    }
+    assert_eq!(
+        lc.do_account_balance(1).await.unwrap()["zbalance"].as_u64().unwrap(),
+        100_000
+    );
+    assert_eq!(
+        lc.do_account_balance(2).await.unwrap()["zbalance"].as_u64().unwrap(),
+        50_000
+    );
+
+    // 3. The accounts' funds aren't spent by a send that isn't given an account
+    assert_eq!(lc.wallet.zbalance(None).await, 150_000);
+    assert!(lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.is_err());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn hd_addresses_need_passphrase() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    // An unencrypted wallet with a passphrase, that was opened again, so the passphrase hasn't been entered
+    let mut lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut wallet_bytes = vec![];
+    LightWallet::new(
+        config.clone(),
+        Some(TEST_SEED.to_string()),
+        Some("secret".to_string()),
+        0,
+        1,
+        1,
+    )
+    .unwrap()
+    .write(&mut wallet_bytes)
+    .await
+    .unwrap();
+    lc.wallet = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    lc.set_wallet_initial_state(0).await;
+    assert!(lc.wallet.keys().read().await.needs_passphrase());
+
+    let mut fcbl = FakeCompactBlockList::new(0);
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+
+    // 1. A send to the wallet's last HD t-address would add HD addresses, so it is refused
+    let tkey = lc.wallet.keys().read().await.tkeys[0].clone();
+    let taddr = tkey.address.clone();
+    let e = lc.test_do_send(vec![(taddr.as_str(), 10_000, None)]).await.unwrap_err();
+    assert!(e.contains("passphrase"), "{}", e);
+
+    // 2. Receiving at it fails the sync, which isn't saved
+    let mut ftx = FakeTransaction::new();
+    ftx.add_t_output(&tkey.pubkey().unwrap(), taddr.clone(), 100_000);
+    fcbl.add_ftx(ftx);
+    data.write().await.add_blocks(fcbl.into_compact_blocks());
+    data.write().await.add_txns(fcbl.into_txns());
+
+    let e = lc.do_sync(true).await.unwrap_err();
+    assert!(e.contains("passphrase"), "{}", e);
+    assert_eq!(lc.wallet.last_scanned_height().await, 10);
+    assert_eq!(lc.wallet.keys().read().await.tkeys.len(), 1);
+
+    // 3. Once the passphrase is entered, the sync adds the next HD t-addresses and finds the funds
+    lc.do_set_passphrase("secret".to_string()).await.unwrap();
+    lc.do_sync(true).await.unwrap();
+    assert_eq!(lc.wallet.last_scanned_height().await, 11);
+    assert_eq!(lc.wallet.keys().read().await.tkeys.len(), 1 + GAP_RULE_UNUSED_ADDRESSES);
+    assert_eq!(lc.wallet.tbalance(Some(taddr)).await, 100_000);
+
+    // 4. Discovery that can't derive the keys it needs stays pending
+    let mut wallet_bytes = vec![];
+    lc.wallet.write(&mut wallet_bytes).await.unwrap();
+    lc.wallet = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    lc.wallet.set_hd_discovery_pending(true);
+    assert!(lc.do_sync(true).await.unwrap_err().contains("passphrase"));
+    assert!(lc.wallet.is_hd_discovery_pending());
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
+#[tokio::test]
+async fn wallet_accounts() {
+    let (data, config, ready_rx, stop_tx, h1) = create_test_server(UnitTestNetwork).await;
+
+    ready_rx.await.unwrap();
+
+    let lc = LightClient::test_new(&config, None, 0).await.unwrap();
+    let mut fcbl = FakeCompactBlockList::new(0);
+
+    mine_random_blocks(&mut fcbl, &data, &lc, 10).await;
+
+    // 1. Every wallet has the default account, with the addresses it already had
+    let accounts = lc.do_accounts().await;
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0]["id"].as_u32().unwrap(), 0);
+    let default_zaddr = accounts[0]["z_addresses"][0].as_str().unwrap().to_string();
+
+    // 2. A new account gets a z and t address of its own. Account names have to be unique
+    let account = lc.do_new_account("Savings").await.unwrap();
+    assert_eq!(account["id"].as_u32().unwrap(), 1);
+    assert_eq!(account["z_addresses"].len(), 1);
+    assert_eq!(account["t_addresses"].len(), 1);
+    let zaddr = account["z_addresses"][0].as_str().unwrap().to_string();
+    assert_ne!(zaddr, default_zaddr);
+    assert!(lc.do_new_account("Savings").await.is_err());
+    assert!(lc.do_account_balance(2).await.is_err());
+
+    // 3. Funds received at the account's address are only counted for the account
+    let pa = match RecipientAddress::decode(&config.get_params(), &zaddr) {
+        Some(RecipientAddress::Shielded(pa)) => pa,
+        _ => panic!("Not a z-address"),
+    };
+    let value = 100_000;
+    fcbl.add_tx_paying_address(&pa, value);
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+    mine_random_blocks(&mut fcbl, &data, &lc, 5).await;
+
+    assert_eq!(
+        lc.do_account_balance(1).await.unwrap()["zbalance"].as_u64().unwrap(),
+        value
+    );
+    assert_eq!(lc.do_account_balance(0).await.unwrap()["zbalance"].as_u64().unwrap(), 0);
+
+    // 4. The default account can't spend the account's funds, and neither can a send that isn't given an
+    // account. The account's change stays in it
+    let options = SendOptions {
+        account: Some(0),
+        ..Default::default()
+    };
+    assert!(lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, 10_000, None)], options)
+        .await
+        .is_err());
+    assert!(lc.test_do_send(vec![(EXT_ZADDR, 10_000, None)]).await.is_err());
+
+    let sent_value = 20_000;
+    let options = SendOptions {
+        account: Some(1),
+        ..Default::default()
+    };
+    let sent_txid = lc
+        .test_do_send_with_options(vec![(EXT_ZADDR, sent_value, None)], options)
+        .await
+        .unwrap();
+    fcbl.add_pending_sends(&data).await;
+    mine_pending_blocks(&mut fcbl, &data, &lc).await;
+
+    let change = value - sent_value - u64::from(DEFAULT_FEE);
+    assert_eq!(
+        lc.do_account_balance(1).await.unwrap()["zbalance"].as_u64().unwrap(),
+        change
+    );
+    assert_eq!(lc.do_account_balance(0).await.unwrap()["zbalance"].as_u64().unwrap(), 0);
+
+    // 5. The account's history has the receive and the send, and the default account's is empty
+    let list = lc.do_list_account_transactions(1, false).await.unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[1]["txid"], sent_txid);
+    assert_eq!(lc.do_list_account_transactions(0, false).await.unwrap().len(), 0);
+
+    // 6. The accounts are saved with the wallet
+    lc.do_rename_account(1, "Rainy day").await.unwrap();
+    let mut wallet_bytes = vec![];
+    lc.wallet.write(&mut wallet_bytes).await.unwrap();
+    let lw = LightWallet::read(&wallet_bytes[..], &config).await.unwrap();
+    let keys = lw.keys().read().await;
+    assert_eq!(keys.get_accounts().len(), 2);
+    assert_eq!(keys.get_account(1).unwrap().name, "Rainy day");
+    assert_eq!(keys.get_account_addresses(1).0, vec![zaddr]);
+
+    // 7. The account's other z-addresses are diversified addresses of its one key
+    let zkey_count = lc.wallet.keys().read().await.zkeys.len();
+    let new_zaddr = lc.do_new_account_address(1, "z").await.unwrap()[0]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(lc.wallet.keys().read().await.zkeys.len(), zkey_count);
+    assert_eq!(lc.wallet.keys().read().await.get_address_account(&new_zaddr), Some(1));
+
+    // Shutdown everything cleanly
+    stop_tx.send(true).unwrap();
+    h1.await.unwrap();
+}
+
 pub const EXT_TADDR: &str = "t1NoS6ZgaUTpmjkge2cVpXGcySasdYDrXqh";
 pub const EXT_ZADDR: &str = "zs1va5902apnzlhdu0pw9r9q7ca8s4vnsrp2alr6xndt69jnepn2v2qrj9vg3wfcnjyks5pg65g9dc";
 pub const EXT_ZADDR2: &str = "zs1fxgluwznkzm52ux7jkf4st5znwzqay8zyz4cydnyegt2rh9uhr9458z0nk62fdsssx0cqhy6lyv";
@@ -10,10 +10,6 @@ use crate::{
     },
 };
 
-// External C function to emit progress updates to the stream
-extern "C" {
-    fn emit_progress_update(progress: u32, total: u32);
-}
 use incrementalmerkletree::{bridgetree::BridgeTree, Position, Tree};
 use std::collections::BTreeMap;
 use std::convert::TryInto;
@@ -41,20 +37,25 @@ use orchard::Anchor;
 use std::sync::mpsc;
 use std::{
     cmp,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{Error, ErrorKind, Read, Write},
-    sync::{atomic::AtomicU64, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicU64},
+        Arc,
+    },
     time::SystemTime,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use zcash_address::unified::Receiver;
 use zcash_address::unified::{Address as UnifiedAddress, Encoding};
 use zcash_client_backend::{
     address,
-    encoding::{decode_extended_full_viewing_key, decode_extended_spending_key, encode_payment_address},
+    encoding::{
+        decode_extended_full_viewing_key, decode_extended_spending_key, decode_payment_address, encode_payment_address,
+    },
 };
 
-use zcash_primitives::consensus;
+use zcash_primitives::consensus::{self, BranchId};
 use zcash_primitives::memo::MemoBytes;
 use zcash_primitives::merkle_tree::incremental::{read_bridge, read_leu64_usize, write_bridge, write_usize_leu64};
 use zcash_primitives::merkle_tree::HashSer;
@@ -62,34 +63,59 @@ use zcash_primitives::sapling::prover::TxProver;
 use zcash_primitives::{
     legacy::Script,
     memo::Memo,
+    sapling::PaymentAddress,
     transaction::{
         builder::Builder,
         components::{amount::DEFAULT_FEE, OutPoint, TxOut},
+        Transaction, TxId,
     },
     zip32::ExtendedFullViewingKey,
 };
 
 use self::data::SpendableOrchardNote;
 use self::{
+    batch::BatchRecords,
     data::{BlockData, SaplingNoteData, Utxo, WalletZecPriceInfo},
     keys::Keys,
     message::Message,
+    proposal::{ProposalOutput, ProposalSaplingSpend, UnsignedProposal},
+    schedule::{DueAt, ScheduledPayment, ScheduledPayments, ScheduledRecipient},
     wallet_txns::WalletTxns,
+    walletaccount::DEFAULT_ACCOUNT,
 };
 
+pub(crate) mod batch;
 pub(crate) mod data;
 mod extended_key;
 pub(crate) mod keys;
+pub mod memo;
 pub(crate) mod message;
+pub(crate) mod proposal;
+pub(crate) mod schedule;
+pub mod seed_phrase;
 pub(crate) mod utils;
 pub(crate) mod wallet_txns;
+pub(crate) mod walletaccount;
 mod walletokey;
 pub(crate) mod wallettkey;
 mod walletzkey;
+pub mod zip321;
 
 pub const MERKLE_DEPTH: u8 = 32;
 pub const MAX_CHECKPOINTS: usize = 100;
 
+// The most notes a single consolidation Tx spends. Every spend needs its own proof, so this keeps the time it takes
+// to create each Tx reasonable.
+pub const CONSOLIDATION_BATCH_SIZE: usize = 25;
+
+// How many send progress updates are kept for a subscriber that hasn't read them yet
+const SEND_PROGRESS_CHANNEL_SIZE: usize = 100;
+
+// How many blocks auto-shielding waits after it first fails before trying again. The wait doubles with every
+// failure in a row, up to about a day of blocks.
+const AUTO_SHIELD_RETRY_BLOCKS: u64 = 10;
+const AUTO_SHIELD_MAX_RETRY_BLOCKS: u64 = 576;
+
 pub fn now() -> u64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -97,10 +123,39 @@ pub fn now() -> u64 {
         .as_secs()
 }
 
+// The stage a send is at. Most of the time is spent proving, which counts the spends and outputs proven so far in
+// the progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendPhase {
+    Idle,
+    Selecting,
+    Proving,
+    Signing,
+    Broadcasting,
+    Done,
+    Failed,
+}
+
+impl std::fmt::Display for SendPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SendPhase::Idle => "idle",
+            SendPhase::Selecting => "selecting",
+            SendPhase::Proving => "proving",
+            SendPhase::Signing => "signing",
+            SendPhase::Broadcasting => "broadcasting",
+            SendPhase::Done => "done",
+            SendPhase::Failed => "failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SendProgress {
     pub id: u32,
     pub is_send_in_progress: bool,
+    pub phase: SendPhase,
     pub progress: u32,
     pub total: u32,
     pub last_error: Option<String>,
@@ -112,6 +167,7 @@ impl SendProgress {
         SendProgress {
             id,
             is_send_in_progress: false,
+            phase: SendPhase::Idle,
             progress: 0,
             total: 0,
             last_error: None,
@@ -133,10 +189,283 @@ pub enum MemoDownloadOption {
     AllMemos,
 }
 
-#[derive(Debug, Clone, Copy)]
+// How transparent UTXOs are picked when a send can't be covered by shielded notes alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtxoSelectionStrategy {
+    // Spend every available UTXO. This is what shielding does.
+    All = 0,
+    // The fewest UTXOs (largest first) that cover the amount
+    Minimal,
+    // Only spend UTXOs from one t-address, failing if no single address has enough
+    SingleAddress,
+    // Spend the oldest UTXOs first
+    OldestFirst,
+    // Try to cover the amount from a single t-address, and only mix addresses if we have to
+    AvoidMixing,
+}
+
+impl Default for UtxoSelectionStrategy {
+    fn default() -> Self {
+        UtxoSelectionStrategy::AvoidMixing
+    }
+}
+
+impl std::str::FromStr for UtxoSelectionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "all" => Ok(UtxoSelectionStrategy::All),
+            "minimal" => Ok(UtxoSelectionStrategy::Minimal),
+            "single_address" | "singleaddress" => Ok(UtxoSelectionStrategy::SingleAddress),
+            "oldest_first" | "oldestfirst" => Ok(UtxoSelectionStrategy::OldestFirst),
+            "avoid_mixing" | "avoidmixing" => Ok(UtxoSelectionStrategy::AvoidMixing),
+            _ => Err(format!(
+                "Unknown UTXO selection strategy '{}'. Use one of: all, minimal, single_address, oldest_first, avoid_mixing",
+                s
+            )),
+        }
+    }
+}
+
+// Where the change of a send goes. Change always goes to one of the wallet's own z-addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangePolicy {
+    // Back to the z-address of the spent notes, or the first z-address if only UTXOs are spent
+    SameAsInput,
+    // A new diversified z-address for every transaction, so change is never sent to an address that was used
+    // before
+    FreshAddress,
+    // Always to this z-address
+    Designated(String),
+}
+
+impl Default for ChangePolicy {
+    fn default() -> Self {
+        ChangePolicy::SameAsInput
+    }
+}
+
+impl std::str::FromStr for ChangePolicy {
+    type Err = String;
+
+    // Anything that isn't one of the policy names is taken to be the designated change address
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" => Err("Change policy can't be empty".to_string()),
+            "same_as_input" | "sameasinput" => Ok(ChangePolicy::SameAsInput),
+            "fresh" | "fresh_address" | "freshaddress" => Ok(ChangePolicy::FreshAddress),
+            _ => Ok(ChangePolicy::Designated(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ChangePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangePolicy::SameAsInput => write!(f, "same_as_input"),
+            ChangePolicy::FreshAddress => write!(f, "fresh"),
+            ChangePolicy::Designated(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+// How much a Tx is allowed to reveal on chain, from the most private to the least private. Each policy allows
+// everything the ones before it allow (revealing a recipient also reveals the amount it gets, for example).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrivacyPolicy {
+    // Only shielded inputs and shielded recipients
+    FullPrivacy = 0,
+    // Allow spending UTXOs, which reveals the amounts moving into the shielded pool
+    AllowRevealedAmounts,
+    // Allow transparent recipients, which reveals who gets paid and how much
+    AllowRevealedRecipients,
+    // Allow spending the UTXOs of several t-addresses in one Tx, which links those addresses together
+    AllowLinkingTransparent,
+}
+
+impl Default for PrivacyPolicy {
+    // Doesn't restrict anything, which is how sends always worked
+    fn default() -> Self {
+        PrivacyPolicy::AllowLinkingTransparent
+    }
+}
+
+impl std::str::FromStr for PrivacyPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "full_privacy" | "fullprivacy" => Ok(PrivacyPolicy::FullPrivacy),
+            "allow_revealed_amounts" | "allowrevealedamounts" => Ok(PrivacyPolicy::AllowRevealedAmounts),
+            "allow_revealed_recipients" | "allowrevealedrecipients" => Ok(PrivacyPolicy::AllowRevealedRecipients),
+            "allow_linking_transparent" | "allowlinkingtransparent" => Ok(PrivacyPolicy::AllowLinkingTransparent),
+            _ => Err(format!(
+                "Unknown privacy policy '{}'. Use one of: full_privacy, allow_revealed_amounts, allow_revealed_recipients, allow_linking_transparent",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PrivacyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PrivacyPolicy::FullPrivacy => "full_privacy",
+            PrivacyPolicy::AllowRevealedAmounts => "allow_revealed_amounts",
+            PrivacyPolicy::AllowRevealedRecipients => "allow_revealed_recipients",
+            PrivacyPolicy::AllowLinkingTransparent => "allow_linking_transparent",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl PrivacyPolicy {
+    // Make sure a Tx spending `utxos` and paying `recipients` doesn't reveal more than this policy allows.
+    // Shielding only moves funds that are already public into the shielded pool, so only the linking of
+    // t-addresses is checked for it.
+    pub fn check(
+        &self,
+        transparent_only: bool,
+        utxos: &[Utxo],
+        recipients: &[address::RecipientAddress],
+    ) -> Result<(), String> {
+        let mut violations = vec![];
+
+        let taddrs = utxos.iter().map(|u| &u.address).collect::<HashSet<_>>();
+        if taddrs.len() > 1 {
+            violations.push((
+                PrivacyPolicy::AllowLinkingTransparent,
+                format!("link {} t-addresses by spending their UTXOs together", taddrs.len()),
+            ));
+        }
+
+        if !transparent_only {
+            let transparent_recipients = recipients
+                .iter()
+                .filter(|ra| match ra {
+                    address::RecipientAddress::Transparent(_) => true,
+                    address::RecipientAddress::Unified(ua) => ua.sapling().is_none(),
+                    address::RecipientAddress::Shielded(_) => false,
+                })
+                .count();
+            if transparent_recipients > 0 {
+                violations.push((
+                    PrivacyPolicy::AllowRevealedRecipients,
+                    format!(
+                        "reveal {} transparent recipient(s) and their amounts",
+                        transparent_recipients
+                    ),
+                ));
+            }
+
+            if !utxos.is_empty() {
+                violations.push((
+                    PrivacyPolicy::AllowRevealedAmounts,
+                    format!("reveal the amounts of the {} UTXO(s) it spends", utxos.len()),
+                ));
+            }
+        }
+
+        match violations.into_iter().filter(|(needed, _)| needed > self).max_by_key(|(needed, _)| *needed) {
+            Some((needed, reason)) => Err(format!(
+                "This transaction would {}, which the '{}' privacy policy doesn't allow. It needs at least the '{}' policy",
+                reason, self, needed
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+// A set of notes to be merged into a single note to self by one consolidation Tx
+#[derive(Debug, Clone)]
+pub struct ConsolidationBatch {
+    // The input ids of the notes to spend
+    pub input_ids: Vec<String>,
+    // The total value of the notes
+    pub value: u64,
+    // The z-address the merged note is sent to
+    pub to: String,
+}
+
+impl ConsolidationBatch {
+    // The value of the merged note, after paying the fee
+    pub fn consolidated_value(&self) -> u64 {
+        self.value.saturating_sub(u64::from(DEFAULT_FEE))
+    }
+}
+
+// Options that control which of the wallet's funds a send is allowed to spend
+#[derive(Debug, Clone, Default)]
+pub struct SendOptions {
+    // How UTXOs are picked, if transparent funds are needed
+    pub utxo_strategy: UtxoSelectionStrategy,
+
+    // If set, spend exactly these inputs instead of selecting them automatically. These are the input ids
+    // of the notes and UTXOs, as returned by `get_spendable_inputs()`
+    pub inputs: Option<Vec<String>>,
+
+    // If set, only spend funds that were received at this address
+    pub from_address: Option<String>,
+
+    // If set, only spend funds that were received by the addresses of this account, and keep the change in it.
+    // Otherwise the account is the one of `from_address` or `inputs`, or else the default account.
+    pub account: Option<u32>,
+
+    // Send everything that can be spent (or all of `inputs`, if set) to a single recipient. The amount of the
+    // recipient is ignored, it receives the selected value minus the fee, and there is no change output.
+    pub send_max: bool,
+
+    // The wallet is sending on its own (auto-shielding), so the send progress the user sees is left alone
+    pub(crate) background: bool,
+}
+
+// When the wallet shields its transparent funds by itself. This is checked after every sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoShieldPolicy {
+    // Only shield once the eligible UTXOs add up to at least this many zats
+    pub threshold: u64,
+    // Only shield UTXOs with at least this many confirmations
+    pub min_confirmations: u32,
+    // The z-address to shield to. If not set, the change policy picks it
+    pub to_address: Option<String>,
+}
+
+impl AutoShieldPolicy {
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let threshold = reader.read_u64::<LittleEndian>()?;
+        let min_confirmations = reader.read_u32::<LittleEndian>()?;
+        let to_address = Optional::read(&mut reader, |r| {
+            let address = Vector::read(r, |r| r.read_u8())?;
+            String::from_utf8(address).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+
+        Ok(Self {
+            threshold,
+            min_confirmations,
+            to_address,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.threshold)?;
+        writer.write_u32::<LittleEndian>(self.min_confirmations)?;
+        Optional::write(&mut writer, self.to_address.as_ref(), |w, a| {
+            Vector::write(w, a.as_bytes(), |w, b| w.write_u8(*b))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct WalletOptions {
     pub(crate) download_memos: MemoDownloadOption,
     pub(crate) spam_threshold: i64,
+    pub(crate) change_policy: ChangePolicy,
+    pub(crate) privacy_policy: PrivacyPolicy,
+    pub(crate) auto_shield: Option<AutoShieldPolicy>,
+
+    // Extra lightwalletd servers that every Tx is also broadcast to, besides the wallet's own server
+    pub(crate) broadcast_servers: Vec<String>,
 }
 
 impl Default for WalletOptions {
@@ -144,13 +473,17 @@ impl Default for WalletOptions {
         WalletOptions {
             download_memos: MemoDownloadOption::WalletMemos,
             spam_threshold: -1,
+            change_policy: ChangePolicy::default(),
+            privacy_policy: PrivacyPolicy::default(),
+            auto_shield: None,
+            broadcast_servers: vec![],
         }
     }
 }
 
 impl WalletOptions {
     pub fn serialized_version() -> u64 {
-        return 2;
+        return 6;
     }
 
     pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
@@ -174,9 +507,63 @@ impl WalletOptions {
             reader.read_i64::<LittleEndian>()?
         };
 
+        let change_policy = if version <= 2 {
+            ChangePolicy::default()
+        } else {
+            match reader.read_u8()? {
+                0 => ChangePolicy::SameAsInput,
+                1 => ChangePolicy::FreshAddress,
+                2 => {
+                    let address = Vector::read(&mut reader, |r| r.read_u8())?;
+                    ChangePolicy::Designated(
+                        String::from_utf8(address).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+                    )
+                }
+                v => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Bad change policy {}", v),
+                    ));
+                }
+            }
+        };
+
+        let privacy_policy = if version <= 3 {
+            PrivacyPolicy::default()
+        } else {
+            match reader.read_u8()? {
+                0 => PrivacyPolicy::FullPrivacy,
+                1 => PrivacyPolicy::AllowRevealedAmounts,
+                2 => PrivacyPolicy::AllowRevealedRecipients,
+                3 => PrivacyPolicy::AllowLinkingTransparent,
+                v => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Bad privacy policy {}", v),
+                    ));
+                }
+            }
+        };
+
+        let auto_shield = if version <= 4 {
+            None
+        } else {
+            Optional::read(&mut reader, |r| AutoShieldPolicy::read(r))?
+        };
+
+        let broadcast_servers = if version <= 5 {
+            vec![]
+        } else {
+            Vector::read(&mut reader, |r| utils::read_string(r))?
+        };
+
         Ok(Self {
             download_memos,
             spam_threshold,
+            change_policy,
+            privacy_policy,
+            auto_shield,
+            broadcast_servers,
         })
     }
 
@@ -186,7 +573,22 @@ impl WalletOptions {
 
         writer.write_u8(self.download_memos as u8)?;
 
-        writer.write_i64::<LittleEndian>(self.spam_threshold)
+        writer.write_i64::<LittleEndian>(self.spam_threshold)?;
+
+        match &self.change_policy {
+            ChangePolicy::SameAsInput => writer.write_u8(0)?,
+            ChangePolicy::FreshAddress => writer.write_u8(1)?,
+            ChangePolicy::Designated(address) => {
+                writer.write_u8(2)?;
+                Vector::write(&mut writer, address.as_bytes(), |w, b| w.write_u8(*b))?;
+            }
+        };
+
+        writer.write_u8(self.privacy_policy as u8)?;
+
+        Optional::write(&mut writer, self.auto_shield.as_ref(), |w, p| p.write(w))?;
+
+        Vector::write(&mut writer, &self.broadcast_servers, |w, s| utils::write_string(w, s))
     }
 }
 
@@ -216,29 +618,60 @@ pub struct LightWallet<P> {
     // The Orchard incremental tree
     pub(crate) orchard_witnesses: Arc<RwLock<Option<BridgeTree<MerkleHashOrchard, MERKLE_DEPTH>>>>,
 
-    // Progress of an outgoing tx
+    // Progress of an outgoing tx. Every change to it is also sent to the subscribers of the progress channel.
     send_progress: Arc<RwLock<SendProgress>>,
+    send_progress_tx: broadcast::Sender<SendProgress>,
 
     // The current price of ZEC. (time_fetched, price in USD)
     pub price: Arc<RwLock<WalletZecPriceInfo>>,
+
+    // Recurring or one-time payments to make from this wallet
+    pub(crate) scheduled_payments: Arc<RwLock<ScheduledPayments>>,
+
+    // Set while a wallet restored from its seed is still looking for the HD addresses that were used
+    hd_discovery_pending: AtomicBool,
+
+    // After auto-shielding fails, it isn't tried again until this height. (failures in a row, height)
+    auto_shield_backoff: Arc<RwLock<(u32, u64)>>,
+
+    // The rows of batch files that have been paid, so paying a file again skips them
+    pub(crate) batch_records: Arc<RwLock<BatchRecords>>,
 }
 
 impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
     pub fn serialized_version() -> u64 {
-        return 25;
+        return 28;
     }
 
     pub fn new(
         config: LightClientConfig<P>,
         seed_phrase: Option<String>,
+        passphrase: Option<String>,
         height: u64,
         num_zaddrs: u32,
         num_oaddrs: u32,
     ) -> io::Result<Self> {
-        let keys = Keys::new(&config, seed_phrase, num_zaddrs, num_oaddrs)
+        let keys = Keys::new(&config, seed_phrase, passphrase, num_zaddrs, num_oaddrs)
             .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
 
-        Ok(Self {
+        Ok(Self::with_keys(config, keys, height))
+    }
+
+    // A wallet without a seed, that watches the given viewing keys and t-addresses
+    pub fn new_watch_only(
+        config: LightClientConfig<P>,
+        viewing_keys: &[String],
+        taddrs: &[String],
+        height: u64,
+    ) -> io::Result<Self> {
+        let keys =
+            Keys::new_watch_only(&config, viewing_keys, taddrs).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        Ok(Self::with_keys(config, keys, height))
+    }
+
+    fn with_keys(config: LightClientConfig<P>, keys: Keys<P>, height: u64) -> Self {
+        Self {
             keys: Arc::new(RwLock::new(keys)),
             txns: Arc::new(RwLock::new(WalletTxns::new())),
             blocks: Arc::new(RwLock::new(vec![])),
@@ -248,8 +681,13 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             birthday: AtomicU64::new(height),
             verified_tree: Arc::new(RwLock::new(None)),
             send_progress: Arc::new(RwLock::new(SendProgress::new(0))),
+            send_progress_tx: broadcast::channel(SEND_PROGRESS_CHANNEL_SIZE).0,
             price: Arc::new(RwLock::new(WalletZecPriceInfo::new())),
-        })
+            scheduled_payments: Arc::new(RwLock::new(ScheduledPayments::new())),
+            hd_discovery_pending: AtomicBool::new(false),
+            auto_shield_backoff: Arc::new(RwLock::new((0, 0))),
+            batch_records: Arc::new(RwLock::new(BatchRecords::new())),
+        }
     }
 
     pub fn read_tree<H: Hashable + HashSer + Ord + Clone, R: Read>(mut reader: R) -> io::Result<BridgeTree<H, 32>> {
@@ -410,6 +848,20 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             Optional::read(&mut reader, |r| Self::read_tree(r))?
         };
 
+        let scheduled_payments = if version <= 25 {
+            ScheduledPayments::new()
+        } else {
+            ScheduledPayments::read(&mut reader)?
+        };
+
+        let hd_discovery_pending = if version <= 26 { false } else { reader.read_u8()? > 0 };
+
+        let batch_records = if version <= 27 {
+            BatchRecords::new()
+        } else {
+            BatchRecords::read(&mut reader)?
+        };
+
         let mut lw = Self {
             keys: Arc::new(RwLock::new(keys)),
             txns: Arc::new(RwLock::new(txns)),
@@ -420,7 +872,12 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             birthday: AtomicU64::new(birthday),
             verified_tree: Arc::new(RwLock::new(verified_tree)),
             send_progress: Arc::new(RwLock::new(SendProgress::new(0))),
+            send_progress_tx: broadcast::channel(SEND_PROGRESS_CHANNEL_SIZE).0,
             price: Arc::new(RwLock::new(price)),
+            scheduled_payments: Arc::new(RwLock::new(scheduled_payments)),
+            hd_discovery_pending: AtomicBool::new(hd_discovery_pending),
+            auto_shield_backoff: Arc::new(RwLock::new((0, 0))),
+            batch_records: Arc::new(RwLock::new(batch_records)),
         };
 
         // For old wallets, remove unused addresses
@@ -435,7 +892,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
 
         // Also make sure we have at least 1 unified address
         if lw.keys().read().await.okeys.len() == 0 {
-            lw.keys().write().await.add_oaddr();
+            if let Err(e) = lw.keys().write().await.add_oaddr() {
+                warn!("Couldn't add a unified address: {}", e);
+            }
         }
 
         Ok(lw)
@@ -483,6 +942,12 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             Self::write_tree(w, o)
         })?;
 
+        self.scheduled_payments.read().await.write(&mut writer)?;
+
+        writer.write_u8(self.is_hd_discovery_pending() as u8)?;
+
+        self.batch_records.read().await.write(&mut writer)?;
+
         Ok(())
     }
 
@@ -536,6 +1001,258 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         self.wallet_options.write().await.spam_threshold = value;
     }
 
+    // Turn auto-shielding on, or off with `None`
+    pub async fn set_auto_shield(&self, value: Option<AutoShieldPolicy>) -> Result<(), String> {
+        if let Some(to) = value.as_ref().and_then(|p| p.to_address.as_ref()) {
+            if !self.keys.read().await.get_all_zaddresses().contains(to) {
+                return Err(format!(
+                    "Can only auto-shield to one of this wallet's z-addresses, not {}",
+                    to
+                ));
+            }
+        }
+
+        self.wallet_options.write().await.auto_shield = value;
+        *self.auto_shield_backoff.write().await = (0, 0);
+        Ok(())
+    }
+
+    // Whether auto-shielding is waiting to be tried again at this height, after it failed
+    pub async fn is_auto_shield_backing_off(&self, height: u64) -> bool {
+        height < self.auto_shield_backoff.read().await.1
+    }
+
+    // Auto-shielding failed at this height. It is checked after every sync, so wait twice as long as the last time
+    // before trying again, instead of failing on every sync.
+    pub async fn auto_shield_failed(&self, height: u64) {
+        let mut backoff = self.auto_shield_backoff.write().await;
+        let wait = cmp::min(
+            AUTO_SHIELD_RETRY_BLOCKS << backoff.0.min(16),
+            AUTO_SHIELD_MAX_RETRY_BLOCKS,
+        );
+        *backoff = (backoff.0 + 1, height + wait);
+    }
+
+    pub async fn auto_shield_succeeded(&self) {
+        *self.auto_shield_backoff.write().await = (0, 0);
+    }
+
+    // The UTXOs the auto-shield policy would shield right now. Empty if auto-shielding is off, or if the eligible
+    // UTXOs don't add up to the threshold yet.
+    pub async fn get_auto_shield_utxos(&self) -> Vec<Utxo> {
+        let policy = match self.wallet_options.read().await.auto_shield.clone() {
+            Some(p) => p,
+            None => return vec![],
+        };
+
+        let last_height = self.last_scanned_height().await as i64;
+        let utxos = self
+            .spendable_utxos(&None)
+            .await
+            .into_iter()
+            .filter(|utxo| last_height - utxo.height as i64 + 1 >= policy.min_confirmations as i64)
+            .collect::<Vec<_>>();
+
+        let total = utxos.iter().map(|u| u.value).sum::<u64>();
+        if total < policy.threshold || total <= u64::from(DEFAULT_FEE) {
+            vec![]
+        } else {
+            utxos
+        }
+    }
+
+    // The spendable UTXOs of every t-address that has any, or of each of the ones in `from`, so they can be shielded
+    // one address at a time. An address in `from` without any is still returned, with no UTXOs.
+    pub async fn get_utxos_by_taddr(&self, from: &Option<Vec<String>>) -> Result<Vec<(String, Vec<Utxo>)>, String> {
+        let mut by_taddr = BTreeMap::<String, Vec<Utxo>>::new();
+        if let Some(from) = from {
+            let taddrs = self.keys.read().await.get_all_taddrs();
+            if let Some(taddr) = from.iter().find(|a| !taddrs.contains(a)) {
+                return Err(format!("{} is not a t-address in this wallet", taddr));
+            }
+            for taddr in from {
+                by_taddr.insert(taddr.clone(), vec![]);
+            }
+        }
+
+        for utxo in self.spendable_utxos(&None).await {
+            if from.as_ref().map_or(true, |from| from.contains(&utxo.address)) {
+                by_taddr.entry(utxo.address.clone()).or_default().push(utxo);
+            }
+        }
+
+        Ok(by_taddr.into_iter().collect())
+    }
+
+    // Mark the Tx that spent these UTXOs as created by the auto-shield policy
+    pub async fn mark_auto_shield(&self, utxos: &[Utxo]) {
+        let mut txns = self.txns.write().await;
+
+        let spent_in = utxos
+            .iter()
+            .filter_map(|utxo| {
+                txns.current
+                    .get(&utxo.txid)
+                    .and_then(|wtx| wtx.utxos.iter().find(|u| u.output_index == utxo.output_index))
+                    .and_then(|u| u.unconfirmed_spent.map(|(txid, _)| txid))
+            })
+            .collect::<HashSet<_>>();
+
+        for txid in spent_in {
+            if let Some(wtx) = txns.current.get_mut(&txid) {
+                wtx.auto_shield = true;
+            }
+        }
+    }
+
+    // Check that a payment of `value` with `memo` can be sent to `address`, before any Tx is built for it
+    pub fn check_recipient(&self, address: &str, value: u64, memo: Option<&String>) -> Result<(), String> {
+        if address::RecipientAddress::decode(&self.config.get_params(), address).is_none() {
+            return Err(format!("Invalid recipient address {}", address));
+        }
+        if value == 0 {
+            return Err(format!("The amount to pay {} needs to be more than 0", address));
+        }
+        if let Some(memo) = memo {
+            memo::check_memo(&self.config.get_params(), address, memo)?;
+        }
+
+        Ok(())
+    }
+
+    // Schedule a payment to these recipients, first due at `next_due` and then every `interval` blocks or seconds
+    // (or only once, if `interval` is 0). Returns the id of the new scheduled payment.
+    pub async fn add_scheduled_payment(
+        &self,
+        recipients: Vec<ScheduledRecipient>,
+        next_due: DueAt,
+        interval: u64,
+    ) -> Result<u64, String> {
+        if recipients.is_empty() {
+            return Err("A scheduled payment needs at least one recipient".to_string());
+        }
+
+        for r in recipients.iter() {
+            self.check_recipient(&r.address, r.value, r.memo.as_ref())?;
+        }
+
+        Ok(self
+            .scheduled_payments
+            .write()
+            .await
+            .add(recipients, next_due, interval))
+    }
+
+    pub async fn remove_scheduled_payment(&self, id: u64) -> Result<(), String> {
+        if self.scheduled_payments.write().await.remove(id) {
+            Ok(())
+        } else {
+            Err(format!("No scheduled payment with id {}", id))
+        }
+    }
+
+    // The scheduled payments that are due at the next block, or at `now`
+    pub async fn due_payments(&self, now: u64) -> Vec<ScheduledPayment> {
+        let height = self.last_scanned_height().await as u32 + 1;
+        self.scheduled_payments.read().await.due(height, now)
+    }
+
+    // Record that `txid` paid the scheduled payment `id`, and move it on to its next due date
+    pub async fn record_scheduled_payment(&self, id: u64, txid: String, now: u64) {
+        let height = self.last_scanned_height().await as u32 + 1;
+        if let Some(p) = self.scheduled_payments.write().await.get_mut(id) {
+            p.record_payment(txid, height, now);
+        }
+    }
+
+    // Set the extra servers Txs are broadcast to. Each one is a lightwalletd URI, like the wallet's own server.
+    pub async fn set_broadcast_servers(&self, servers: Vec<String>) -> Result<(), String> {
+        let mut uris = vec![];
+        for server in servers {
+            let s = if server.starts_with("http") {
+                server.clone()
+            } else {
+                format!("http://{}", server)
+            };
+            let uri = s
+                .parse::<http::Uri>()
+                .map_err(|e| format!("Invalid server '{}': {}", server, e))?;
+            if uri.host().is_none() {
+                return Err(format!("Invalid server '{}': no host", server));
+            }
+
+            let s = if uri.port().is_none() { format!("{}:443", s) } else { s };
+            if !uris.contains(&s) {
+                uris.push(s);
+            }
+        }
+
+        self.wallet_options.write().await.broadcast_servers = uris;
+        Ok(())
+    }
+
+    pub async fn get_broadcast_servers(&self) -> Vec<String> {
+        self.wallet_options.read().await.broadcast_servers.clone()
+    }
+
+    pub async fn set_privacy_policy(&self, value: PrivacyPolicy) {
+        self.wallet_options.write().await.privacy_policy = value;
+    }
+
+    pub async fn set_change_policy(&self, value: ChangePolicy) -> Result<(), String> {
+        if let ChangePolicy::Designated(address) = &value {
+            if !self.keys.read().await.get_all_zaddresses().contains(address) {
+                return Err(format!(
+                    "The change address {} has to be one of this wallet's z-addresses",
+                    address
+                ));
+            }
+        }
+
+        self.wallet_options.write().await.change_policy = value;
+        Ok(())
+    }
+
+    // Work out the z-address the change of a Tx goes to, according to the change policy. `input_address` is the
+    // address of the spent notes, if any. A fresh address is only handed out when `allow_new_address` is set, and
+    // it is a diversified address that is only kept once a Tx has been built with it (see
+    // `Keys::keep_diversified_addresses`). Otherwise the change goes to the input address.
+    // The change stays in the `account` that is spent from, so a designated address outside of the account is
+    // not used.
+    pub(crate) async fn get_change_address(
+        &self,
+        input_address: Option<String>,
+        allow_new_address: bool,
+        account: u32,
+    ) -> Result<PaymentAddress, String> {
+        let policy = self.wallet_options.read().await.change_policy.clone();
+        let account_zaddrs = self.keys.read().await.get_account_addresses(account).0;
+
+        let address = match policy {
+            ChangePolicy::Designated(address) if account_zaddrs.contains(&address) => address,
+            ChangePolicy::FreshAddress if allow_new_address => {
+                return self
+                    .keys
+                    .read()
+                    .await
+                    .next_change_address(account, input_address.as_deref())
+                    .map_err(|e| format!("Couldn't create a fresh change address: {}", e));
+            }
+            _ => match input_address {
+                Some(address) => address,
+                None => match account_zaddrs.into_iter().next() {
+                    Some(address) => address,
+                    None => return Err(format!("No z-address in account {} to send the change to", account)),
+                },
+            },
+        };
+
+        match decode_payment_address(self.config.hrp_sapling_address(), &address) {
+            Ok(Some(pa)) => Ok(pa),
+            _ => Err(format!("Invalid change address {}", address)),
+        }
+    }
+
     pub async fn get_birthday(&self) -> u64 {
         let birthday = self.birthday.load(std::sync::atomic::Ordering::SeqCst);
         if birthday == 0 {
@@ -560,36 +1277,60 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         self.send_progress.read().await.clone()
     }
 
-    // Set the previous send's status as an error
-    async fn set_send_error(&self, e: String) {
-        let mut p = self.send_progress.write().await;
-
-        p.is_send_in_progress = false;
-        p.last_error = Some(e);
+    // Get every change to the sending status from now on
+    pub fn subscribe_send_progress(&self) -> broadcast::Receiver<SendProgress> {
+        self.send_progress_tx.subscribe()
     }
 
-    // Set the previous send's status as success
-    async fn set_send_success(&self, txid: String) {
+    // Change the sending status, and tell the subscribers about it
+    async fn update_send_progress<F: FnOnce(&mut SendProgress)>(&self, f: F) {
         let mut p = self.send_progress.write().await;
+        f(&mut p);
 
-        p.is_send_in_progress = false;
-        p.last_txid = Some(txid);
+        // It is fine if nobody is listening
+        let _ = self.send_progress_tx.send(p.clone());
     }
 
-    // Reset the send progress status to blank
-    async fn reset_send_progress(&self) {
-        let mut g = self.send_progress.write().await;
-        let next_id = g.id + 1;
-
-        // Discard the old value, since we are replacing it
-        let _ = std::mem::replace(&mut *g, SendProgress::new(next_id));
+    async fn set_send_phase(&self, phase: SendPhase) {
+        self.update_send_progress(|p| p.phase = phase).await;
     }
 
-    pub async fn is_unlocked_for_spending(&self) -> bool {
-        self.keys.read().await.is_unlocked_for_spending()
+    // Set the previous send's status as an error
+    async fn set_send_error(&self, e: String) {
+        self.update_send_progress(|p| {
+            p.is_send_in_progress = false;
+            p.phase = SendPhase::Failed;
+            p.last_error = Some(e);
+        })
+        .await;
     }
 
-    pub async fn is_encrypted(&self) -> bool {
+    // Set the previous send's status as success
+    async fn set_send_success(&self, txid: String) {
+        self.update_send_progress(|p| {
+            p.is_send_in_progress = false;
+            p.phase = SendPhase::Done;
+            p.last_txid = Some(txid);
+        })
+        .await;
+    }
+
+    // Start the send progress of a new send
+    async fn reset_send_progress(&self) {
+        self.update_send_progress(|p| {
+            let mut next = SendProgress::new(p.id + 1);
+            next.is_send_in_progress = true;
+            next.phase = SendPhase::Selecting;
+            *p = next;
+        })
+        .await;
+    }
+
+    pub async fn is_unlocked_for_spending(&self) -> bool {
+        self.keys.read().await.is_unlocked_for_spending()
+    }
+
+    pub async fn is_encrypted(&self) -> bool {
         self.keys.read().await.is_encrypted()
     }
 
@@ -918,7 +1659,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             .flat_map(|tx| {
                 tx.utxos
                     .iter()
-                    .filter(|utxo| utxo.spent.is_none() && utxo.unconfirmed_spent.is_none())
+                    .filter(|utxo| utxo.spent.is_none() && utxo.unconfirmed_spent.is_none() && !utxo.frozen)
                     .filter(|utxo| match addr.as_ref() {
                         Some(a) => utxo.address == *a,
                         None => true,
@@ -1017,7 +1758,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
                 if tx.block <= BlockHeight::from_u32(anchor_height) {
                     tx.s_notes
                         .iter()
-                        .filter(|nd| nd.spent.is_none() && nd.unconfirmed_spent.is_none())
+                        .filter(|nd| nd.spent.is_none() && nd.unconfirmed_spent.is_none() && !nd.frozen)
                         .filter(|nd| {
                             // Check to see if we have this note's spending key and witnesses
                             keys.have_sapling_spending_key(&nd.extfvk) && nd.witnesses.len() > 0
@@ -1067,13 +1808,76 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         }
 
         if highest_account.unwrap() == 0 {
-            // Remove unused addresses
-            self.keys.write().await.tkeys.truncate(1);
+            // Remove unused addresses, keeping the ones of the other accounts
+            let mut keys = self.keys.write().await;
+            let first = keys.tkeys[0].clone();
+            keys.tkeys.retain(|k| k.account != DEFAULT_ACCOUNT);
+            keys.tkeys.insert(0, first);
         }
     }
 
+    pub fn is_hd_discovery_pending(&self) -> bool {
+        self.hd_discovery_pending.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    pub fn set_hd_discovery_pending(&self, value: bool) {
+        self.hd_discovery_pending
+            .store(value, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    // The t-addresses and the z-keys that have received anything
+    async fn used_hd_keys(&self) -> (HashSet<String>, Vec<ExtendedFullViewingKey>) {
+        let txns = self.txns.read().await;
+
+        let used_taddrs = txns
+            .current
+            .values()
+            .flat_map(|wtx| wtx.utxos.iter().map(|u| u.address.clone()))
+            .collect::<HashSet<_>>();
+
+        let mut used_extfvks = vec![];
+        for nd in txns.current.values().flat_map(|wtx| wtx.s_notes.iter()) {
+            if !used_extfvks.contains(&nd.extfvk) {
+                used_extfvks.push(nd.extfvk.clone());
+            }
+        }
+
+        (used_taddrs, used_extfvks)
+    }
+
+    // Add accounts until the last GAP_RULE_UNUSED_ACCOUNTS haven't received anything, and HD addresses until the
+    // last GAP_RULE_UNUSED_ADDRESSES t- and z-addresses of every account haven't. Returns how many were added.
+    // The wallet has to be rescanned to find the funds of the new addresses.
+    pub async fn discover_hd_addresses(&self) -> Result<usize, String> {
+        let (used_taddrs, used_extfvks) = self.used_hd_keys().await;
+
+        let mut keys = self.keys.write().await;
+        Ok(keys.fill_hd_account_gap(&used_taddrs, &used_extfvks)?
+            + keys.fill_hd_taddr_gap(&used_taddrs)?
+            + keys.fill_hd_zaddr_gap(&used_extfvks)?)
+    }
+
+    // Stop discovering HD addresses, dropping the accounts that were only added to look for more
+    pub async fn finish_hd_discovery(&self) {
+        let (used_taddrs, used_extfvks) = self.used_hd_keys().await;
+        self.keys
+            .write()
+            .await
+            .remove_unused_accounts(&used_taddrs, &used_extfvks);
+
+        self.set_hd_discovery_pending(false);
+    }
+
     pub async fn remove_unused_zaddrs(&self) {
-        let zaddrs = self.keys.read().await.get_all_zaddresses();
+        // Only the keys' default addresses, since the notes are matched to the key they were received by
+        let zaddrs = self
+            .keys
+            .read()
+            .await
+            .zkeys
+            .iter()
+            .map(|zk| encode_payment_address(self.config.hrp_sapling_address(), &zk.zaddress))
+            .collect::<Vec<_>>();
         if zaddrs.len() <= 1 {
             return;
         }
@@ -1098,8 +1902,11 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         }
 
         if highest_account.unwrap() == 0 {
-            // Remove unused addresses
-            self.keys().write().await.zkeys.truncate(1);
+            // Remove unused addresses, keeping the ones of the other accounts
+            let mut keys = self.keys.write().await;
+            let first = keys.zkeys[0].clone();
+            keys.zkeys.retain(|k| k.account != DEFAULT_ACCOUNT);
+            keys.zkeys.insert(0, first);
         }
     }
 
@@ -1163,7 +1970,99 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         });
     }
 
-    async fn select_orchard_notes(&self, target_amount: Amount) -> Vec<SpendableOrchardNote> {
+    // The account a send spends from and keeps its change in. A send that isn't given an `account` spends from
+    // the account of its `from_address` or of its `inputs`, and otherwise from the default account.
+    async fn send_account(&self, options: &SendOptions) -> Result<u32, String> {
+        if let Some(account) = options.account {
+            return Ok(account);
+        }
+
+        let addresses = match (&options.from_address, &options.inputs) {
+            (Some(address), _) => vec![address.clone()],
+            (None, Some(input_ids)) => {
+                let (notes, utxos) = self.get_spendable_inputs().await;
+                notes
+                    .into_iter()
+                    .filter(|(sn, _)| input_ids.contains(&sn.input_id()))
+                    .map(|(_, address)| address)
+                    .chain(
+                        utxos
+                            .into_iter()
+                            .filter(|u| input_ids.contains(&u.input_id()))
+                            .map(|u| u.address),
+                    )
+                    .collect()
+            }
+            (None, None) => return Ok(DEFAULT_ACCOUNT),
+        };
+
+        let keys = self.keys.read().await;
+        let mut accounts = addresses
+            .iter()
+            .filter_map(|address| keys.get_address_account(address))
+            .collect::<Vec<_>>();
+        accounts.sort();
+        accounts.dedup();
+
+        match accounts.as_slice() {
+            [] => Ok(DEFAULT_ACCOUNT),
+            [account] => Ok(*account),
+            _ => Err("The inputs belong to more than one account, send from one account at a time".to_string()),
+        }
+    }
+
+    // The addresses a send is allowed to spend from: the addresses of the account it spends from, or just its
+    // `from_address`, which has to belong to that account. The account's notes are included by the address they
+    // were received at, since that can be a diversified address the wallet didn't hand out itself.
+    async fn input_addresses(&self, options: &SendOptions) -> Result<HashSet<String>, String> {
+        let account = self.send_account(options).await?;
+
+        let (mut addresses, account_extfvks) = {
+            let keys = self.keys.read().await;
+            if keys.get_account(account).is_none() {
+                return Err(format!("No account {}", account));
+            }
+
+            if let Some(address) = &options.from_address {
+                if keys.get_address_account(address) != Some(account) {
+                    return Err(format!("{} is not an address of account {}", address, account));
+                }
+                return Ok(HashSet::from([address.clone()]));
+            }
+
+            let (zaddrs, taddrs) = keys.get_account_addresses(account);
+            let account_extfvks = keys
+                .zkeys
+                .iter()
+                .filter(|zk| zk.account == account)
+                .map(|zk| zk.extfvk.clone())
+                .collect::<Vec<_>>();
+
+            let addresses = zaddrs.into_iter().chain(taddrs).collect::<HashSet<_>>();
+
+            (addresses, account_extfvks)
+        };
+
+        let hrp = self.config.hrp_sapling_address();
+        addresses.extend(
+            self.txns
+                .read()
+                .await
+                .current
+                .values()
+                .flat_map(|wtx| wtx.s_notes.iter())
+                .filter(|nd| account_extfvks.contains(&nd.extfvk))
+                .filter_map(|nd| Self::sapling_note_address(hrp, nd)),
+        );
+
+        Ok(addresses)
+    }
+
+    async fn select_orchard_notes(
+        &self,
+        target_amount: Amount,
+        from_addresses: &Option<HashSet<String>>,
+    ) -> Vec<SpendableOrchardNote> {
         let keys = self.keys.read().await;
         let owt = self.orchard_witnesses.read().await;
         let orchard_witness_tree = owt.as_ref().unwrap();
@@ -1176,6 +2075,10 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             .iter()
             .flat_map(|(txid, tx)| tx.o_notes.iter().map(move |note| (*txid, note)))
             .filter(|(_, note)| note.note.value().inner() > 0)
+            .filter(|(_, note)| match from_addresses {
+                Some(a) => a.contains(&Self::orchard_ua_address(&self.config, &note.note.recipient())),
+                None => true,
+            })
             .filter_map(|(txid, note)| {
                 // Filter out notes that are already spent
                 if note.spent.is_some() || note.unconfirmed_spent.is_some() {
@@ -1234,19 +2137,24 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         o_notes
     }
 
-    async fn select_sapling_notes(&self, target_amount: Amount) -> Vec<SpendableSaplingNote> {
+    // All the sapling notes that can be spent right now, optionally only the ones received at `from_addresses`
+    async fn spendable_sapling_notes(&self, from_addresses: &Option<HashSet<String>>) -> Vec<SpendableSaplingNote> {
         let keys = self.keys.read().await;
-        let mut candidate_notes = self
-            .txns
+        self.txns
             .read()
             .await
             .current
             .iter()
             .flat_map(|(txid, tx)| tx.s_notes.iter().map(move |note| (*txid, note)))
             .filter(|(_, note)| note.note.value > 0)
+            .filter(|(_, note)| match from_addresses {
+                Some(a) => Self::sapling_note_address(self.config.hrp_sapling_address(), note)
+                    .map_or(false, |address| a.contains(&address)),
+                None => true,
+            })
             .filter_map(|(txid, note)| {
-                // Filter out notes that are already spent
-                if note.spent.is_some() || note.unconfirmed_spent.is_some() {
+                // Filter out notes that are already spent or have been frozen by the user
+                if note.spent.is_some() || note.unconfirmed_spent.is_some() || note.frozen {
                     None
                 } else {
                     // Get the spending key for the selected fvk, if we have it
@@ -1254,7 +2162,143 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
                     SpendableSaplingNote::from(txid, note, self.config.anchor_offset as usize, &extsk)
                 }
             })
-            .collect::<Vec<_>>();
+            .collect::<Vec<_>>()
+    }
+
+    // All the UTXOs that can be spent right now, optionally only the ones received at `from_addresses`
+    async fn spendable_utxos(&self, from_addresses: &Option<HashSet<String>>) -> Vec<Utxo> {
+        self.get_utxos()
+            .await
+            .into_iter()
+            .filter(|utxo| utxo.unconfirmed_spent.is_none() && utxo.spent.is_none() && !utxo.frozen)
+            .filter(|utxo| match from_addresses {
+                Some(a) => a.contains(&utxo.address),
+                None => true,
+            })
+            .collect::<Vec<_>>()
+    }
+
+    // List the notes and UTXOs that can be spent right now, along with the address they were received at.
+    // Their input ids can be passed in `SendOptions::inputs` to spend them explicitly.
+    pub async fn get_spendable_inputs(&self) -> (Vec<(SpendableSaplingNote, String)>, Vec<Utxo>) {
+        let notes = self
+            .spendable_sapling_notes(&None)
+            .await
+            .into_iter()
+            .filter_map(|sn| {
+                ExtendedFullViewingKey::from(&sn.extsk)
+                    .fvk
+                    .vk
+                    .to_payment_address(sn.diversifier)
+                    .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), &pa))
+                    .map(|address| (sn, address))
+            })
+            .collect();
+
+        (notes, self.spendable_utxos(&None).await)
+    }
+
+    // Work out how to merge the smallest `max_notes` spendable notes (or all of them) into one note per batch of
+    // at most `CONSOLIDATION_BATCH_SIZE` notes. Batches that would leave a note worth less than `dust_threshold`
+    // after the fee are not worth sending, and are returned separately as skipped.
+    // Returns the (batches, skipped batches)
+    pub async fn plan_consolidation(
+        &self,
+        max_notes: Option<usize>,
+        dust_threshold: u64,
+    ) -> Result<(Vec<ConsolidationBatch>, Vec<ConsolidationBatch>), String> {
+        // Like any send that isn't given an account, this only spends from the default account
+        let mut notes = self.get_spendable_inputs().await.0;
+        {
+            let keys = self.keys.read().await;
+            notes.retain(|(_, address)| keys.get_address_account(address) == Some(DEFAULT_ACCOUNT));
+        }
+        notes.sort_by_key(|(sn, _)| sn.note.value);
+        // A limit of 0 notes means no limit
+        if let Some(max_notes) = max_notes.filter(|n| *n > 0) {
+            notes.truncate(max_notes);
+        }
+
+        let mut batches = vec![];
+        let mut skipped = vec![];
+        for chunk in notes.chunks(CONSOLIDATION_BATCH_SIZE) {
+            // Merging a single note would only cost a fee
+            if chunk.len() < 2 {
+                continue;
+            }
+
+            // The merged note goes where the change of the batch would go, which is the address of its
+            // biggest note unless the change policy says otherwise
+            let input_address = chunk.last().map(|(_, address)| address.clone());
+            let to = self.get_change_address(input_address, false, DEFAULT_ACCOUNT).await?;
+
+            let batch = ConsolidationBatch {
+                input_ids: chunk.iter().map(|(sn, _)| sn.input_id()).collect(),
+                value: chunk.iter().map(|(sn, _)| sn.note.value).sum(),
+                to: encode_payment_address(self.config.hrp_sapling_address(), &to),
+            };
+
+            if batch.value <= u64::from(DEFAULT_FEE) || batch.consolidated_value() < dust_threshold {
+                skipped.push(batch);
+            } else {
+                batches.push(batch);
+            }
+        }
+
+        Ok((batches, skipped))
+    }
+
+    async fn is_input_frozen(&self, input_id: &String) -> bool {
+        self.txns.read().await.current.values().any(|tx| {
+            tx.s_notes.iter().any(|nd| nd.frozen && nd.input_id() == *input_id)
+                || tx.utxos.iter().any(|u| u.frozen && u.input_id() == *input_id)
+        })
+    }
+
+    // Freeze (or unfreeze) the notes and UTXOs with the given input ids. Frozen inputs are never picked when
+    // selecting inputs for a send or a shield, and can't be spent explicitly either until they are unfrozen.
+    // Returns the number of inputs that were changed
+    pub async fn set_inputs_frozen(&self, input_ids: &Vec<String>, frozen: bool) -> Result<usize, String> {
+        if input_ids.is_empty() {
+            return Err("Need at least one input id".to_string());
+        }
+
+        let mut txns = self.txns.write().await;
+
+        // Make sure all the ids are known before changing anything
+        if let Some(id) = input_ids.iter().find(|id| {
+            !txns.current.values().any(|tx| {
+                tx.s_notes.iter().any(|nd| nd.input_id() == **id) || tx.utxos.iter().any(|u| u.input_id() == **id)
+            })
+        }) {
+            return Err(format!("Input {} is not in this wallet", id));
+        }
+
+        let mut changed = 0;
+        for tx in txns.current.values_mut() {
+            for nd in tx.s_notes.iter_mut().filter(|nd| input_ids.contains(&nd.input_id())) {
+                if nd.frozen != frozen {
+                    nd.frozen = frozen;
+                    changed += 1;
+                }
+            }
+            for utxo in tx.utxos.iter_mut().filter(|u| input_ids.contains(&u.input_id())) {
+                if utxo.frozen != frozen {
+                    utxo.frozen = frozen;
+                    changed += 1;
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    async fn select_sapling_notes(
+        &self,
+        target_amount: Amount,
+        from_addresses: &Option<HashSet<String>>,
+    ) -> Vec<SpendableSaplingNote> {
+        let mut candidate_notes = self.spendable_sapling_notes(from_addresses).await;
         candidate_notes.sort_by(|a, b| b.note.value.cmp(&a.note.value));
 
         // Select the minimum number of notes required to satisfy the target value
@@ -1291,91 +2335,244 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         s_notes
     }
 
+    // Pick the UTXOs that cover `target_amount` from the candidates, according to the given strategy. If the
+    // candidates can't cover the amount, whatever was picked is returned so the caller can display a proper error.
+    fn select_utxos(mut candidates: Vec<Utxo>, target_amount: Amount, strategy: UtxoSelectionStrategy) -> Vec<Utxo> {
+        // Take UTXOs in order until the target is reached
+        fn take_until(utxos: Vec<Utxo>, target_amount: Amount) -> Vec<Utxo> {
+            let target = u64::from(target_amount);
+            utxos
+                .into_iter()
+                .scan(0u64, |running_total, utxo| {
+                    if *running_total >= target {
+                        None
+                    } else {
+                        *running_total += utxo.value;
+                        Some(utxo)
+                    }
+                })
+                .collect()
+        }
+
+        // Find the t-address that can cover the target with the fewest UTXOs
+        fn best_single_address(candidates: &Vec<Utxo>, target_amount: Amount) -> Option<Vec<Utxo>> {
+            let mut by_address: BTreeMap<String, Vec<Utxo>> = BTreeMap::new();
+            for utxo in candidates {
+                by_address.entry(utxo.address.clone()).or_default().push(utxo.clone());
+            }
+
+            by_address
+                .into_values()
+                .filter(|utxos| utxos.iter().map(|u| u.value).sum::<u64>() >= u64::from(target_amount))
+                .map(|mut utxos| {
+                    utxos.sort_by(|a, b| b.value.cmp(&a.value));
+                    take_until(utxos, target_amount)
+                })
+                .min_by_key(|utxos| utxos.len())
+        }
+
+        if target_amount <= Amount::zero() {
+            return vec![];
+        }
+
+        match strategy {
+            UtxoSelectionStrategy::All => candidates,
+            UtxoSelectionStrategy::Minimal => {
+                candidates.sort_by(|a, b| b.value.cmp(&a.value));
+                take_until(candidates, target_amount)
+            }
+            UtxoSelectionStrategy::OldestFirst => {
+                candidates.sort_by(|a, b| a.height.cmp(&b.height).then(b.value.cmp(&a.value)));
+                take_until(candidates, target_amount)
+            }
+            UtxoSelectionStrategy::SingleAddress => best_single_address(&candidates, target_amount).unwrap_or(vec![]),
+            UtxoSelectionStrategy::AvoidMixing => match best_single_address(&candidates, target_amount) {
+                Some(utxos) => utxos,
+                None => {
+                    candidates.sort_by(|a, b| b.value.cmp(&a.value));
+                    take_until(candidates, target_amount)
+                }
+            },
+        }
+    }
+
+    // Select the notes and UTXOs to cover `target_amount`, from the addresses a send with `options` is allowed to
+    // spend from. Nothing is selected if the options don't allow a send.
     async fn select_notes_and_utxos(
         &self,
         target_amount: Amount,
         transparent_only: bool,
         prefer_orchard: bool,
+        options: &SendOptions,
+    ) -> Result<(Vec<SpendableOrchardNote>, Vec<SpendableSaplingNote>, Vec<Utxo>, Amount), String> {
+        let from_addresses = self.input_addresses(options).await?;
+        Ok(self
+            .select_notes_and_utxos_from(
+                target_amount,
+                transparent_only,
+                prefer_orchard,
+                &Some(from_addresses),
+                options.utxo_strategy,
+            )
+            .await)
+    }
+
+    async fn select_notes_and_utxos_from(
+        &self,
+        target_amount: Amount,
+        transparent_only: bool,
+        prefer_orchard: bool,
+        from_addresses: &Option<HashSet<String>>,
+        utxo_strategy: UtxoSelectionStrategy,
     ) -> (Vec<SpendableOrchardNote>, Vec<SpendableSaplingNote>, Vec<Utxo>, Amount) {
-        // First, we pick all the transparent values, which allows the auto shielding
-        let utxos = self
-            .get_utxos()
-            .await
-            .iter()
-            .filter(|utxo| utxo.unconfirmed_spent.is_none() && utxo.spent.is_none())
-            .map(|utxo| utxo.clone())
-            .collect::<Vec<_>>();
+        let candidate_utxos = self.spendable_utxos(from_addresses).await;
 
-        // Check how much we've selected
-        let transparent_value_selected = utxos.iter().fold(Amount::zero(), |prev, utxo| {
-            (prev + Amount::from_u64(utxo.value).unwrap()).unwrap()
-        });
+        let sum_utxos = |utxos: &Vec<Utxo>| {
+            utxos.iter().fold(Amount::zero(), |prev, utxo| {
+                (prev + Amount::from_u64(utxo.value).unwrap()).unwrap()
+            })
+        };
 
-        // If we are allowed only transparent funds or we've selected enough then return
-        if transparent_only || transparent_value_selected >= target_amount {
-            return (vec![], vec![], utxos, transparent_value_selected);
+        // If we are allowed only transparent funds, we pick all the transparent values, which allows the auto shielding
+        if transparent_only {
+            let transparent_value_selected = sum_utxos(&candidate_utxos);
+            return (vec![], vec![], candidate_utxos, transparent_value_selected);
         }
 
+        // Shielded notes are always preferred, so transparent inputs are only added to cover whatever is left
         let mut orchard_value_selected = Amount::zero();
         let mut sapling_value_selected = Amount::zero();
 
         let mut o_notes = vec![];
         let mut s_notes = vec![];
 
-        let mut remaining_amount = target_amount - transparent_value_selected;
         if prefer_orchard {
             // Collect orchard notes first
-            o_notes = self.select_orchard_notes(remaining_amount.unwrap()).await;
+            o_notes = self.select_orchard_notes(target_amount, from_addresses).await;
             orchard_value_selected = o_notes.iter().fold(Amount::zero(), |prev, on| {
                 (prev + Amount::from_u64(on.note.value().inner()).unwrap()).unwrap()
             });
-
-            // If we've selected enough, just return
-            let selected_value = (orchard_value_selected + transparent_value_selected).unwrap();
-            if selected_value > target_amount {
-                return (o_notes, vec![], utxos, selected_value);
-            }
         } else {
             // Collect sapling notes first
-            s_notes = self.select_sapling_notes(remaining_amount.unwrap()).await;
+            s_notes = self.select_sapling_notes(target_amount, from_addresses).await;
             sapling_value_selected = s_notes.iter().fold(Amount::zero(), |prev, sn| {
                 (prev + Amount::from_u64(sn.note.value).unwrap()).unwrap()
             });
+        }
 
-            // If we've selected enough, just return
-            let selected_value = (sapling_value_selected + transparent_value_selected).unwrap();
-            if selected_value > target_amount {
-                return (vec![], s_notes, utxos, selected_value);
+        // If we still don't have enough, then select across the other pool
+        let mut shielded_value_selected = (orchard_value_selected + sapling_value_selected).unwrap();
+        if shielded_value_selected < target_amount {
+            let remaining_amount = (target_amount - shielded_value_selected).unwrap();
+            if prefer_orchard {
+                // Select sapling notes
+                s_notes = self.select_sapling_notes(remaining_amount, from_addresses).await;
+                sapling_value_selected = s_notes.iter().fold(Amount::zero(), |prev, sn| {
+                    (prev + Amount::from_u64(sn.note.value).unwrap()).unwrap()
+                });
+            } else {
+                // Select orchard notes
+                o_notes = self.select_orchard_notes(remaining_amount, from_addresses).await;
+                orchard_value_selected = o_notes.iter().fold(Amount::zero(), |prev, on| {
+                    (prev + Amount::from_u64(on.note.value().inner()).unwrap()).unwrap()
+                });
             }
+            shielded_value_selected = (orchard_value_selected + sapling_value_selected).unwrap();
         }
 
-        // If we still don't have enough, then select across the other pool
-        remaining_amount =
-            target_amount - (transparent_value_selected + orchard_value_selected + sapling_value_selected).unwrap();
-        if prefer_orchard {
-            // Select sapling notes
-            s_notes = self.select_sapling_notes(remaining_amount.unwrap()).await;
-            sapling_value_selected = s_notes.iter().fold(Amount::zero(), |prev, sn| {
-                (prev + Amount::from_u64(sn.note.value).unwrap()).unwrap()
-            });
+        // Finally, use the strategy to pick only the UTXOs needed to cover the shortfall
+        let utxos = if shielded_value_selected < target_amount {
+            let remaining_amount = (target_amount - shielded_value_selected).unwrap();
+            Self::select_utxos(candidate_utxos, remaining_amount, utxo_strategy)
         } else {
-            // Select orchard notes
-            o_notes = self.select_orchard_notes(remaining_amount.unwrap()).await;
-            orchard_value_selected = o_notes.iter().fold(Amount::zero(), |prev, on| {
-                (prev + Amount::from_u64(on.note.value().inner()).unwrap()).unwrap()
-            });
-        }
+            vec![]
+        };
+        let transparent_value_selected = sum_utxos(&utxos);
 
         // Return whatever we have selected, even if it is not enough, so the caller can display a proper error
-        let total_value_selected =
-            (orchard_value_selected + sapling_value_selected + transparent_value_selected).unwrap();
+        let total_value_selected = (shielded_value_selected + transparent_value_selected).unwrap();
         return (o_notes, s_notes, utxos, total_value_selected);
     }
 
+    // Pick every note and UTXO that can be spent right now (and was received at `from_addresses`, if it is set).
+    // Notes that don't have enough confirmations yet and frozen inputs are left out.
+    async fn select_all_spendable_inputs(
+        &self,
+        from_addresses: &Option<HashSet<String>>,
+    ) -> Result<(Vec<SpendableSaplingNote>, Vec<Utxo>, Amount), String> {
+        let s_notes = self.spendable_sapling_notes(from_addresses).await;
+        let utxos = self.spendable_utxos(from_addresses).await;
+
+        let selected_value = s_notes
+            .iter()
+            .map(|sn| sn.note.value)
+            .chain(utxos.iter().map(|u| u.value))
+            .sum::<u64>();
+        let selected_value =
+            Amount::from_u64(selected_value).map_err(|_| "Selected inputs are too large".to_string())?;
+
+        Ok((s_notes, utxos, selected_value))
+    }
+
+    // The most a send with these options could spend right now, fees included
+    pub(crate) async fn spendable_value(&self, options: &SendOptions) -> Result<u64, String> {
+        let from_addresses = Some(self.input_addresses(options).await?);
+        let (_, _, selected_value) = self.select_all_spendable_inputs(&from_addresses).await?;
+
+        Ok(u64::from(selected_value))
+    }
+
+    // Pick exactly the notes and UTXOs with the given input ids. Every id has to refer to a note or UTXO that can be
+    // spent right now (and was received at `from_addresses`, if it is set)
+    async fn select_explicit_inputs(
+        &self,
+        input_ids: &Vec<String>,
+        from_addresses: &Option<HashSet<String>>,
+    ) -> Result<(Vec<SpendableSaplingNote>, Vec<Utxo>, Amount), String> {
+        let mut input_ids = input_ids.clone();
+        input_ids.sort();
+        input_ids.dedup();
+
+        if input_ids.is_empty() {
+            return Err("Need at least one input to spend".to_string());
+        }
+
+        let mut candidate_notes = self.spendable_sapling_notes(from_addresses).await;
+        let mut candidate_utxos = self.spendable_utxos(from_addresses).await;
+
+        let mut s_notes = vec![];
+        let mut utxos = vec![];
+        for id in input_ids {
+            if let Some(pos) = candidate_notes.iter().position(|sn| sn.input_id() == id) {
+                s_notes.push(candidate_notes.swap_remove(pos));
+            } else if let Some(pos) = candidate_utxos.iter().position(|u| u.input_id() == id) {
+                utxos.push(candidate_utxos.swap_remove(pos));
+            } else if self.is_input_frozen(&id).await {
+                return Err(format!("Input {} is frozen. Unfreeze it before spending it", id));
+            } else {
+                return Err(format!(
+                    "Input {} can't be spent. It is either already spent, not confirmed yet, not in this wallet or not received at the 'from' address",
+                    id
+                ));
+            }
+        }
+
+        let selected_value = s_notes
+            .iter()
+            .map(|sn| sn.note.value)
+            .chain(utxos.iter().map(|u| u.value))
+            .sum::<u64>();
+        let selected_value =
+            Amount::from_u64(selected_value).map_err(|_| "Selected inputs are too large".to_string())?;
+
+        Ok((s_notes, utxos, selected_value))
+    }
+
     pub async fn send_to_address<F, Fut, PR: TxProver>(
         &self,
         prover: PR,
         transparent_only: bool,
+        options: SendOptions,
         tos: Vec<(&str, u64, Option<String>)>,
         broadcast_fn: F,
     ) -> Result<(String, Vec<u8>), String>
@@ -1383,20 +2580,28 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         F: Fn(Box<[u8]>) -> Fut,
         Fut: Future<Output = Result<String, String>>,
     {
+        let background = options.background;
+
         // Reset the progress to start. Any errors will get recorded here
-        self.reset_send_progress().await;
+        if !background {
+            self.reset_send_progress().await;
+        }
 
         // Call the internal function
         match self
-            .send_to_address_internal(prover, transparent_only, tos, broadcast_fn)
+            .send_to_address_internal(prover, transparent_only, options, tos, broadcast_fn)
             .await
         {
             Ok((txid, rawtx)) => {
-                self.set_send_success(txid.clone()).await;
+                if !background {
+                    self.set_send_success(txid.clone()).await;
+                }
                 Ok((txid, rawtx))
             }
             Err(e) => {
-                self.set_send_error(format!("{}", e)).await;
+                if !background {
+                    self.set_send_error(format!("{}", e)).await;
+                }
                 Err(e)
             }
         }
@@ -1406,6 +2611,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         &self,
         prover: PR,
         transparent_only: bool,
+        options: SendOptions,
         tos: Vec<(&str, u64, Option<String>)>,
         broadcast_fn: F,
     ) -> Result<(String, Vec<u8>), String>
@@ -1413,15 +2619,32 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         F: Fn(Box<[u8]>) -> Fut,
         Fut: Future<Output = Result<String, String>>,
     {
+        if self.keys.read().await.is_watch_only() {
+            return Err(
+                "This is a watch-only wallet, it can't spend. Create an unsigned transaction instead".to_string(),
+            );
+        }
         if !self.keys.read().await.unlocked {
             return Err("Cannot spend while wallet is locked".to_string());
         }
+        {
+            // Paying one of our own HD t-addresses can add HD addresses, which needs the passphrase
+            let keys = self.keys.read().await;
+            for to in tos.iter() {
+                keys.check_hd_recipient(&to.0.to_string())?;
+            }
+        }
 
         let start_time = now();
+        let background = options.background;
         if tos.len() == 0 {
             return Err("Need at least one destination address".to_string());
         }
 
+        if options.send_max && tos.len() != 1 {
+            return Err("Sending the maximum amount needs exactly one destination address".to_string());
+        }
+
         let total_value = tos.iter().map(|to| to.1).sum::<u64>();
         println!(
             "0: Creating transaction sending {} ztoshis to {} addresses",
@@ -1434,8 +2657,9 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             // Processing recipient
         }
 
-        // Convert address (str) to RecepientAddress and value to Amount
-        let recepients = tos
+        // Convert address (str) to RecepientAddress and value to Amount. Memos are checked here, before any notes
+        // are selected, so a bad memo (or one to a t-address) fails the send right away.
+        let mut recepients = tos
             .iter()
             .map(|to| {
                 let ra = match address::RecipientAddress::decode(&self.config.get_params(), to.0) {
@@ -1449,9 +2673,20 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
 
                 let value = Amount::from_u64(to.1).unwrap();
 
-                Ok((ra, value, to.2.clone()))
+                let memo = match &to.2 {
+                    None => None,
+                    Some(m) => match memo::check_memo(&self.config.get_params(), to.0, m) {
+                        Ok(m) => Some(m),
+                        Err(e) => {
+                            error!("{}", e);
+                            return Err(e);
+                        }
+                    },
+                };
+
+                Ok((ra, value, memo))
             })
-            .collect::<Result<Vec<(address::RecipientAddress, Amount, Option<String>)>, String>>()?;
+            .collect::<Result<Vec<(address::RecipientAddress, Amount, Option<MemoBytes>)>, String>>()?;
 
         // Calculate how much we're sending to each type of address
         let (_t_out, s_out, _o_out) = recepients
@@ -1467,7 +2702,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         // Select notes to cover the target value
         println!("{}: Selecting notes", now() - start_time);
         
-        let target_amount = (Amount::from_u64(total_value).unwrap() + DEFAULT_FEE).unwrap();
+        let mut target_amount = (Amount::from_u64(total_value).unwrap() + DEFAULT_FEE).unwrap();
         info!("Target amount: {} zatoshis (including fee)", u64::from(target_amount));
         // Target amount calculated
         let target_height = match self.get_target_height().await {
@@ -1489,9 +2724,43 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         // BitcoinZ doesn't support Orchard, so never prefer it
         let prefer_orchard = false;
 
-        let (o_notes, s_notes, utxos, selected_value) = self
-            .select_notes_and_utxos(target_amount, transparent_only, prefer_orchard)
-            .await;
+        let from_addresses = Some(self.input_addresses(&options).await?);
+        let (o_notes, s_notes, utxos, selected_value) = match &options.inputs {
+            Some(input_ids) => {
+                let (s_notes, utxos, selected_value) = self.select_explicit_inputs(input_ids, &from_addresses).await?;
+                (vec![], s_notes, utxos, selected_value)
+            }
+            None if options.send_max => {
+                let (s_notes, utxos, selected_value) = self.select_all_spendable_inputs(&from_addresses).await?;
+                (vec![], s_notes, utxos, selected_value)
+            }
+            None => {
+                self.select_notes_and_utxos(target_amount, transparent_only, prefer_orchard, &options)
+                    .await?
+            }
+        };
+
+        // When sending the maximum, the recipient gets everything that was selected minus the fee, which leaves
+        // nothing for a change output
+        if options.send_max {
+            let value = match selected_value - DEFAULT_FEE {
+                Some(v) if v.is_positive() => v,
+                _ => {
+                    let e = format!(
+                        "Not enough spendable funds to pay the fee. Have {} zats, the fee is {} zats",
+                        u64::from(selected_value),
+                        u64::from(DEFAULT_FEE)
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+            };
+
+            info!("Sending the maximum of {} zatoshis", u64::from(value));
+            recepients[0].1 = value;
+            target_amount = selected_value;
+        }
+
         if selected_value < target_amount {
             let e = format!(
                 "Insufficient verified funds. Have {} zats, need {} zats. NOTE: funds need at least {} confirmations before they can be spent.",
@@ -1501,6 +2770,13 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
             return Err(e);
         }
 
+        let privacy_policy = self.wallet_options.read().await.privacy_policy;
+        let recipient_addresses = recepients.iter().map(|(ra, _, _)| ra.clone()).collect::<Vec<_>>();
+        if let Err(e) = privacy_policy.check(transparent_only, &utxos, &recipient_addresses) {
+            error!("{}", e);
+            return Err(e);
+        }
+
         // Create the transaction
         println!(
             "{}: Adding {} o_notes {} s_notes and {} utxos",
@@ -1567,24 +2843,14 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         let s_ovk = self.keys.read().await.zkeys[0].extfvk.fvk.ovk;
         // BitcoinZ doesn't use Orchard OVK
 
+        // The shielded addresses the Tx pays to. A new address of ours among them is only kept once the Tx is built
+        let mut paid_zaddrs = vec![];
+
         let mut total_z_recepients = 0u32;
         let mut total_o_recepients = 0u32;
         for (to, value, memo) in recepients {
-            // Compute memo if it exists
-            let encoded_memo = match memo {
-                None => MemoBytes::empty(),
-                Some(s) => {
-                    // If the string starts with an "0x", and contains only hex chars ([a-f0-9]+) then
-                    // interpret it as a hex
-                    match utils::interpret_memo_string(s) {
-                        Ok(m) => m,
-                        Err(e) => {
-                            error!("{}", e);
-                            return Err(e);
-                        }
-                    }
-                }
-            };
+            // The memo was already checked above
+            let encoded_memo = memo.unwrap_or_else(MemoBytes::empty);
 
             println!("{}: Adding output", now() - start_time);
 
@@ -1594,6 +2860,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
                     if let Some(sapling_addr) = to.sapling() {
                         total_z_recepients += 1;
                         change -= u64::from(value);
+                        paid_zaddrs.push(sapling_addr.clone());
                         builder.add_sapling_output(Some(s_ovk), sapling_addr.clone(), value, encoded_memo)
                     } else if let Some(t_addr) = to.transparent() {
                         change -= u64::from(value);
@@ -1605,6 +2872,7 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
                 address::RecipientAddress::Shielded(to) => {
                     total_z_recepients += 1;
                     change -= u64::from(value);
+                    paid_zaddrs.push(to.clone());
                     builder.add_sapling_output(Some(s_ovk), to.clone(), value, encoded_memo)
                 }
                 address::RecipientAddress::Transparent(to) => {
@@ -1622,216 +2890,165 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         // BitcoinZ doesn't support Orchard, so always send change to Sapling
         change -= u64::from(DEFAULT_FEE);
         if change > 0 {
-            // Always send to sapling address for BitcoinZ
-            builder.send_change_to(
-                self.keys.read().await.zkeys[0].extfvk.fvk.ovk,
-                self.keys.read().await.zkeys[0].zaddress.clone(),
-            );
+            // The change policy decides which of our sapling addresses gets the change. The input address is
+            // the one the biggest spent note was received at.
+            let input_address = s_notes
+                .iter()
+                .max_by_key(|sn| sn.note.value)
+                .and_then(|sn| {
+                    ExtendedFullViewingKey::from(&sn.extsk)
+                        .fvk
+                        .vk
+                        .to_payment_address(sn.diversifier)
+                })
+                .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), &pa));
+            let account = self.send_account(&options).await?;
+            let change_to = self.get_change_address(input_address, true, account).await?;
+
+            let keys = self.keys.read().await;
+            let change_ovk = keys
+                .zkeys
+                .iter()
+                .find(|zk| zk.owns_address(&change_to))
+                .unwrap_or(&keys.zkeys[0])
+                .extfvk
+                .fvk
+                .ovk;
+            paid_zaddrs.push(change_to.clone());
+            builder.send_change_to(change_ovk, change_to);
         }
 
-        // Set up a channel to recieve updates on the progress of building the transaction.
-        let progress = self.send_progress.clone();
-
-        // Use a separate thread to handle sending from std::mpsc to tokio::sync::mpsc
-        let (tx2, mut rx2) = tokio::sync::mpsc::unbounded_channel();
-        std::thread::spawn(move || {
-            println!("PROGRESS DEBUG: Starting progress notification receiver thread");
+        // Pass the updates on the progress of building the transaction to the send progress. The builder reports
+        // every spend and output it has proven, and is done proving once it has reported all of them. It blocks while
+        // it builds and reports on a std channel, so a thread forwards the updates to a task that applies them.
+        let total = s_notes.len() as u32 + total_z_recepients + total_o_recepients;
+        let (updates_tx, mut updates_rx) = tokio::sync::mpsc::unbounded_channel();
+        let forward_thread = std::thread::spawn(move || {
             while let Ok(r) = progress_notifier_rx.recv() {
-                println!("PROGRESS DEBUG: Received progress notification: {}", r.cur());
-                tx2.send(r.cur()).unwrap();
-            }
-            println!("PROGRESS DEBUG: Progress notification receiver thread ended");
-        });
-
-        // Create a separate task for fallback progress updates
-        let progress_fallback = self.send_progress.clone();
-        let total_steps = s_notes.len() as u32 + total_z_recepients + total_o_recepients;
-        
-        // Use a cancellation token to stop the fallback when transaction completes
-        let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
-        
-        let fallback_handle = tokio::spawn(async move {
-            println!("PROGRESS DEBUG: Starting fallback progress task for {} steps", total_steps);
-            if total_steps > 0 {
-                // Wait a moment to see if real progress notifications come through
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                
-                // Start sending fallback progress updates
-                for i in 1..=total_steps {
-                    // Check if we should cancel
-                    if cancel_rx.try_recv().is_ok() {
-                        println!("PROGRESS DEBUG: Fallback progress task cancelled");
-                        return;
-                    }
-                    
-                    {
-                        let current_progress = progress_fallback.read().await.progress;
-                        if current_progress < i {
-                            println!("PROGRESS DEBUG: Fallback updating progress to {}/{}", i, total_steps);
-                            progress_fallback.write().await.progress = i;
-                            
-                            // Emit progress update to stream via C bridge
-                            unsafe {
-                                emit_progress_update(i, total_steps);
-                            }
-                            println!("PROGRESS DEBUG: Emitted stream update via C bridge: {}/{}", i, total_steps);
-                        }
-                    }
-                    
-                    // Slower updates for more steps, faster for fewer steps
-                    let delay_ms = if total_steps > 50 { 100 } else if total_steps > 10 { 200 } else { 500 };
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                if updates_tx.send(r).is_err() {
+                    break;
                 }
             }
-            println!("PROGRESS DEBUG: Fallback progress task completed");
         });
+        let progress = self.send_progress.clone();
+        let progress_tx = self.send_progress_tx.clone();
+        let progress_task = tokio::spawn(async move {
+            while let Some(r) = updates_rx.recv().await {
+                if background {
+                    continue;
+                }
 
-        let progress_handle = tokio::spawn(async move {
-            println!("PROGRESS DEBUG: Starting progress handler task");
-            let mut last_progress = 0u32;
-            let mut timeout_count = 0;
-            
-            loop {
-                // Wait for progress with timeout
-                let result = tokio::time::timeout(
-                    tokio::time::Duration::from_millis(200), 
-                    rx2.recv()
-                ).await;
-                
-                match result {
-                    Ok(Some(r)) => {
-                        println!("PROGRESS DEBUG: Processing progress update: {} (was: {})", r, last_progress);
-                        progress.write().await.progress = r;
-                        last_progress = r;
-                        timeout_count = 0; // Reset timeout counter
-                    }
-                    Ok(None) => {
-                        println!("PROGRESS DEBUG: Progress channel closed");
-                        break;
-                    }
-                    Err(_) => {
-                        // Timeout - this is expected if no real progress notifications
-                        timeout_count += 1;
-                        if timeout_count > 20 { // After 4 seconds of no updates, give up
-                            println!("PROGRESS DEBUG: Progress handler timed out, relying on fallback");
-                            break;
-                        }
-                    }
+                let mut p = progress.write().await;
+                p.total = r.end().unwrap_or(p.total);
+                p.progress = r.cur().min(p.total);
+                if p.progress == p.total {
+                    p.phase = SendPhase::Signing;
                 }
+
+                let _ = progress_tx.send(p.clone());
             }
-            
-            println!("PROGRESS DEBUG: Progress handler task ending");
-            progress.write().await.is_send_in_progress = false;
         });
 
-        {
-            // TODO(orchard): Orchard building progress
-            let mut p = self.send_progress.write().await;
-            p.is_send_in_progress = true;
-            p.progress = 0;
-            p.total = s_notes.len() as u32 + total_z_recepients + total_o_recepients;
-            println!("PROGRESS DEBUG: Set up progress tracking - total: {}, sapling notes: {}, z recipients: {}, o recipients: {}", 
-                p.total, s_notes.len(), total_z_recepients, total_o_recepients);
+        // TODO(orchard): Orchard building progress
+        if !background {
+            self.update_send_progress(|p| {
+                p.phase = if total > 0 {
+                    SendPhase::Proving
+                } else {
+                    SendPhase::Signing
+                };
+                p.progress = 0;
+                p.total = total;
+            })
+            .await;
         }
 
         println!("{}: Building transaction", now() - start_time);
-        info!("Building transaction with {} sapling notes, {} orchard notes, {} transparent inputs", s_notes.len(), o_notes.len(), utxos.len());
-        
-        // Building transaction with detailed progress logging
-        println!("PROGRESS DEBUG: Starting transaction build with {} total progress steps", s_notes.len() as u32 + total_z_recepients + total_o_recepients);
-        let (tx, _) = match builder.build(&prover) {
-            Ok(res) => {
-                println!("PROGRESS DEBUG: Transaction build successful, cancelling fallback progress");
-                // Cancel the fallback progress task since build completed
-                let _ = cancel_tx.send(());
-                res
-            },
+        info!(
+            "Building transaction with {} sapling notes, {} orchard notes, {} transparent inputs",
+            s_notes.len(),
+            o_notes.len(),
+            utxos.len()
+        );
+
+        let result = builder.build(&prover);
+
+        // The builder is gone, so the progress is up to date once the last update has been applied
+        let _ = forward_thread.join();
+        let _ = progress_task.await;
+
+        let (tx, _) = match result {
+            Ok(res) => res,
             Err(e) => {
                 let e = format!("Error creating transaction: {:?}", e);
                 error!("{}", e);
-                // Transaction build failed, cancel fallback and mark as not in progress
-                let _ = cancel_tx.send(());
-                self.send_progress.write().await.is_send_in_progress = false;
                 return Err(e);
             }
         };
-
-        // Set progress to complete immediately after successful build
-        {
-            let mut p = self.send_progress.write().await;
-            p.progress = p.total;  // Set to 100%
-            println!("PROGRESS DEBUG: Set progress to complete: {}/{}", p.progress, p.total);
-        }
-
-        // Wait for all the progress to be updated
-        println!("PROGRESS DEBUG: Waiting for progress handlers to complete");
-        
-        // Wait for both the real progress handler and fallback to complete
-        let (_, _) = tokio::join!(progress_handle, fallback_handle);
-        
-        println!("PROGRESS DEBUG: All progress handlers completed");
+        self.keys.write().await.keep_diversified_addresses(&paid_zaddrs);
 
         println!("{}: Transaction created", now() - start_time);
-        println!("Transaction ID: {}", tx.txid());
         info!("Transaction created with txid: {}", tx.txid());
 
-        {
-            self.send_progress.write().await.is_send_in_progress = false;
-        }
-
         // Create the TX bytes
         let mut raw_tx = vec![];
         tx.write(&mut raw_tx).unwrap();
         info!("Transaction size: {} bytes", raw_tx.len());
 
         info!("Broadcasting transaction to network...");
+        if !background {
+            self.set_send_phase(SendPhase::Broadcasting).await;
+        }
         let txid = broadcast_fn(raw_tx.clone().into_boxed_slice()).await?;
         info!("Transaction broadcast successful, txid: {}", txid);
 
-        // Mark notes as spent.
+        self.record_sent_tx(tx, &raw_tx, target_height).await;
+
+        Ok((txid, raw_tx))
+    }
+
+    // Mark the notes and utxos spent by a Tx we just broadcast as unconfirmed spent, and add the Tx to the wallet as
+    // an unconfirmed Tx. The raw tx is kept around, so it can be rebroadcast until it is mined.
+    async fn record_sent_tx(&self, tx: Transaction, raw_tx: &Vec<u8>, target_height: BlockHeight) {
+        let sent_txid = tx.txid();
+        let expiry_height = u32::from(tx.expiry_height());
+        let unconfirmed_spent = Some((sent_txid, u32::from(target_height)));
+
         {
-            // Mark sapling and orchard notes as unconfirmed spent
             let mut txs = self.txns.write().await;
-            for selected in o_notes {
-                let mut spent_note = txs
-                    .current
-                    .get_mut(&selected.txid)
-                    .unwrap()
-                    .o_notes
-                    .iter_mut()
-                    .find(|nd| {
-                        nd.note.nullifier(&nd.fvk)
-                            == selected
-                                .note
-                                .nullifier(&orchard::keys::FullViewingKey::from(&selected.sk))
-                    })
-                    .unwrap();
-                spent_note.unconfirmed_spent = Some((tx.txid(), u32::from(target_height)));
+
+            if let Some(o_bundle) = tx.orchard_bundle() {
+                for action in o_bundle.actions().iter() {
+                    txs.current
+                        .values_mut()
+                        .flat_map(|wtx| wtx.o_notes.iter_mut())
+                        .filter(|nd| nd.note.nullifier(&nd.fvk) == *action.nullifier())
+                        .for_each(|nd| nd.unconfirmed_spent = unconfirmed_spent);
+                }
             }
 
-            for selected in s_notes {
-                let mut spent_note = txs
-                    .current
-                    .get_mut(&selected.txid)
-                    .unwrap()
-                    .s_notes
-                    .iter_mut()
-                    .find(|nd| nd.nullifier == selected.nullifier)
-                    .unwrap();
-                spent_note.unconfirmed_spent = Some((tx.txid(), u32::from(target_height)));
+            if let Some(s_bundle) = tx.sapling_bundle() {
+                for spend in s_bundle.shielded_spends.iter() {
+                    txs.current
+                        .values_mut()
+                        .flat_map(|wtx| wtx.s_notes.iter_mut())
+                        .filter(|nd| nd.nullifier == spend.nullifier)
+                        .for_each(|nd| nd.unconfirmed_spent = unconfirmed_spent);
+                }
             }
 
-            // Mark this utxo as unconfirmed spent
-            for utxo in utxos {
-                let mut spent_utxo = txs
-                    .current
-                    .get_mut(&utxo.txid)
-                    .unwrap()
-                    .utxos
-                    .iter_mut()
-                    .find(|u| utxo.txid == u.txid && utxo.output_index == u.output_index)
-                    .unwrap();
-                spent_utxo.unconfirmed_spent = Some((tx.txid(), u32::from(target_height)));
+            if let Some(t_bundle) = tx.transparent_bundle() {
+                for vin in t_bundle.vin.iter() {
+                    let prev_txid = TxId::from_bytes(*vin.prevout.hash());
+                    let prev_n = vin.prevout.n() as u64;
+
+                    if let Some(wtx) = txs.current.get_mut(&prev_txid) {
+                        wtx.utxos
+                            .iter_mut()
+                            .filter(|u| u.output_index == prev_n)
+                            .for_each(|u| u.unconfirmed_spent = unconfirmed_spent);
+                    }
+                }
             }
         }
 
@@ -1839,7 +3056,8 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
         {
             let price = self.price.read().await.clone();
 
-            FetchFullTxns::<P>::scan_full_tx(
+            // The Tx has been broadcast already, and the sync reports this again once it is mined
+            if let Err(e) = FetchFullTxns::<P>::scan_full_tx(
                 self.config.clone(),
                 tx,
                 // For unconfirmed sent transactions, use height 0 to ensure they're excluded from spendable balance
@@ -1850,10 +3068,359 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
                 self.txns.clone(),
                 WalletTx::get_price(now(), &price),
             )
-            .await;
+            .await
+            {
+                warn!("{}", e);
+            }
         }
 
-        Ok((txid, raw_tx))
+        self.txns
+            .write()
+            .await
+            .set_pending_broadcast(&sent_txid, raw_tx.clone(), expiry_height);
+    }
+
+    // All the sapling notes that can be spent right now by the keys in this wallet, whether or not the wallet has the
+    // spending key for them, along with their input ids. Used by watch-only wallets to put together unsigned proposals.
+    async fn proposal_sapling_notes(
+        &self,
+        from_addresses: &Option<HashSet<String>>,
+    ) -> Vec<(String, ProposalSaplingSpend)> {
+        let anchor_offset = self.config.anchor_offset as usize;
+
+        self.txns
+            .read()
+            .await
+            .current
+            .iter()
+            .flat_map(|(txid, wtx)| wtx.s_notes.iter().map(move |nd| (*txid, nd)))
+            .filter(|(_, nd)| nd.note.value > 0 && nd.spent.is_none() && nd.unconfirmed_spent.is_none() && !nd.frozen)
+            .filter(|(_, nd)| match from_addresses {
+                Some(a) => Self::sapling_note_address(self.config.hrp_sapling_address(), nd)
+                    .map_or(false, |address| a.contains(&address)),
+                None => true,
+            })
+            .filter(|(_, nd)| nd.witnesses.len() >= anchor_offset + 1)
+            .filter_map(|(txid, nd)| {
+                nd.witnesses.get(nd.witnesses.len() - anchor_offset - 1).map(|w| {
+                    let spend = ProposalSaplingSpend {
+                        txid,
+                        extfvk: nd.extfvk.clone(),
+                        diversifier: nd.diversifier,
+                        note: nd.note.clone(),
+                        witness: w.clone(),
+                    };
+                    (nd.input_id(), spend)
+                })
+            })
+            .collect()
+    }
+
+    // Put together a Tx without proving or signing it, so it can be created by a wallet that only has viewing keys
+    // and t-addresses. Inputs are picked the same way as `send_to_address()` does.
+    pub async fn create_unsigned_proposal(
+        &self,
+        tos: Vec<(&str, u64, Option<String>)>,
+        options: &SendOptions,
+    ) -> Result<UnsignedProposal, String> {
+        if tos.len() == 0 {
+            return Err("Need at least one destination address".to_string());
+        }
+
+        if options.send_max && tos.len() != 1 {
+            return Err("Sending the maximum amount needs exactly one destination address".to_string());
+        }
+
+        for (address, _, memo) in tos.iter() {
+            if address::RecipientAddress::decode(&self.config.get_params(), address).is_none() {
+                return Err(format!("Invalid recipient address: '{}'", address));
+            }
+
+            if let Some(m) = memo {
+                memo::check_memo(&self.config.get_params(), address, m)?;
+            }
+        }
+
+        let target_height = match self.get_target_height().await {
+            Some(h) => h,
+            None => return Err("No blocks in wallet to target, please sync first".to_string()),
+        };
+
+        let fee = u64::from(DEFAULT_FEE);
+        let target_value = tos.iter().map(|to| to.1).sum::<u64>() + fee;
+
+        let from_addresses = Some(self.input_addresses(&options).await?);
+        let mut candidate_notes = self.proposal_sapling_notes(&from_addresses).await;
+        let mut candidate_utxos = self.spendable_utxos(&from_addresses).await;
+
+        let (sapling_spends, utxos) = if let Some(input_ids) = &options.inputs {
+            let mut input_ids = input_ids.clone();
+            input_ids.sort();
+            input_ids.dedup();
+
+            if input_ids.is_empty() {
+                return Err("Need at least one input to spend".to_string());
+            }
+
+            let mut sapling_spends = vec![];
+            let mut utxos = vec![];
+            for id in input_ids {
+                if let Some(pos) = candidate_notes.iter().position(|(note_id, _)| *note_id == id) {
+                    sapling_spends.push(candidate_notes.swap_remove(pos).1);
+                } else if let Some(pos) = candidate_utxos.iter().position(|u| u.input_id() == id) {
+                    utxos.push(candidate_utxos.swap_remove(pos));
+                } else {
+                    return Err(format!(
+                        "Input {} can't be spent. It is either already spent, frozen, not confirmed yet, not in this wallet or not received at the 'from' address",
+                        id
+                    ));
+                }
+            }
+
+            (sapling_spends, utxos)
+        } else if options.send_max {
+            (
+                candidate_notes.into_iter().map(|(_, spend)| spend).collect(),
+                candidate_utxos,
+            )
+        } else {
+            // Shielded notes first, biggest first, and only then the UTXOs needed to cover whatever is left
+            candidate_notes.sort_by(|a, b| b.1.note.value.cmp(&a.1.note.value));
+
+            let mut selected_value = 0u64;
+            let sapling_spends = candidate_notes
+                .into_iter()
+                .map(|(_, spend)| spend)
+                .take_while(|spend| {
+                    if selected_value >= target_value {
+                        false
+                    } else {
+                        selected_value += spend.note.value;
+                        true
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let utxos = if selected_value < target_value {
+                let remaining =
+                    Amount::from_u64(target_value - selected_value).map_err(|_| "Amount is too large".to_string())?;
+                Self::select_utxos(candidate_utxos, remaining, options.utxo_strategy)
+            } else {
+                vec![]
+            };
+
+            (sapling_spends, utxos)
+        };
+
+        let mut proposal = UnsignedProposal {
+            target_height,
+            sapling_spends,
+            utxos,
+            outputs: tos
+                .iter()
+                .map(|(address, value, memo)| ProposalOutput {
+                    address: address.to_string(),
+                    value: *value,
+                    memo: memo.clone(),
+                })
+                .collect(),
+            change_address: String::new(),
+            fee,
+        };
+
+        let selected_value = proposal.total_input_value();
+        if options.send_max {
+            if selected_value <= fee {
+                return Err(format!(
+                    "Not enough spendable funds to pay the fee. Have {} zats, the fee is {} zats",
+                    selected_value, fee
+                ));
+            }
+            proposal.outputs[0].value = selected_value - fee;
+        } else if selected_value < target_value {
+            return Err(format!(
+                "Insufficient verified funds. Have {} zats, need {} zats. NOTE: funds need at least {} confirmations before they can be spent.",
+                selected_value, target_value, self.config.anchor_offset + 1
+            ));
+        }
+
+        let recipient_addresses = proposal
+            .outputs
+            .iter()
+            .filter_map(|o| address::RecipientAddress::decode(&self.config.get_params(), &o.address))
+            .collect::<Vec<_>>();
+        self.wallet_options
+            .read()
+            .await
+            .privacy_policy
+            .check(false, &proposal.utxos, &recipient_addresses)?;
+
+        // The change follows the change policy, like a regular send. A watch-only wallet can't create a fresh
+        // address, and wouldn't see the change if the offline wallet did, so that falls back to the input address.
+        let input_address = proposal
+            .sapling_spends
+            .iter()
+            .max_by_key(|spend| spend.note.value)
+            .and_then(|spend| spend.extfvk.fvk.vk.to_payment_address(spend.diversifier))
+            .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), &pa));
+        let account = self.send_account(&options).await?;
+        let change_to = self.get_change_address(input_address, false, account).await?;
+        proposal.change_address = encode_payment_address(self.config.hrp_sapling_address(), &change_to);
+
+        Ok(proposal)
+    }
+
+    // Prove and sign an unsigned proposal. This wallet needs to have the spending keys for all of its inputs, but it
+    // doesn't need to be synced. Returns the txid and the raw Tx, which the online wallet can then broadcast.
+    pub async fn sign_proposal<PR: TxProver>(
+        &self,
+        prover: PR,
+        proposal: &UnsignedProposal,
+    ) -> Result<(TxId, Vec<u8>), String> {
+        let keys = self.keys.read().await;
+        if keys.is_watch_only() {
+            return Err("This is a watch-only wallet, it can't sign".to_string());
+        }
+        if !keys.unlocked {
+            return Err("Cannot spend while wallet is locked".to_string());
+        }
+
+        if proposal.fee != u64::from(DEFAULT_FEE) {
+            return Err(format!(
+                "Unsupported fee of {} zats in the proposal, expected {} zats",
+                proposal.fee,
+                u64::from(DEFAULT_FEE)
+            ));
+        }
+
+        let change = match proposal.change_value() {
+            Some(c) => c,
+            None => {
+                return Err(format!(
+                    "The proposal's inputs of {} zats don't cover its outputs of {} zats and the fee",
+                    proposal.total_input_value(),
+                    proposal.total_output_value()
+                ))
+            }
+        };
+
+        let mut builder = Builder::new(
+            self.config.get_params().clone(),
+            BlockHeight::from_u32(proposal.target_height),
+        );
+
+        let address_to_sk = keys.get_taddr_to_sk_map();
+        for utxo in proposal.utxos.iter() {
+            let sk = match address_to_sk.get(&utxo.address) {
+                Some(sk) => sk,
+                None => return Err(format!("Don't have the spending key for {}", utxo.address)),
+            };
+
+            let coin = TxOut {
+                value: Amount::from_u64(utxo.value).map_err(|_| format!("Invalid UTXO value {}", utxo.value))?,
+                script_pubkey: Script { 0: utxo.script.clone() },
+            };
+
+            builder
+                .add_transparent_input(*sk, utxo.to_outpoint(), coin)
+                .map_err(|e| format!("Error adding transparent input: {:?}", e))?;
+        }
+
+        for spend in proposal.sapling_spends.iter() {
+            let extsk = match keys.get_extsk_for_extfvk(&spend.extfvk) {
+                Some(extsk) => extsk,
+                None => {
+                    return Err(format!(
+                        "Don't have the spending key for a note received in {}",
+                        spend.txid
+                    ))
+                }
+            };
+
+            let path = match spend.witness.path() {
+                Some(p) => p,
+                None => return Err(format!("Invalid witness for a note received in {}", spend.txid)),
+            };
+
+            builder
+                .add_sapling_spend(extsk, spend.diversifier, spend.note.clone(), path)
+                .map_err(|e| format!("Error adding sapling note: {:?}", e))?;
+        }
+
+        // The change has to come back to this wallet. The proposal was put together somewhere else, so a change
+        // address that isn't ours would send the change to whoever changed the proposal.
+        let change_to = match decode_payment_address(self.config.hrp_sapling_address(), &proposal.change_address) {
+            Ok(Some(pa)) => pa,
+            _ => return Err(format!("Invalid change address {}", proposal.change_address)),
+        };
+        let ovk = match keys.zkeys.iter().find(|zk| zk.owns_address(&change_to)) {
+            Some(zk) => zk.extfvk.fvk.ovk,
+            None => {
+                return Err(format!(
+                    "The proposal's change address {} isn't in this wallet",
+                    proposal.change_address
+                ))
+            }
+        };
+
+        for output in proposal.outputs.iter() {
+            let value = Amount::from_u64(output.value).map_err(|_| format!("Invalid amount {}", output.value))?;
+            let memo = match &output.memo {
+                None => MemoBytes::empty(),
+                Some(m) => memo::check_memo(&self.config.get_params(), &output.address, m)?,
+            };
+
+            match address::RecipientAddress::decode(&self.config.get_params(), &output.address) {
+                Some(address::RecipientAddress::Shielded(to)) => builder.add_sapling_output(Some(ovk), to, value, memo),
+                Some(address::RecipientAddress::Transparent(to)) => builder.add_transparent_output(&to, value),
+                Some(address::RecipientAddress::Unified(ua)) => match (ua.sapling(), ua.transparent()) {
+                    (Some(to), _) => builder.add_sapling_output(Some(ovk), to.clone(), value, memo),
+                    (None, Some(to)) => builder.add_transparent_output(to, value),
+                    _ => return Err("Unified address has no supported receivers for BitcoinZ".to_string()),
+                },
+                None => return Err(format!("Invalid recipient address: '{}'", output.address)),
+            }
+            .map_err(|e| format!("Error adding output: {:?}", e))?;
+        }
+
+        if change > 0 {
+            builder.send_change_to(ovk, change_to);
+        }
+
+        let (tx, _) = builder
+            .build(&prover)
+            .map_err(|e| format!("Error creating transaction: {:?}", e))?;
+
+        let mut raw_tx = vec![];
+        tx.write(&mut raw_tx).map_err(|e| e.to_string())?;
+
+        Ok((tx.txid(), raw_tx))
+    }
+
+    // Broadcast a Tx that was signed somewhere else, like an unsigned proposal that was signed by an offline wallet,
+    // and add it to this wallet as an unconfirmed send
+    pub async fn broadcast_signed_tx<F, Fut>(&self, raw_tx: Vec<u8>, broadcast_fn: F) -> Result<String, String>
+    where
+        F: Fn(Box<[u8]>) -> Fut,
+        Fut: Future<Output = Result<String, String>>,
+    {
+        let target_height = match self.get_target_height().await {
+            Some(h) => BlockHeight::from_u32(h),
+            None => return Err("No blocks in wallet to target, please sync first".to_string()),
+        };
+
+        let tx = Transaction::read(
+            &raw_tx[..],
+            BranchId::for_height(&self.config.get_params(), target_height),
+        )
+        .map_err(|e| format!("Couldn't read the transaction: {}", e))?;
+
+        let txid = broadcast_fn(raw_tx.clone().into_boxed_slice()).await?;
+        info!("Broadcast signed transaction, txid: {}", txid);
+
+        self.record_sent_tx(tx, &raw_tx, target_height).await;
+
+        Ok(txid)
     }
 
     pub async fn encrypt(&self, passwd: String) -> io::Result<()> {
@@ -1875,13 +3442,17 @@ impl<P: consensus::Parameters + Send + Sync + 'static> LightWallet<P> {
 
 #[cfg(test)]
 mod test {
-    use zcash_primitives::transaction::components::Amount;
+    use zcash_primitives::transaction::{components::Amount, TxId};
 
+    use zcash_client_backend::address::RecipientAddress;
+
+    use super::{data::Utxo, LightWallet, PrivacyPolicy, SendOptions, UtxoSelectionStrategy};
     use crate::{
         blaze::test_utils::{incw_to_string, FakeCompactBlockList, FakeTransaction},
         lightclient::{
             lightclient_config::UnitTestNetwork,
             test_server::{create_test_server, mine_pending_blocks, mine_random_blocks},
+            tests::{EXT_TADDR, EXT_ZADDR},
             LightClient,
         },
     };
@@ -1911,7 +3482,11 @@ mod test {
         let amt = Amount::from_u64(10_000).unwrap();
         // Reset the anchor offsets
         lc.wallet.config.anchor_offset = 0;
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected >= amt);
         assert_eq!(notes.len(), 1);
         assert_eq!(notes[0].note.value, value);
@@ -1928,14 +3503,22 @@ mod test {
 
         // With min anchor_offset at 1, we can't select any notes
         lc.wallet.config.anchor_offset = 1;
-        let (_, notes, utxos, _selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, _selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert_eq!(notes.len(), 0);
         assert_eq!(utxos.len(), 0);
 
         // Mine 1 block, then it should be selectable
         mine_random_blocks(&mut fcbl, &data, &lc, 1).await;
 
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected >= amt);
         assert_eq!(notes.len(), 1);
         assert_eq!(notes[0].note.value, value);
@@ -1953,7 +3536,11 @@ mod test {
         // Mine 15 blocks, then selecting the note should result in witness only 10 blocks deep
         mine_random_blocks(&mut fcbl, &data, &lc, 15).await;
         lc.wallet.config.anchor_offset = 9;
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, true).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, true, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected >= amt);
         assert_eq!(notes.len(), 1);
         assert_eq!(notes[0].note.value, value);
@@ -1970,7 +3557,11 @@ mod test {
 
         // Trying to select a large amount will fail
         let amt = Amount::from_u64(1_000_000).unwrap();
-        let (_, _, _, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, _, _, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected < amt);
 
         // 4. Get an incoming tx to a t address
@@ -1986,14 +3577,22 @@ mod test {
 
         // Trying to select a large amount will now succeed
         let amt = Amount::from_u64(value + tvalue - 10_000).unwrap();
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, true).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, true, &SendOptions::default())
+            .await
+            .unwrap();
         assert_eq!(selected, Amount::from_u64(value + tvalue).unwrap());
         assert_eq!(notes.len(), 1);
         assert_eq!(utxos.len(), 1);
 
         // If we set transparent-only = true, only the utxo should be selected
         let amt = Amount::from_u64(tvalue - 10_000).unwrap();
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, true, true).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, true, true, &SendOptions::default())
+            .await
+            .unwrap();
         assert_eq!(selected, Amount::from_u64(tvalue).unwrap());
         assert_eq!(notes.len(), 0);
         assert_eq!(utxos.len(), 1);
@@ -2001,7 +3600,11 @@ mod test {
         // Set min confs to 5, so the sapling note will not be selected
         lc.wallet.config.anchor_offset = 4;
         let amt = Amount::from_u64(tvalue - 10_000).unwrap();
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, true).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, true, &SendOptions::default())
+            .await
+            .unwrap();
         assert_eq!(selected, Amount::from_u64(tvalue).unwrap());
         assert_eq!(notes.len(), 0);
         assert_eq!(utxos.len(), 1);
@@ -2036,7 +3639,11 @@ mod test {
         let amt = Amount::from_u64(10_000).unwrap();
         // Reset the anchor offsets
         lc.wallet.config.anchor_offset = 0;
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected >= amt);
         assert_eq!(notes.len(), 1);
         assert_eq!(notes[0].note.value, value1);
@@ -2060,7 +3667,11 @@ mod test {
         mine_pending_blocks(&mut fcbl, &data, &lc).await;
 
         let amt = Amount::from_u64(10_000).unwrap();
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected >= amt);
         assert_eq!(notes.len(), 1);
         assert_eq!(notes[0].note.value, value2);
@@ -2068,13 +3679,138 @@ mod test {
 
         // Selecting a bigger amount should select both notes
         let amt = Amount::from_u64(value1 + value2).unwrap();
-        let (_, notes, utxos, selected) = lc.wallet.select_notes_and_utxos(amt, false, false).await;
+        let (_, notes, utxos, selected) = lc
+            .wallet
+            .select_notes_and_utxos(amt, false, false, &SendOptions::default())
+            .await
+            .unwrap();
         assert!(selected == amt);
         assert_eq!(notes.len(), 2);
         assert_eq!(utxos.len(), 0);
 
+        // Selecting from an account that doesn't exist is an error, not an empty selection
+        let options = SendOptions { account: Some(7), ..Default::default() };
+        assert!(lc.wallet.select_notes_and_utxos(amt, false, false, &options).await.is_err());
+
         // Shutdown everything cleanly
         stop_tx.send(true).unwrap();
         h1.await.unwrap();
     }
+
+    fn test_utxo(address: &str, txid_byte: u8, value: u64, height: i32) -> Utxo {
+        Utxo {
+            address: address.to_string(),
+            txid: TxId::from_bytes([txid_byte; 32]),
+            output_index: 0,
+            script: vec![],
+            value,
+            height,
+            spent_at_height: None,
+            spent: None,
+            unconfirmed_spent: None,
+            frozen: false,
+        }
+    }
+
+    #[test]
+    fn utxo_selection_strategies() {
+        let select = LightWallet::<UnitTestNetwork>::select_utxos;
+        let candidates = vec![
+            test_utxo("t1a", 1, 30_000, 10),
+            test_utxo("t1a", 2, 50_000, 20),
+            test_utxo("t1b", 3, 70_000, 5),
+            test_utxo("t1b", 4, 10_000, 30),
+        ];
+        let txids = |utxos: Vec<Utxo>| utxos.iter().map(|u| u.txid.as_ref()[0]).collect::<Vec<_>>();
+
+        // Nothing is needed if there is no shortfall
+        assert!(select(candidates.clone(), Amount::zero(), UtxoSelectionStrategy::Minimal).is_empty());
+
+        // All selects everything, regardless of the amount
+        let amt = Amount::from_u64(1_000).unwrap();
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::All)),
+            vec![1, 2, 3, 4]
+        );
+
+        // Minimal picks the largest UTXOs first
+        let amt = Amount::from_u64(100_000).unwrap();
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::Minimal)),
+            vec![3, 2]
+        );
+
+        // Oldest first goes by height
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::OldestFirst)),
+            vec![3, 1]
+        );
+
+        // Single address can't cover 100_000 from either address
+        assert!(select(candidates.clone(), amt, UtxoSelectionStrategy::SingleAddress).is_empty());
+
+        // ...but can cover 75_000 from both, and picks the one needing fewer UTXOs
+        let amt = Amount::from_u64(75_000).unwrap();
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::SingleAddress)),
+            vec![2, 1]
+        );
+        let amt = Amount::from_u64(60_000).unwrap();
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::SingleAddress)),
+            vec![3]
+        );
+
+        // Avoid mixing uses a single address when it can, and mixes addresses only when it has to
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::AvoidMixing)),
+            vec![3]
+        );
+        let amt = Amount::from_u64(150_000).unwrap();
+        assert_eq!(
+            txids(select(candidates.clone(), amt, UtxoSelectionStrategy::AvoidMixing)),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn privacy_policy_checks() {
+        let decode = |a: &str| RecipientAddress::decode(&UnitTestNetwork, a).unwrap();
+        let zaddr = decode(EXT_ZADDR);
+        let taddr = decode(EXT_TADDR);
+        let one_taddr = vec![test_utxo("t1a", 1, 30_000, 10), test_utxo("t1a", 2, 50_000, 20)];
+        let two_taddrs = vec![test_utxo("t1a", 1, 30_000, 10), test_utxo("t1b", 3, 70_000, 5)];
+
+        // Shielded to shielded is always fine
+        assert!(PrivacyPolicy::FullPrivacy.check(false, &[], &[zaddr.clone()]).is_ok());
+
+        // Spending UTXOs reveals their amounts
+        assert!(PrivacyPolicy::FullPrivacy
+            .check(false, &one_taddr, &[zaddr.clone()])
+            .is_err());
+        assert!(PrivacyPolicy::AllowRevealedAmounts
+            .check(false, &one_taddr, &[zaddr.clone()])
+            .is_ok());
+
+        // Paying a t-address reveals the recipient
+        let e = PrivacyPolicy::AllowRevealedAmounts
+            .check(false, &[], &[zaddr.clone(), taddr.clone()])
+            .unwrap_err();
+        assert!(e.contains("'allow_revealed_recipients'"));
+        assert!(PrivacyPolicy::AllowRevealedRecipients
+            .check(false, &one_taddr, &[taddr.clone()])
+            .is_ok());
+
+        // Spending UTXOs of several t-addresses links them, even when shielding
+        let e = PrivacyPolicy::AllowRevealedRecipients
+            .check(true, &two_taddrs, &[zaddr.clone()])
+            .unwrap_err();
+        assert!(e.contains("'allow_linking_transparent'"));
+        assert!(PrivacyPolicy::AllowLinkingTransparent
+            .check(false, &two_taddrs, &[taddr.clone()])
+            .is_ok());
+
+        // Shielding the UTXOs of a single t-address doesn't reveal anything new
+        assert!(PrivacyPolicy::FullPrivacy.check(true, &one_taddr, &[zaddr]).is_ok());
+    }
 }
@@ -0,0 +1,304 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use json::{object, JsonValue};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+use zcash_encoding::Vector;
+
+use super::utils::{read_string, write_string};
+
+// The most recipients paid by a single batch Tx. Every shielded output needs its own proof and adds to the size of
+// the Tx, so larger batches are split into several Txs.
+pub const MAX_BATCH_OUTPUTS: usize = 50;
+
+// How many batch files the wallet remembers the paid rows of. The oldest is forgotten first.
+pub const MAX_BATCH_RECORDS: usize = 50;
+
+// One payment in a batch file. `row` is the line number in a CSV file, or the position in a JSON array, so
+// problems can be found in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRow {
+    pub row: usize,
+    pub address: String,
+    pub value: u64,
+    pub memo: Option<String>,
+
+    // Set once the row has been sent, or if it couldn't be
+    pub txid: Option<String>,
+    pub error: Option<String>,
+
+    // The row was paid by an earlier run of the same batch file
+    pub already_sent: bool,
+}
+
+impl BatchRow {
+    fn new(row: usize, address: String, value: u64, memo: Option<String>) -> Self {
+        Self {
+            row,
+            address,
+            value,
+            memo,
+            txid: None,
+            error: None,
+            already_sent: false,
+        }
+    }
+
+    fn invalid(row: usize, address: String, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(row, address, 0, None)
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let mut j = object! {
+            "row"     => self.row,
+            "address" => self.address.clone(),
+            "amount"  => self.value,
+            "memo"    => self.memo.clone(),
+        };
+
+        match (&self.txid, &self.error) {
+            (Some(txid), _) => {
+                j["status"] = if self.already_sent { "already_sent" } else { "sent" }.into();
+                j["txid"] = txid.clone().into();
+            }
+            (None, Some(e)) => {
+                j["status"] = "error".into();
+                j["error"] = e.clone().into();
+            }
+            (None, None) => j["status"] = "not_sent".into(),
+        }
+
+        j
+    }
+}
+
+// The txids that paid the rows of recently sent batch files. A file is identified by the hash of its contents, so
+// a file that is changed is a new batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRecords {
+    // (batch id, [(row, txid)]), oldest first
+    batches: Vec<(String, Vec<(u64, String)>)>,
+}
+
+impl BatchRecords {
+    pub fn serialized_version() -> u64 {
+        1
+    }
+
+    pub fn new() -> Self {
+        Self { batches: vec![] }
+    }
+
+    pub fn batch_id(contents: &str) -> String {
+        hex::encode(Sha256::digest(contents.as_bytes()))
+    }
+
+    // The txid that paid each row of the batch that has been paid
+    pub fn sent_rows(&self, batch_id: &str) -> Vec<(usize, String)> {
+        self.batches
+            .iter()
+            .find(|(id, _)| id == batch_id)
+            .map(|(_, rows)| rows.iter().map(|(row, txid)| (*row as usize, txid.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    // Remember the rows that were just paid. The batch becomes the newest one, so it is forgotten last.
+    pub fn record(&mut self, batch_id: &str, rows: &[&BatchRow]) {
+        let mut sent = match self.batches.iter().position(|(id, _)| id == batch_id) {
+            Some(i) => self.batches.remove(i).1,
+            None => vec![],
+        };
+        for row in rows {
+            if let Some(txid) = &row.txid {
+                sent.retain(|(r, _)| *r != row.row as u64);
+                sent.push((row.row as u64, txid.clone()));
+            }
+        }
+
+        self.batches.push((batch_id.to_string(), sent));
+        if self.batches.len() > MAX_BATCH_RECORDS {
+            self.batches.drain(..self.batches.len() - MAX_BATCH_RECORDS);
+        }
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let version = reader.read_u64::<LittleEndian>()?;
+        if version > Self::serialized_version() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Can't read batch records version {}, newest supported is {}",
+                    version,
+                    Self::serialized_version()
+                ),
+            ));
+        }
+
+        let batches = Vector::read(&mut reader, |r| {
+            let id = read_string(r)?;
+            let rows = Vector::read(r, |r| {
+                let row = r.read_u64::<LittleEndian>()?;
+                let txid = read_string(r)?;
+                Ok((row, txid))
+            })?;
+            Ok((id, rows))
+        })?;
+
+        Ok(Self { batches })
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u64::<LittleEndian>(Self::serialized_version())?;
+
+        Vector::write(&mut writer, &self.batches, |w, (id, rows)| {
+            write_string(w, id)?;
+            Vector::write(w, rows, |w, (row, txid)| {
+                w.write_u64::<LittleEndian>(*row)?;
+                write_string(w, txid)
+            })
+        })
+    }
+}
+
+// Parse a batch file. It is either a JSON array of {"address", "amount", "memo"} objects, or CSV lines of
+// `address,amount[,memo]` with an optional header line. Amounts are in zats. Rows that can't be understood are
+// returned with their `error` set, so they can all be reported at once.
+pub fn parse_batch(contents: &str) -> Result<Vec<BatchRow>, String> {
+    let rows = if contents.trim_start().starts_with('[') {
+        parse_json_batch(contents)?
+    } else {
+        parse_csv_batch(contents)
+    };
+
+    if rows.is_empty() {
+        return Err("There are no payments in the batch file".to_string());
+    }
+
+    Ok(rows)
+}
+
+fn parse_json_batch(contents: &str) -> Result<Vec<BatchRow>, String> {
+    let j = json::parse(contents).map_err(|e| format!("Couldn't understand JSON: {}", e))?;
+
+    Ok(j.members()
+        .enumerate()
+        .map(|(i, p)| {
+            let address = p["address"].as_str().unwrap_or("").to_string();
+            match p["amount"].as_u64() {
+                Some(value) => BatchRow::new(i + 1, address, value, p["memo"].as_str().map(|m| m.to_string())),
+                None => BatchRow::invalid(i + 1, address, "Need an 'amount' in zats".to_string()),
+            }
+        })
+        .collect())
+}
+
+fn parse_csv_batch(contents: &str) -> Vec<BatchRow> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        // The header line, if there is one
+        .filter(|(i, line)| !(*i == 0 && line.trim().to_lowercase().starts_with("address")))
+        .map(|(i, line)| {
+            // The memo is everything after the second comma, so it can have commas of its own
+            let mut fields = line.splitn(3, ',');
+            let address = fields.next().unwrap_or("").trim().to_string();
+            let amount = fields.next().unwrap_or("").trim();
+            let memo = fields.next().map(|m| unquote(m.trim())).filter(|m| !m.is_empty());
+
+            match amount.parse::<u64>() {
+                Ok(value) => BatchRow::new(i + 1, address, value, memo),
+                Err(_) => BatchRow::invalid(i + 1, address, format!("Couldn't parse the amount '{}'", amount)),
+            }
+        })
+        .collect()
+}
+
+// A CSV field in double quotes, with any quotes inside it doubled
+fn unquote(field: &str) -> String {
+    if field.len() >= 2 && field.starts_with('"') && field.ends_with('"') {
+        field[1..field.len() - 1].replace("\"\"", "\"")
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{parse_batch, BatchRecords, MAX_BATCH_RECORDS};
+
+    #[test]
+    fn parse_csv() {
+        let csv = "address,amount,memo\n\
+                   zs1first,1000,\"Thanks, see you \"\"soon\"\"\"\n\
+                   \n\
+                   # Not paid this month\n\
+                   t1second,2000\n\
+                   zs1third,lots,memo\n";
+
+        let rows = parse_batch(csv).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        assert_eq!(rows[0].row, 2);
+        assert_eq!(rows[0].address, "zs1first");
+        assert_eq!(rows[0].value, 1000);
+        assert_eq!(rows[0].memo, Some("Thanks, see you \"soon\"".to_string()));
+        assert!(rows[0].error.is_none());
+
+        assert_eq!(rows[1].row, 5);
+        assert_eq!(rows[1].memo, None);
+        assert!(rows[1].error.is_none());
+
+        assert_eq!(rows[2].row, 6);
+        assert!(rows[2].error.is_some());
+    }
+
+    #[test]
+    fn parse_json() {
+        let j = r#"[{"address": "zs1first", "amount": 1000, "memo": "Hi"}, {"address": "t1second"}]"#;
+
+        let rows = parse_batch(j).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row, 1);
+        assert_eq!(rows[0].memo, Some("Hi".to_string()));
+        assert!(rows[0].error.is_none());
+        assert_eq!(rows[1].address, "t1second");
+        assert!(rows[1].error.is_some());
+
+        assert!(parse_batch("[]").is_err());
+        assert!(parse_batch("[{\"address\": ").is_err());
+    }
+
+    #[test]
+    fn batch_records() {
+        let contents = "zs1first,1000\nzs1second,2000\n";
+        let id = BatchRecords::batch_id(contents);
+        assert_ne!(id, BatchRecords::batch_id("zs1first,1000\n"));
+
+        let mut rows = parse_batch(contents).unwrap();
+        let mut records = BatchRecords::new();
+        assert!(records.sent_rows(&id).is_empty());
+
+        // Only rows with a txid are remembered
+        rows[0].txid = Some("txid1".to_string());
+        records.record(&id, &[&rows[0], &rows[1]]);
+        assert_eq!(records.sent_rows(&id), vec![(1, "txid1".to_string())]);
+
+        rows[1].txid = Some("txid2".to_string());
+        records.record(&id, &[&rows[1]]);
+        assert_eq!(records.sent_rows(&id).len(), 2);
+
+        let mut bytes = vec![];
+        records.write(&mut bytes).unwrap();
+        assert_eq!(BatchRecords::read(&bytes[..]).unwrap(), records);
+
+        // The oldest batches are forgotten
+        for i in 0..MAX_BATCH_RECORDS {
+            records.record(&BatchRecords::batch_id(&i.to_string()), &[&rows[0]]);
+        }
+        assert!(records.sent_rows(&id).is_empty());
+        assert_eq!(records.batches.len(), MAX_BATCH_RECORDS);
+    }
+}
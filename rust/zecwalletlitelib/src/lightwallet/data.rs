@@ -346,10 +346,13 @@ pub struct SaplingNoteData {
 
     // If the spending key is available in the wallet (i.e., whether to keep witness up-to-date)
     pub have_spending_key: bool,
+
+    // If the user has frozen this note, it is never selected to be spent
+    pub frozen: bool,
 }
 
 // Reading a note also needs the corresponding address to read from.
-fn read_rseed<R: Read>(mut reader: R) -> io::Result<Rseed> {
+pub(crate) fn read_rseed<R: Read>(mut reader: R) -> io::Result<Rseed> {
     let note_type = reader.read_u8()?;
 
     let mut r_bytes: [u8; 32] = [0; 32];
@@ -364,7 +367,7 @@ fn read_rseed<R: Read>(mut reader: R) -> io::Result<Rseed> {
     Ok(r)
 }
 
-fn write_rseed<W: Write>(mut writer: W, rseed: &Rseed) -> io::Result<()> {
+pub(crate) fn write_rseed<W: Write>(mut writer: W, rseed: &Rseed) -> io::Result<()> {
     let note_type = match rseed {
         Rseed::BeforeZip212(_) => 1,
         Rseed::AfterZip212(_) => 2,
@@ -379,7 +382,12 @@ fn write_rseed<W: Write>(mut writer: W, rseed: &Rseed) -> io::Result<()> {
 
 impl SaplingNoteData {
     fn serialized_version() -> u64 {
-        20
+        21
+    }
+
+    // Stable identifier for this note, used to pick it as an input to a send. This is the hex of the nullifier.
+    pub fn input_id(&self) -> String {
+        hex::encode(self.nullifier.0)
     }
 
     // Reading a note also needs the corresponding address to read from.
@@ -510,6 +518,8 @@ impl SaplingNoteData {
             reader.read_u8()? > 0
         };
 
+        let frozen = if version <= 20 { false } else { reader.read_u8()? > 0 };
+
         Ok(SaplingNoteData {
             extfvk,
             diversifier,
@@ -521,6 +531,7 @@ impl SaplingNoteData {
             memo,
             is_change,
             have_spending_key,
+            frozen,
         })
     }
 
@@ -561,6 +572,8 @@ impl SaplingNoteData {
 
         writer.write_u8(if self.have_spending_key { 1 } else { 0 })?;
 
+        writer.write_u8(if self.frozen { 1 } else { 0 })?;
+
         // Note that we don't write the unconfirmed_spent field, because if the wallet is restarted,
         // we don't want to be beholden to any expired txns
 
@@ -583,17 +596,25 @@ pub struct Utxo {
     // If this utxo was spent in a send, but has not yet been confirmed.
     // Contains the txid and height at which the Tx was broadcast
     pub unconfirmed_spent: Option<(TxId, u32)>,
+
+    // If the user has frozen this utxo, it is never selected to be spent
+    pub frozen: bool,
 }
 
 impl Utxo {
     pub fn serialized_version() -> u64 {
-        return 3;
+        return 4;
     }
 
     pub fn to_outpoint(&self) -> OutPoint {
         OutPoint::new(*self.txid.as_ref(), self.output_index as u32)
     }
 
+    // Stable identifier for this UTXO, used to pick it as an input to a send. This is "txid:output_index"
+    pub fn input_id(&self) -> String {
+        format!("{}:{}", self.txid, self.output_index)
+    }
+
     pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
         let version = reader.read_u64::<LittleEndian>()?;
 
@@ -641,6 +662,8 @@ impl Utxo {
             })?
         };
 
+        let frozen = if version <= 3 { false } else { reader.read_u8()? > 0 };
+
         Ok(Utxo {
             address,
             txid,
@@ -651,6 +674,7 @@ impl Utxo {
             spent_at_height,
             spent,
             unconfirmed_spent,
+            frozen,
         })
     }
 
@@ -677,6 +701,8 @@ impl Utxo {
             w.write_u32::<LittleEndian>(height)
         })?;
 
+        writer.write_u8(if self.frozen { 1 } else { 0 })?;
+
         Ok(())
     }
 }
@@ -769,11 +795,20 @@ pub struct WalletTx {
 
     // Price of Zec when this Tx was created
     pub zec_price: Option<f64>,
+
+    // Raw bytes of a Tx sent from this wallet. Kept while the Tx is unconfirmed, so it can be rebroadcast
+    pub raw_tx: Option<Vec<u8>>,
+
+    // Last height at which this Tx can be mined. 0 if it doesn't expire
+    pub expiry_height: u32,
+
+    // If this Tx was created by the wallet's auto-shield policy, instead of by the user
+    pub auto_shield: bool,
 }
 
 impl WalletTx {
     pub fn serialized_version() -> u64 {
-        return 23;
+        return 25;
     }
 
     pub fn new_txid(txid: &Vec<u8>) -> TxId {
@@ -814,6 +849,9 @@ impl WalletTx {
             outgoing_metadata: vec![],
             full_tx_scanned: false,
             zec_price: None,
+            raw_tx: None,
+            expiry_height: 0,
+            auto_shield: false,
         }
     }
 
@@ -883,6 +921,17 @@ impl WalletTx {
             })?
         };
 
+        let (raw_tx, expiry_height) = if version <= 23 {
+            (None, 0)
+        } else {
+            (
+                Optional::read(&mut reader, |r| Vector::read(r, |r| r.read_u8()))?,
+                reader.read_u32::<LittleEndian>()?,
+            )
+        };
+
+        let auto_shield = if version <= 24 { false } else { reader.read_u8()? == 1 };
+
         Ok(Self {
             block,
             unconfirmed,
@@ -899,6 +948,9 @@ impl WalletTx {
             outgoing_metadata,
             full_tx_scanned,
             zec_price,
+            raw_tx,
+            expiry_height,
+            auto_shield,
         })
     }
 
@@ -934,6 +986,13 @@ impl WalletTx {
 
         Vector::write(&mut writer, &self.o_spent_nullifiers, |w, n| w.write_all(&n.to_bytes()))?;
 
+        Optional::write(&mut writer, self.raw_tx.as_ref(), |w, b| {
+            Vector::write(w, b, |w, b| w.write_u8(*b))
+        })?;
+        writer.write_u32::<LittleEndian>(self.expiry_height)?;
+
+        writer.write_u8(if self.auto_shield { 1 } else { 0 })?;
+
         Ok(())
     }
 
@@ -959,6 +1018,11 @@ pub struct SpendableSaplingNote {
 }
 
 impl SpendableSaplingNote {
+    // Same as `SaplingNoteData::input_id()`
+    pub fn input_id(&self) -> String {
+        hex::encode(self.nullifier.0)
+    }
+
     pub fn from(
         txid: TxId,
         nd: &SaplingNoteData,
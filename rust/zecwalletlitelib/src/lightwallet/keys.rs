@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{self, Error, ErrorKind, Read, Write},
 };
 
@@ -14,7 +14,10 @@ use sodiumoxide::crypto::secretbox;
 use zcash_address::unified::Encoding;
 use zcash_client_backend::{
     address,
-    encoding::{encode_extended_full_viewing_key, encode_extended_spending_key, encode_payment_address},
+    encoding::{
+        decode_extended_full_viewing_key, decode_payment_address, encode_extended_full_viewing_key,
+        encode_extended_spending_key, encode_payment_address,
+    },
 };
 use zcash_encoding::Vector;
 use zcash_primitives::{
@@ -25,11 +28,13 @@ use zcash_primitives::{
 };
 
 use crate::{
-    lightclient::lightclient_config::{LightClientConfig, GAP_RULE_UNUSED_ADDRESSES},
+    lightclient::lightclient_config::{LightClientConfig, GAP_RULE_UNUSED_ACCOUNTS, GAP_RULE_UNUSED_ADDRESSES},
     lightwallet::utils,
 };
 
 use super::{
+    seed_phrase::seed_phrase_error,
+    walletaccount::{account_zip32_index, WalletAccount, DEFAULT_ACCOUNT},
     walletokey::WalletOKey,
     wallettkey::{WalletTKey, WalletTKeyType},
     walletzkey::{WalletZKey, WalletZKeyType},
@@ -112,6 +117,19 @@ pub struct Keys<P> {
 
     seed: [u8; 32], // Seed phrase for this wallet. If wallet is locked, this is 0
 
+    // A watch-only wallet has no seed, only the viewing keys and t-addresses it was created with. It can see
+    // its funds and history, but never spend or derive new keys.
+    watch_only: bool,
+
+    // Was the seed created with a BIP39 passphrase? The passphrase itself is only ever written encrypted, with its
+    // own nonce. An unencrypted wallet doesn't write it at all, and asks for it again before deriving new keys.
+    has_passphrase: bool,
+    enc_passphrase: Vec<u8>,
+    passphrase_nonce: Vec<u8>,
+
+    // In memory only. The passphrase, if it is known
+    passphrase: Option<String>,
+
     // List of keys, actually in this wallet. This is a combination of HD keys derived from the seed,
     // viewing keys and imported spending keys.
     pub(crate) zkeys: Vec<WalletZKey>,
@@ -123,11 +141,18 @@ pub struct Keys<P> {
     // Unified address (Orchard) keys actually in this wallet.
     // If wallet is locked, only viewing keys are present.
     pub(crate) okeys: Vec<WalletOKey>,
+
+    // The accounts the t- and z-keys are grouped in. There is always at least the default account.
+    pub(crate) accounts: Vec<WalletAccount>,
 }
 
 impl<P: consensus::Parameters> Keys<P> {
     pub fn serialized_version() -> u64 {
-        return 22;
+        return 25;
+    }
+
+    fn default_accounts() -> Vec<WalletAccount> {
+        vec![WalletAccount::new(DEFAULT_ACCOUNT, "Default".to_string())]
     }
 
     #[cfg(test)]
@@ -140,18 +165,28 @@ impl<P: consensus::Parameters> Keys<P> {
             enc_seed: [0; 48],
             nonce: vec![],
             seed: [0u8; 32],
+            watch_only: false,
+            has_passphrase: false,
+            enc_passphrase: vec![],
+            passphrase_nonce: vec![],
+            passphrase: None,
             zkeys: vec![],
             tkeys: vec![],
             okeys: vec![],
+            accounts: Self::default_accounts(),
         }
     }
 
     pub fn new(
         config: &LightClientConfig<P>,
         seed_phrase: Option<String>,
+        passphrase: Option<String>,
         num_zaddrs: u32,
         num_oaddrs: u32,
     ) -> Result<Self, String> {
+        // An empty passphrase is the same as none
+        let passphrase = passphrase.filter(|p| !p.is_empty());
+
         let mut seed_bytes = [0u8; 32];
 
         if seed_phrase.is_none() {
@@ -159,7 +194,13 @@ impl<P: consensus::Parameters> Keys<P> {
             let mut system_rng = OsRng;
             system_rng.fill(&mut seed_bytes);
         } else {
-            let phrase = match Mnemonic::from_phrase(seed_phrase.unwrap().as_str(), Language::English) {
+            // Check the words and the length first, since only a 24 word phrase fills the seed
+            let seed_phrase = seed_phrase.unwrap();
+            if let Some(e) = seed_phrase_error(&seed_phrase) {
+                return Err(format!("Error parsing phrase: {}", e));
+            }
+
+            let phrase = match Mnemonic::from_phrase(seed_phrase.as_str(), Language::English) {
                 Ok(p) => p,
                 Err(e) => {
                     let e = format!("Error parsing phrase: {}", e);
@@ -173,7 +214,10 @@ impl<P: consensus::Parameters> Keys<P> {
 
         // The seed bytes is the raw entropy. To pass it to HD wallet generation,
         // we need to get the 64 byte bip39 entropy
-        let bip39_seed = Seed::new(&Mnemonic::from_entropy(&seed_bytes, Language::English).unwrap(), "");
+        let bip39_seed = Seed::new(
+            &Mnemonic::from_entropy(&seed_bytes, Language::English).unwrap(),
+            passphrase.as_deref().unwrap_or(""),
+        );
 
         // Transparent keys - generate multiple for HD discovery during restoration
         let mut tkeys = vec![];
@@ -213,9 +257,64 @@ impl<P: consensus::Parameters> Keys<P> {
             enc_seed: [0; 48],
             nonce: vec![],
             seed: seed_bytes,
+            watch_only: false,
+            has_passphrase: passphrase.is_some(),
+            enc_passphrase: vec![],
+            passphrase_nonce: vec![],
+            passphrase,
             zkeys,
             tkeys,
             okeys,
+            accounts: Self::default_accounts(),
+        })
+    }
+
+    // Create the keys of a watch-only wallet from Sapling extended full viewing keys and t-addresses
+    pub fn new_watch_only(
+        config: &LightClientConfig<P>,
+        viewing_keys: &[String],
+        taddrs: &[String],
+    ) -> Result<Self, String> {
+        if viewing_keys.is_empty() && taddrs.is_empty() {
+            return Err("Need at least one viewing key or t-address to watch".to_string());
+        }
+
+        let mut zkeys: Vec<WalletZKey> = vec![];
+        for vk in viewing_keys {
+            let extfvk = match decode_extended_full_viewing_key(config.hrp_sapling_viewing_key(), vk) {
+                Ok(Some(k)) => k,
+                Ok(None) => return Err(format!("Couldn't decode viewing key {}", vk)),
+                Err(e) => return Err(format!("Error decoding viewing key {}: {}", vk, e)),
+            };
+            if !zkeys.iter().any(|zk| zk.extfvk == extfvk) {
+                zkeys.push(WalletZKey::new_imported_viewkey(extfvk));
+            }
+        }
+
+        let mut tkeys: Vec<WalletTKey> = vec![];
+        for taddr in taddrs {
+            let tkey = WalletTKey::new_watch_only(config, taddr).map_err(|e| e.to_string())?;
+            if !tkeys.iter().any(|tk| tk.address == tkey.address) {
+                tkeys.push(tkey);
+            }
+        }
+
+        Ok(Self {
+            config: config.clone(),
+            encrypted: false,
+            unlocked: true,
+            enc_seed: [0; 48],
+            nonce: vec![],
+            seed: [0u8; 32],
+            watch_only: true,
+            has_passphrase: false,
+            enc_passphrase: vec![],
+            passphrase_nonce: vec![],
+            passphrase: None,
+            zkeys,
+            tkeys,
+            okeys: vec![],
+            accounts: Self::default_accounts(),
         })
     }
 
@@ -339,9 +438,15 @@ impl<P: consensus::Parameters> Keys<P> {
             enc_seed,
             nonce,
             seed: seed_bytes,
+            watch_only: false,
+            has_passphrase: false,
+            enc_passphrase: vec![],
+            passphrase_nonce: vec![],
+            passphrase: None,
             zkeys,
             tkeys,
             okeys: vec![],
+            accounts: Self::default_accounts(),
         })
     }
 
@@ -394,6 +499,24 @@ impl<P: consensus::Parameters> Keys<P> {
             Vector::read(&mut reader, |r| WalletTKey::read(r))?
         };
 
+        let accounts = if version <= 22 {
+            Self::default_accounts()
+        } else {
+            Vector::read(&mut reader, |r| WalletAccount::read(r))?
+        };
+
+        let watch_only = if version <= 23 { false } else { reader.read_u8()? > 0 };
+
+        let (has_passphrase, enc_passphrase, passphrase_nonce) = if version <= 24 {
+            (false, vec![], vec![])
+        } else {
+            (
+                reader.read_u8()? > 0,
+                Vector::read(&mut reader, |r| r.read_u8())?,
+                Vector::read(&mut reader, |r| r.read_u8())?,
+            )
+        };
+
         let keys = Self {
             config: config.clone(),
             encrypted,
@@ -401,9 +524,15 @@ impl<P: consensus::Parameters> Keys<P> {
             enc_seed,
             nonce,
             seed: seed_bytes,
+            watch_only,
+            has_passphrase,
+            enc_passphrase,
+            passphrase_nonce,
+            passphrase: None,
             zkeys,
             tkeys,
             okeys,
+            accounts,
         };
 
         // If there are no okeys, derive the first one.
@@ -439,6 +568,14 @@ impl<P: consensus::Parameters> Keys<P> {
         // Write the transparent private keys
         Vector::write(&mut writer, &self.tkeys, |w, sk| sk.write(w))?;
 
+        Vector::write(&mut writer, &self.accounts, |w, a| a.write(w))?;
+
+        writer.write_u8(if self.watch_only { 1 } else { 0 })?;
+
+        writer.write_u8(if self.has_passphrase { 1 } else { 0 })?;
+        Vector::write(&mut writer, &self.enc_passphrase, |w, b| w.write_u8(*b))?;
+        Vector::write(&mut writer, &self.passphrase_nonce, |w, b| w.write_u8(*b))?;
+
         Ok(())
     }
 
@@ -446,8 +583,70 @@ impl<P: consensus::Parameters> Keys<P> {
         self.config.clone()
     }
 
-    pub fn get_seed_phrase(&self) -> String {
+    pub fn is_watch_only(&self) -> bool {
+        self.watch_only
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.has_passphrase
+    }
+
+    // The passphrase of an unencrypted wallet isn't saved, so it has to be entered again before new keys can be
+    // derived from the seed
+    pub fn needs_passphrase(&self) -> bool {
+        self.has_passphrase && self.passphrase.is_none()
+    }
+
+    // The 64 byte BIP39 seed all the HD keys are derived from. This needs the passphrase, if the wallet has one.
+    fn bip39_seed(&self) -> Result<Seed, String> {
+        if self.needs_passphrase() {
+            return Err("The wallet's passphrase needs to be entered first".to_string());
+        }
+
+        Ok(Seed::new(
+            &Mnemonic::from_entropy(&self.seed, Language::English).unwrap(),
+            self.passphrase.as_deref().unwrap_or(""),
+        ))
+    }
+
+    // Enter the passphrase of an unencrypted wallet. It is checked against the wallet's HD keys, since a wrong
+    // passphrase would silently derive the keys of a different wallet.
+    pub fn set_passphrase(&mut self, passphrase: String) -> Result<(), String> {
+        if !self.has_passphrase {
+            return Err("This wallet doesn't have a passphrase".to_string());
+        }
         if !self.unlocked {
+            return Err("Can't check the passphrase while the wallet is locked".to_string());
+        }
+
+        let bip39_seed = Seed::new(
+            &Mnemonic::from_entropy(&self.seed, Language::English).unwrap(),
+            passphrase.as_str(),
+        );
+        let zkey_matches = self.zkeys.iter().filter(|zk| zk.hdkey_num.is_some()).all(|zk| {
+            let (_, extfvk, _) =
+                Self::get_zaddr_from_bip39seed(&self.config, bip39_seed.as_bytes(), zk.hdkey_num.unwrap());
+            extfvk == zk.extfvk
+        });
+        let tkey_matches = self.tkeys.iter().filter(|tk| tk.hdkey_num.is_some()).all(|tk| {
+            let key = WalletTKey::new_hdkey_for_account(
+                &self.config,
+                tk.account,
+                tk.hdkey_num.unwrap(),
+                bip39_seed.as_bytes(),
+            );
+            key.address == tk.address
+        });
+        if !zkey_matches || !tkey_matches {
+            return Err("Wrong passphrase".to_string());
+        }
+
+        self.passphrase = Some(passphrase);
+        Ok(())
+    }
+
+    pub fn get_seed_phrase(&self) -> String {
+        if !self.unlocked || self.watch_only {
             return "".to_string();
         }
 
@@ -478,10 +677,12 @@ impl<P: consensus::Parameters> Keys<P> {
             .collect()
     }
 
+    // All the z-addresses of the wallet. Each key's default address is followed by its diversified addresses.
     pub fn get_all_zaddresses(&self) -> Vec<String> {
         self.zkeys
             .iter()
-            .map(|zk| encode_payment_address(self.config.hrp_sapling_address(), &zk.zaddress))
+            .flat_map(|zk| zk.all_addresses())
+            .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), pa))
             .collect()
     }
 
@@ -497,7 +698,8 @@ impl<P: consensus::Parameters> Keys<P> {
         self.zkeys
             .iter()
             .filter(|zk| zk.have_spending_key())
-            .map(|zk| encode_payment_address(self.config.hrp_sapling_address(), &zk.zaddress))
+            .flat_map(|zk| zk.all_addresses())
+            .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), pa))
             .collect()
     }
 
@@ -540,82 +742,223 @@ impl<P: consensus::Parameters> Keys<P> {
     pub fn get_taddr_to_sk_map(&self) -> HashMap<String, secp256k1::SecretKey> {
         self.tkeys
             .iter()
-            .map(|tk| (tk.address.clone(), tk.key.unwrap().clone()))
+            .filter_map(|tk| tk.key.map(|sk| (tk.address.clone(), sk)))
             .collect()
     }
 
-    // If one of the last 'n' taddress was used, ensure we add the next HD taddress to the wallet.
-    pub fn ensure_hd_taddresses(&mut self, address: &String) {
+    // The account of `address` and how many HD t-addresses to add to it, if `address` is one of the last 'n'
+    // HD t-addresses of its account
+    fn hd_taddr_gap(&self, address: &String) -> Option<(u32, usize)> {
         if GAP_RULE_UNUSED_ADDRESSES == 0 {
-            return;
+            return None;
         }
 
+        let account = self
+            .tkeys
+            .iter()
+            .find(|tk| tk.keytype == WalletTKeyType::HdKey && tk.address == *address)?
+            .account;
+
         let last_addresses = {
             self.tkeys
                 .iter()
-                .filter(|tk| tk.keytype == WalletTKeyType::HdKey)
+                .filter(|tk| tk.keytype == WalletTKeyType::HdKey && tk.account == account)
                 .rev()
                 .take(GAP_RULE_UNUSED_ADDRESSES)
                 .map(|s| s.address.clone())
                 .collect::<Vec<String>>()
         };
 
-        match last_addresses.iter().position(|s| *s == *address) {
-            None => {
-                return;
-            }
-            Some(pos) => {
-                //info!("Adding {} new zaddrs", (GAP_RULE_UNUSED_ADDRESSES - pos));
-                // If it in the last unused, addresses, create that many more
-                for _ in 0..(GAP_RULE_UNUSED_ADDRESSES - pos) {
-                    // If the wallet is locked, this is a no-op. That is fine, since we really
-                    // need to only add new addresses when restoring a new wallet, when it will not be locked.
-                    // Also, if it is locked, the user can't create new addresses anyway.
-                    self.add_taddr();
-                }
+        let pos = last_addresses.iter().position(|s| *s == *address)?;
+        Some((account, GAP_RULE_UNUSED_ADDRESSES - pos))
+    }
+
+    // If one of the last 'n' taddress of an account was used, ensure we add the next HD taddress to the account.
+    // Fails if the addresses can't be derived because the passphrase hasn't been entered.
+    pub fn ensure_hd_taddresses(&mut self, address: &String) -> Result<(), String> {
+        // If the wallet is locked, this is a no-op. That is fine, since we really
+        // need to only add new addresses when restoring a new wallet, when it will not be locked.
+        // Also, if it is locked, the user can't create new addresses anyway.
+        if !self.unlocked || self.watch_only {
+            return Ok(());
+        }
+
+        if let Some((account, count)) = self.hd_taddr_gap(address) {
+            //info!("Adding {} new taddrs", count);
+            for _ in 0..count {
+                self.add_taddr_to_account(account)?;
             }
         }
+
+        Ok(())
     }
 
-    // If one of the last 'n' zaddress was used, ensure we add the next HD zaddress to the wallet
-    pub fn ensure_hd_zaddresses(&mut self, address: &String) {
-        if GAP_RULE_UNUSED_ADDRESSES == 0 {
-            return;
+    // Receiving at `address` would add HD t-addresses, so check that they can be derived before sending to it
+    pub fn check_hd_recipient(&self, address: &String) -> Result<(), String> {
+        if !self.unlocked || self.watch_only || self.hd_taddr_gap(address).is_none() {
+            return Ok(());
+        }
+
+        self.bip39_seed().map(|_| ())
+    }
+
+    // If one of the last 'n' zaddress was used, ensure we add the next HD zaddress to the wallet. Only the default
+    // account has a key per z-address, the other accounts' z-addresses are all diversified addresses of one key.
+    // Fails if the addresses can't be derived because the passphrase hasn't been entered.
+    pub fn ensure_hd_zaddresses(&mut self, address: &String) -> Result<(), String> {
+        if GAP_RULE_UNUSED_ADDRESSES == 0 || !self.unlocked || self.watch_only {
+            return Ok(());
         }
 
         let last_addresses = {
             self.zkeys
                 .iter()
-                .filter(|zk| zk.keytype == WalletZKeyType::HdKey)
+                .filter(|zk| zk.keytype == WalletZKeyType::HdKey && zk.account == DEFAULT_ACCOUNT)
                 .rev()
                 .take(GAP_RULE_UNUSED_ADDRESSES)
                 .map(|s| encode_payment_address(self.config.hrp_sapling_address(), &s.zaddress))
                 .collect::<Vec<String>>()
         };
 
-        match last_addresses.iter().position(|s| *s == *address) {
-            None => {
-                return;
+        if let Some(pos) = last_addresses.iter().position(|s| *s == *address) {
+            //info!("Adding {} new zaddrs", (GAP_RULE_UNUSED_ADDRESSES - pos));
+            // If it in the last unused, addresses, create that many more
+            for _ in 0..(GAP_RULE_UNUSED_ADDRESSES - pos) {
+                self.add_zaddr()?;
             }
-            Some(pos) => {
-                //info!("Adding {} new zaddrs", (GAP_RULE_UNUSED_ADDRESSES - pos));
-                // If it in the last unused, addresses, create that many more
-                for _ in 0..(GAP_RULE_UNUSED_ADDRESSES - pos) {
-                    // If the wallet is locked, this is a no-op. That is fine, since we really
-                    // need to only add new addresses when restoring a new wallet, when it will not be locked.
-                    // Also, if it is locked, the user can't create new addresses anyway.
-                    self.add_zaddr();
-                }
+        }
+
+        Ok(())
+    }
+
+    // Derive HD t-addresses of every account until the last GAP_RULE_UNUSED_ADDRESSES of each account are not
+    // in `used`. Unlike `ensure_hd_taddresses`, this looks at all the used addresses at once, so it also fills a
+    // gap that was left when addresses were only added while syncing. Returns the number of addresses added.
+    pub fn fill_hd_taddr_gap(&mut self, used: &HashSet<String>) -> Result<usize, String> {
+        if !self.unlocked || self.watch_only {
+            return Ok(0);
+        }
+
+        let mut added = 0;
+        for account in self.accounts.iter().map(|a| a.id).collect::<Vec<_>>() {
+            let account_tkeys = || self.tkeys.iter().filter(|tk| tk.account == account);
+
+            let next = account_tkeys()
+                .filter_map(|tk| tk.hdkey_num)
+                .max()
+                .map_or(0, |n| n as usize + 1);
+            let target = account_tkeys()
+                .filter(|tk| used.contains(&tk.address))
+                .filter_map(|tk| tk.hdkey_num)
+                .max()
+                .map_or(0, |n| n as usize + 1)
+                + GAP_RULE_UNUSED_ADDRESSES;
+
+            for _ in next..target {
+                self.add_taddr_to_account(account)?;
+                added += 1;
             }
         }
+
+        Ok(added)
+    }
+
+    // Derive HD z-addresses of the default account until the keys of the last GAP_RULE_UNUSED_ADDRESSES of them
+    // are not in `used`. The other accounts have a single z-key, which receives at all their z-addresses.
+    // Returns the number of addresses added.
+    pub fn fill_hd_zaddr_gap(&mut self, used: &[ExtendedFullViewingKey]) -> Result<usize, String> {
+        if !self.unlocked || self.watch_only {
+            return Ok(0);
+        }
+
+        let default_zkeys = || self.zkeys.iter().filter(|zk| zk.account == DEFAULT_ACCOUNT);
+
+        let next = default_zkeys()
+            .filter_map(|zk| zk.hdkey_num)
+            .max()
+            .map_or(0, |n| n as usize + 1);
+        let target = default_zkeys()
+            .filter(|zk| used.contains(&zk.extfvk))
+            .filter_map(|zk| zk.hdkey_num)
+            .max()
+            .map_or(0, |n| n as usize + 1)
+            + GAP_RULE_UNUSED_ADDRESSES;
+
+        let mut added = 0;
+        for _ in next..target {
+            self.add_zaddr()?;
+            added += 1;
+        }
+
+        Ok(added)
+    }
+
+    // The highest id of an account with a t-address in `used_taddrs` or a z-key in `used_extfvks`
+    fn highest_used_account(&self, used_taddrs: &HashSet<String>, used_extfvks: &[ExtendedFullViewingKey]) -> u32 {
+        let used_tkeys = self
+            .tkeys
+            .iter()
+            .filter(|tk| used_taddrs.contains(&tk.address))
+            .map(|tk| tk.account);
+        let used_zkeys = self
+            .zkeys
+            .iter()
+            .filter(|zk| used_extfvks.contains(&zk.extfvk))
+            .map(|zk| zk.account);
+
+        used_tkeys.chain(used_zkeys).max().unwrap_or(DEFAULT_ACCOUNT)
+    }
+
+    // Add accounts until the last GAP_RULE_UNUSED_ACCOUNTS of them haven't received anything, so that a wallet
+    // restored from its seed finds the accounts it was created with. Accounts get their ids in the order they are
+    // created, and all their keys are derived from the id. Returns the number of addresses added.
+    pub fn fill_hd_account_gap(
+        &mut self,
+        used_taddrs: &HashSet<String>,
+        used_extfvks: &[ExtendedFullViewingKey],
+    ) -> Result<usize, String> {
+        if !self.unlocked || self.watch_only {
+            return Ok(0);
+        }
+
+        let next = self.accounts.iter().map(|a| a.id).max().map_or(0, |id| id + 1);
+        let target = self.highest_used_account(used_taddrs, used_extfvks) + GAP_RULE_UNUSED_ACCOUNTS as u32;
+        if next <= target {
+            // Fail before any account is added if their keys can't be derived
+            self.bip39_seed()?;
+        }
+
+        let mut added = 0;
+        for id in next..=target {
+            self.accounts.push(WalletAccount::new(id, format!("Account {}", id)));
+            self.add_zaddr_to_account(id)?;
+            self.add_taddr_to_account(id)?;
+            added += 2;
+        }
+
+        Ok(added)
+    }
+
+    // Remove the accounts after the last one that has received anything, with their keys. After a restore, these
+    // are the accounts `fill_hd_account_gap` added to look for more. An account that is created again later gets
+    // the same id, so the same keys.
+    pub fn remove_unused_accounts(&mut self, used_taddrs: &HashSet<String>, used_extfvks: &[ExtendedFullViewingKey]) {
+        let highest = self.highest_used_account(used_taddrs, used_extfvks);
+
+        self.accounts.retain(|a| a.id <= highest);
+        self.tkeys.retain(|tk| tk.account <= highest);
+        self.zkeys.retain(|zk| zk.account <= highest);
     }
 
     /// Adds a new unified address to the wallet. This will derive a new address from the seed
     /// at the next position and add it to the wallet.
     /// NOTE: This does NOT rescan
-    pub fn add_oaddr(&mut self) -> String {
+    pub fn add_oaddr(&mut self) -> Result<String, String> {
+        if self.watch_only {
+            return Err("Can't derive keys in a watch-only wallet".to_string());
+        }
         if !self.unlocked {
-            return "Error: Can't add key while wallet is locked".to_string();
+            return Err("Can't add key while wallet is locked".to_string());
         }
 
         // Find the highest pos we have
@@ -626,7 +969,7 @@ impl<P: consensus::Parameters> Keys<P> {
             .max_by(|ok1, ok2| ok1.hdkey_num.unwrap().cmp(&ok2.hdkey_num.unwrap()))
             .map_or(0, |ok| ok.hdkey_num.unwrap() + 1);
 
-        let bip39_seed = bip39::Seed::new(&Mnemonic::from_entropy(&self.seed, Language::English).unwrap(), "");
+        let bip39_seed = self.bip39_seed()?;
 
         let spending_key =
             orchard::keys::SpendingKey::from_zip32_seed(&bip39_seed.as_bytes(), self.config.get_coin_type(), pos)
@@ -636,59 +979,248 @@ impl<P: consensus::Parameters> Keys<P> {
 
         self.okeys.push(newkey);
 
-        return ua;
+        Ok(ua)
     }
 
     /// Adds a new z address to the wallet. This will derive a new address from the seed
     /// at the next position and add it to the wallet.
     /// NOTE: This does NOT rescan
-    pub fn add_zaddr(&mut self) -> String {
+    pub fn add_zaddr(&mut self) -> Result<String, String> {
+        self.add_zaddr_to_account(DEFAULT_ACCOUNT)
+    }
+
+    /// Adds a new z address to an account. The default account derives a key for every z-address, at the next
+    /// ZIP-32 account index of its own. The other accounts derive their one key at `account_zip32_index`, and
+    /// after that, every new z-address is the next diversified address of that key.
+    /// NOTE: This does NOT rescan
+    pub fn add_zaddr_to_account(&mut self, account: u32) -> Result<String, String> {
+        if self.watch_only {
+            return Err("Can't derive keys in a watch-only wallet".to_string());
+        }
         if !self.unlocked {
-            return "Error: Can't add key while wallet is locked".to_string();
+            return Err("Can't add key while wallet is locked".to_string());
+        }
+        if self.get_account(account).is_none() {
+            return Err(format!("No account {}", account));
         }
 
-        // Find the highest pos we have
-        let pos = self
-            .zkeys
-            .iter()
-            .filter(|zk| zk.hdkey_num.is_some())
-            .max_by(|zk1, zk2| zk1.hdkey_num.unwrap().cmp(&zk2.hdkey_num.unwrap()))
-            .map_or(0, |zk| zk.hdkey_num.unwrap() + 1);
+        let pos = if account == DEFAULT_ACCOUNT {
+            // Find the highest pos we have in the default account
+            self.zkeys
+                .iter()
+                .filter(|zk| zk.hdkey_num.is_some() && zk.account == DEFAULT_ACCOUNT)
+                .max_by(|zk1, zk2| zk1.hdkey_num.unwrap().cmp(&zk2.hdkey_num.unwrap()))
+                .map_or(0, |zk| zk.hdkey_num.unwrap() + 1)
+        } else {
+            let hrp = self.config.hrp_sapling_address();
+            let account_zkey = self
+                .zkeys
+                .iter_mut()
+                .find(|zk| zk.hdkey_num.is_some() && zk.account == account);
+            if let Some(zk) = account_zkey {
+                return match zk.new_diversified_address() {
+                    Some(pa) => Ok(encode_payment_address(hrp, &pa)),
+                    None => Err("No more diversified addresses for this account".to_string()),
+                };
+            }
+
+            account_zip32_index(account)
+        };
 
-        let bip39_seed = bip39::Seed::new(&Mnemonic::from_entropy(&self.seed, Language::English).unwrap(), "");
+        let bip39_seed = self.bip39_seed()?;
 
         let (extsk, _, _) = Self::get_zaddr_from_bip39seed(&self.config, &bip39_seed.as_bytes(), pos);
 
         // let zaddr = encode_payment_address(self.config.hrp_sapling_address(), &address);
-        let newkey = WalletZKey::new_hdkey(pos, extsk);
+        let mut newkey = WalletZKey::new_hdkey(pos, extsk);
+        newkey.account = account;
         self.zkeys.push(newkey.clone());
 
-        encode_payment_address(self.config.hrp_sapling_address(), &newkey.zaddress)
+        Ok(encode_payment_address(
+            self.config.hrp_sapling_address(),
+            &newkey.zaddress,
+        ))
+    }
+
+    /// Adds a new diversified address for the key of `zaddr`, or for the first HD key if there is no `zaddr`.
+    /// The address shares the key's viewing key, so unlike `add_zaddr`, it doesn't add a key that has to be
+    /// trial decrypted during sync.
+    /// NOTE: This does NOT rescan
+    pub fn add_diversified_zaddr(&mut self, zaddr: Option<&str>) -> Result<String, String> {
+        let zkey = match zaddr {
+            Some(zaddr) => {
+                let address = match decode_payment_address(self.config.hrp_sapling_address(), zaddr) {
+                    Ok(Some(pa)) => pa,
+                    _ => return Err(format!("Invalid z-address {}", zaddr)),
+                };
+                self.zkeys.iter_mut().find(|zk| zk.owns_address(&address))
+            }
+            None => self.zkeys.iter_mut().find(|zk| zk.keytype == WalletZKeyType::HdKey),
+        };
+
+        let zkey = match zkey {
+            Some(zk) => zk,
+            None => return Err(format!("No key in this wallet for {}", zaddr.unwrap_or("a z-address"))),
+        };
+
+        match zkey.new_diversified_address() {
+            Some(pa) => Ok(encode_payment_address(self.config.hrp_sapling_address(), &pa)),
+            None => Err("No more diversified addresses for this key".to_string()),
+        }
+    }
+
+    /// A new change address of `account`: the next diversified address of the key of `input_address`, if it is
+    /// one of the account's keys, or else of the account's first key. The address isn't kept until
+    /// `keep_diversified_addresses` is called with it, so a send that fails doesn't use it up.
+    pub fn next_change_address(&self, account: u32, input_address: Option<&str>) -> Result<PaymentAddress, String> {
+        let input_address = input_address
+            .and_then(|a| decode_payment_address(self.config.hrp_sapling_address(), a).ok())
+            .flatten();
+
+        let mut account_zkeys = self
+            .zkeys
+            .iter()
+            .filter(|zk| zk.account == account && zk.have_spending_key());
+        let zkey = match &input_address {
+            Some(pa) => account_zkeys.clone().find(|zk| zk.owns_address(pa)),
+            None => None,
+        }
+        .or_else(|| account_zkeys.next());
+
+        match zkey.map(|zk| zk.next_diversified_address()) {
+            Some(Some((_, pa))) => Ok(pa),
+            Some(None) => Err("No more diversified addresses for the change".to_string()),
+            None => Err(format!("No z-address in account {} to send the change to", account)),
+        }
+    }
+
+    /// Keep the diversified addresses handed out by `next_change_address` that a Tx was built with. Addresses
+    /// that aren't new addresses of this wallet are ignored.
+    pub fn keep_diversified_addresses(&mut self, addresses: &[PaymentAddress]) {
+        for address in addresses {
+            if let Some(zk) = self.zkeys.iter_mut().find(|zk| zk.owns_address(address)) {
+                if !zk.all_addresses().contains(&address) {
+                    zk.keep_diversified_address(address);
+                }
+            }
+        }
     }
 
     /// Add a new t address to the wallet. This will derive a new address from the seed
     /// at the next position.
     /// NOTE: This will not rescan the wallet
-    pub fn add_taddr(&mut self) -> String {
+    pub fn add_taddr(&mut self) -> Result<String, String> {
+        self.add_taddr_to_account(DEFAULT_ACCOUNT)
+    }
+
+    /// Add a new t address to an account, deriving it at the next position of the account's BIP44 path, whose
+    /// account' is the account's id.
+    /// NOTE: This will not rescan the wallet
+    pub fn add_taddr_to_account(&mut self, account: u32) -> Result<String, String> {
+        if self.watch_only {
+            return Err("Can't derive keys in a watch-only wallet".to_string());
+        }
         if !self.unlocked {
-            return "Error: Can't add key while wallet is locked".to_string();
+            return Err("Can't add key while wallet is locked".to_string());
+        }
+        if self.get_account(account).is_none() {
+            return Err(format!("No account {}", account));
         }
 
-        // Find the highest pos we have
+        // Find the highest pos we have in this account
         let pos = self
             .tkeys
             .iter()
-            .filter(|sk| sk.hdkey_num.is_some())
+            .filter(|sk| sk.hdkey_num.is_some() && sk.account == account)
             .max_by(|sk1, sk2| sk1.hdkey_num.unwrap().cmp(&sk2.hdkey_num.unwrap()))
             .map_or(0, |sk| sk.hdkey_num.unwrap() + 1);
 
-        let bip39_seed = bip39::Seed::new(&Mnemonic::from_entropy(&self.seed, Language::English).unwrap(), "");
+        let bip39_seed = self.bip39_seed()?;
 
-        let key = WalletTKey::new_hdkey(&self.config, pos, &bip39_seed.as_bytes());
+        let key = WalletTKey::new_hdkey_for_account(&self.config, account, pos, &bip39_seed.as_bytes());
         let address = key.address.clone();
         self.tkeys.push(key);
 
-        address
+        Ok(address)
+    }
+
+    pub fn get_account(&self, account: u32) -> Option<&WalletAccount> {
+        self.accounts.iter().find(|a| a.id == account)
+    }
+
+    pub fn get_accounts(&self) -> Vec<WalletAccount> {
+        self.accounts.clone()
+    }
+
+    fn check_account_name(&self, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("The account name can't be empty".to_string());
+        }
+        if self.accounts.iter().any(|a| a.name == name) {
+            return Err(format!("There already is an account named '{}'", name));
+        }
+
+        Ok(name.to_string())
+    }
+
+    /// Create a new account, with a z and a t address of its own. Returns the id of the account.
+    /// NOTE: This does NOT rescan
+    pub fn add_account(&mut self, name: &str) -> Result<u32, String> {
+        if self.watch_only {
+            return Err("Can't add an account to a watch-only wallet".to_string());
+        }
+        if !self.unlocked {
+            return Err("Can't add an account while the wallet is locked".to_string());
+        }
+        let name = self.check_account_name(name)?;
+        // Fail before the account is added if its keys can't be derived
+        self.bip39_seed()?;
+
+        let id = self.accounts.iter().map(|a| a.id).max().map_or(0, |id| id + 1);
+        self.accounts.push(WalletAccount::new(id, name));
+
+        self.add_zaddr_to_account(id)?;
+        self.add_taddr_to_account(id)?;
+
+        Ok(id)
+    }
+
+    pub fn rename_account(&mut self, account: u32, name: &str) -> Result<(), String> {
+        if self.get_account(account).is_none() {
+            return Err(format!("No account {}", account));
+        }
+        let name = self.check_account_name(name)?;
+
+        self.accounts.iter_mut().find(|a| a.id == account).unwrap().name = name;
+        Ok(())
+    }
+
+    // The z-addresses (with the diversified ones) and t-addresses of an account
+    pub fn get_account_addresses(&self, account: u32) -> (Vec<String>, Vec<String>) {
+        let zaddrs = self
+            .zkeys
+            .iter()
+            .filter(|zk| zk.account == account)
+            .flat_map(|zk| zk.all_addresses())
+            .map(|pa| encode_payment_address(self.config.hrp_sapling_address(), pa))
+            .collect();
+        let taddrs = self
+            .tkeys
+            .iter()
+            .filter(|tk| tk.account == account)
+            .map(|tk| tk.address.clone())
+            .collect();
+
+        (zaddrs, taddrs)
+    }
+
+    // The account a z-address (diversified or not) or t-address of this wallet belongs to
+    pub fn get_address_account(&self, address: &str) -> Option<u32> {
+        match decode_payment_address(self.config.hrp_sapling_address(), address) {
+            Ok(Some(pa)) => self.zkeys.iter().find(|zk| zk.owns_address(&pa)).map(|zk| zk.account),
+            _ => self.tkeys.iter().find(|tk| tk.address == address).map(|tk| tk.account),
+        }
     }
 
     // Get all z-address private keys. Returns a Vector of (address, privatekey, viewkey)
@@ -731,6 +1263,19 @@ impl<P: consensus::Parameters> Keys<P> {
         if self.encrypted {
             return Err(io::Error::new(ErrorKind::AlreadyExists, "Wallet is already encrypted"));
         }
+        if self.watch_only {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "A watch-only wallet has no keys to encrypt",
+            ));
+        }
+
+        if self.needs_passphrase() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "The wallet's passphrase needs to be entered before encrypting it",
+            ));
+        }
 
         // Get the doublesha256 of the password, which is the right length
         let key = secretbox::Key::from_slice(&double_sha256(passwd.as_bytes())).unwrap();
@@ -741,6 +1286,13 @@ impl<P: consensus::Parameters> Keys<P> {
         self.enc_seed.copy_from_slice(&cipher);
         self.nonce = nonce.as_ref().to_vec();
 
+        // The passphrase is sealed with the same key, but never with the same nonce as the seed
+        if let Some(passphrase) = &self.passphrase {
+            let passphrase_nonce = secretbox::gen_nonce();
+            self.enc_passphrase = secretbox::seal(passphrase.as_bytes(), &passphrase_nonce, &key);
+            self.passphrase_nonce = passphrase_nonce.as_ref().to_vec();
+        }
+
         // Encrypt the individual keys
         self.zkeys
             .iter_mut()
@@ -767,8 +1319,9 @@ impl<P: consensus::Parameters> Keys<P> {
             return Err(io::Error::new(ErrorKind::AlreadyExists, "Wallet is already locked"));
         }
 
-        // Empty the seed and the secret keys
+        // Empty the seed, the passphrase and the secret keys
         self.seed.copy_from_slice(&[0u8; 32]);
+        self.passphrase = None;
 
         // Remove all the private key from the zkeys and tkeys
         self.tkeys
@@ -812,9 +1365,27 @@ impl<P: consensus::Parameters> Keys<P> {
         // Now that we have the seed, we'll generate the extsks and tkeys, and verify the fvks and addresses
         // respectively match
 
+        let passphrase = if self.has_passphrase {
+            let passphrase_nonce = secretbox::Nonce::from_slice(&self.passphrase_nonce).unwrap();
+            match secretbox::open(&self.enc_passphrase, &passphrase_nonce, &key) {
+                Ok(p) => Some(String::from_utf8(p).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?),
+                Err(_) => {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        "Decryption of the passphrase failed. Is your password correct?",
+                    ));
+                }
+            }
+        } else {
+            None
+        };
+
         // The seed bytes is the raw entropy. To pass it to HD wallet generation,
         // we need to get the 64 byte bip39 entropy
-        let bip39_seed = bip39::Seed::new(&Mnemonic::from_entropy(&seed, Language::English).unwrap(), "");
+        let bip39_seed = bip39::Seed::new(
+            &Mnemonic::from_entropy(&seed, Language::English).unwrap(),
+            passphrase.as_deref().unwrap_or(""),
+        );
         let config = self.config.clone();
 
         // Transparent keys
@@ -830,6 +1401,7 @@ impl<P: consensus::Parameters> Keys<P> {
             .collect::<io::Result<Vec<()>>>()?;
 
         self.seed.copy_from_slice(&seed);
+        self.passphrase = passphrase;
 
         self.encrypted = true;
         self.unlocked = true;
@@ -864,6 +1436,8 @@ impl<P: consensus::Parameters> Keys<P> {
         self.encrypted = false;
         self.nonce = vec![];
         self.enc_seed.copy_from_slice(&[0u8; 48]);
+        self.enc_passphrase = vec![];
+        self.passphrase_nonce = vec![];
 
         Ok(())
     }
@@ -0,0 +1,152 @@
+use json::{object, JsonValue};
+use std::convert::TryFrom;
+use zcash_client_backend::address::RecipientAddress;
+use zcash_primitives::{
+    consensus,
+    memo::{Memo, MemoBytes},
+};
+
+pub const MAX_MEMO_SIZE: usize = 512;
+
+// How a memo was written: as text, or as "0x" followed by the hex bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoEncoding {
+    Text,
+    Hex,
+}
+
+// The bytes a memo string stands for. A string that starts with "0x" and is otherwise valid hex is the hex bytes,
+// anything else is the UTF-8 text.
+fn memo_bytes(memo_str: &str) -> (MemoEncoding, Vec<u8>) {
+    if memo_str.to_lowercase().starts_with("0x") {
+        if let Ok(bytes) = hex::decode(&memo_str[2..]) {
+            return (MemoEncoding::Hex, bytes);
+        }
+    }
+
+    (MemoEncoding::Text, memo_str.as_bytes().to_vec())
+}
+
+// Turn a memo string into the memo field of an output, checking it is a memo ZIP-302 allows: at most 512 bytes of
+// UTF-8 text, the empty memo, or arbitrary data (starting with 0xFF).
+pub fn parse_memo(memo_str: &str) -> Result<MemoBytes, String> {
+    let (_, bytes) = memo_bytes(memo_str);
+    if bytes.len() > MAX_MEMO_SIZE {
+        return Err(format!(
+            "The memo is {} bytes, which is {} more than the limit of {} bytes",
+            bytes.len(),
+            bytes.len() - MAX_MEMO_SIZE,
+            MAX_MEMO_SIZE
+        ));
+    }
+
+    match Memo::from_bytes(&bytes) {
+        Ok(Memo::Future(_)) => Err(format!(
+            "The memo starts with 0x{:02x}, which ZIP-302 reserves for future use",
+            bytes[0]
+        )),
+        Ok(_) => MemoBytes::from_bytes(&bytes).map_err(|e| format!("Invalid memo: {:?}", e)),
+        Err(_) => Err("The memo starts like a text memo, but isn't valid UTF-8".to_string()),
+    }
+}
+
+// Check a memo for a payment to `address`. Only shielded addresses can get a memo.
+pub fn check_memo<P: consensus::Parameters>(params: &P, address: &str, memo_str: &str) -> Result<MemoBytes, String> {
+    match RecipientAddress::decode(params, address) {
+        Some(RecipientAddress::Shielded(_)) => {}
+        Some(RecipientAddress::Unified(ua)) if ua.sapling().is_some() => {}
+        Some(_) => {
+            return Err(format!(
+                "Can't send a memo to {}, which isn't a shielded address",
+                address
+            ))
+        }
+        None => return Err(format!("Invalid recipient address: '{}'", address)),
+    }
+
+    parse_memo(memo_str)
+}
+
+// Everything the UI needs to show about a memo while it is being typed: how it is encoded, the number of bytes it
+// takes up and how many are left, and whether it can be sent (to `address`, if there is one).
+pub fn memo_report<P: consensus::Parameters>(params: &P, address: Option<&str>, memo_str: &str) -> JsonValue {
+    let (encoding, bytes) = memo_bytes(memo_str);
+
+    let result = match address {
+        Some(address) => check_memo(params, address, memo_str),
+        None => parse_memo(memo_str),
+    };
+    let memo_type = match result.as_ref().map(Memo::try_from) {
+        Ok(Ok(Memo::Empty)) => "empty",
+        Ok(Ok(Memo::Arbitrary(_))) => "arbitrary",
+        _ => "text",
+    };
+
+    let mut j = object! {
+        "valid"     => result.is_ok(),
+        "encoding"  => if encoding == MemoEncoding::Hex { "hex" } else { "text" },
+        "type"      => memo_type,
+        "bytes"     => bytes.len(),
+        "max_bytes" => MAX_MEMO_SIZE,
+        "remaining" => MAX_MEMO_SIZE as i64 - bytes.len() as i64,
+    };
+    if let Err(e) = result {
+        j["error"] = e.into();
+    }
+
+    j
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{check_memo, memo_report, parse_memo, MAX_MEMO_SIZE};
+    use crate::bitcoinz_params::BITCOINZ_MAINNET;
+    use crate::lightclient::tests::{EXT_TADDR, EXT_ZADDR};
+
+    #[test]
+    fn memo_checks() {
+        // Text is counted in UTF-8 bytes
+        assert!(parse_memo(&"é".repeat(MAX_MEMO_SIZE / 2)).is_ok());
+        let e = parse_memo(&"é".repeat(MAX_MEMO_SIZE / 2 + 1)).unwrap_err();
+        assert!(e.contains("514 bytes"), "{}", e);
+
+        // Hex memos have to be valid ZIP-302 memos
+        assert!(parse_memo("0x48656c6c6f").is_ok());
+        assert!(parse_memo(&format!("0xff{}", "00".repeat(511))).is_ok());
+        assert!(parse_memo("0xf6").is_ok());
+        assert!(parse_memo("0xc328").is_err());
+        assert!(parse_memo("0xf7").is_err());
+        assert!(parse_memo(&format!("0x{}", "41".repeat(MAX_MEMO_SIZE + 1))).is_err());
+
+        // Not valid hex, so it is just text
+        assert!(parse_memo("0xnothex").is_ok());
+
+        assert!(check_memo(&BITCOINZ_MAINNET, EXT_ZADDR, "Hi").is_ok());
+        assert!(check_memo(&BITCOINZ_MAINNET, EXT_TADDR, "Hi").is_err());
+        assert!(check_memo(&BITCOINZ_MAINNET, "zs1notanaddress", "Hi").is_err());
+    }
+
+    #[test]
+    fn memo_reports() {
+        let r = memo_report(&BITCOINZ_MAINNET, Some(EXT_ZADDR), "Héllo");
+        assert_eq!(r["valid"].as_bool().unwrap(), true);
+        assert_eq!(r["encoding"], "text");
+        assert_eq!(r["type"], "text");
+        assert_eq!(r["bytes"].as_usize().unwrap(), 6);
+        assert_eq!(r["remaining"].as_i64().unwrap(), 506);
+
+        let r = memo_report(&BITCOINZ_MAINNET, None, &format!("0xff{}", "01".repeat(10)));
+        assert_eq!(r["valid"].as_bool().unwrap(), true);
+        assert_eq!(r["encoding"], "hex");
+        assert_eq!(r["type"], "arbitrary");
+        assert_eq!(r["bytes"].as_usize().unwrap(), 11);
+
+        let r = memo_report(&BITCOINZ_MAINNET, Some(EXT_TADDR), "Hi");
+        assert_eq!(r["valid"].as_bool().unwrap(), false);
+        assert!(r["error"].as_str().unwrap().contains(EXT_TADDR));
+
+        let r = memo_report(&BITCOINZ_MAINNET, None, &"a".repeat(600));
+        assert_eq!(r["valid"].as_bool().unwrap(), false);
+        assert_eq!(r["remaining"].as_i64().unwrap(), -88);
+    }
+}
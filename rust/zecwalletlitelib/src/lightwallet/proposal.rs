@@ -0,0 +1,159 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Read, Write};
+use zcash_encoding::{Optional, Vector};
+use zcash_primitives::{
+    merkle_tree::IncrementalWitness,
+    sapling::{Diversifier, Node, Note},
+    transaction::TxId,
+    zip32::ExtendedFullViewingKey,
+};
+
+use super::data::{read_rseed, write_rseed, Utxo};
+use super::utils::{read_string, write_string};
+
+// A sapling note spent by an unsigned proposal. It has everything needed to create the spend, except for the
+// spending key, which only the offline wallet has.
+pub struct ProposalSaplingSpend {
+    pub txid: TxId,
+    pub extfvk: ExtendedFullViewingKey,
+    pub diversifier: Diversifier,
+    pub note: Note,
+    pub witness: IncrementalWitness<Node>,
+}
+
+impl ProposalSaplingSpend {
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut txid_bytes = [0u8; 32];
+        reader.read_exact(&mut txid_bytes)?;
+        let txid = TxId::from_bytes(txid_bytes);
+
+        let extfvk = ExtendedFullViewingKey::read(&mut reader)?;
+
+        let mut diversifier_bytes = [0u8; 11];
+        reader.read_exact(&mut diversifier_bytes)?;
+        let diversifier = Diversifier { 0: diversifier_bytes };
+
+        let value = reader.read_u64::<LittleEndian>()?;
+        let rseed = read_rseed(&mut reader)?;
+
+        let note = extfvk
+            .fvk
+            .vk
+            .to_payment_address(diversifier)
+            .and_then(|pa| pa.create_note(value, rseed))
+            .ok_or(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Couldn't create the note for the address",
+            ))?;
+
+        let witness = IncrementalWitness::<Node>::read(&mut reader)?;
+
+        Ok(Self {
+            txid,
+            extfvk,
+            diversifier,
+            note,
+            witness,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.txid.as_ref())?;
+        self.extfvk.write(&mut writer)?;
+        writer.write_all(&self.diversifier.0)?;
+        writer.write_u64::<LittleEndian>(self.note.value)?;
+        write_rseed(&mut writer, &self.note.rseed)?;
+        self.witness.write(&mut writer)
+    }
+}
+
+pub struct ProposalOutput {
+    pub address: String,
+    pub value: u64,
+    pub memo: Option<String>,
+}
+
+impl ProposalOutput {
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let address = read_string(&mut reader)?;
+        let value = reader.read_u64::<LittleEndian>()?;
+        let memo = Optional::read(&mut reader, |r| read_string(r))?;
+
+        Ok(Self { address, value, memo })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_string(&mut writer, &self.address)?;
+        writer.write_u64::<LittleEndian>(self.value)?;
+        Optional::write(&mut writer, self.memo.as_ref(), |w, m| write_string(w, m))
+    }
+}
+
+// A transaction that has been put together by a wallet that can't spend (i.e., a watch-only wallet), but is not yet
+// proven or signed. The wallet that has the spending keys turns it into a transaction that can be broadcast.
+pub struct UnsignedProposal {
+    pub target_height: u32,
+    pub sapling_spends: Vec<ProposalSaplingSpend>,
+    pub utxos: Vec<Utxo>,
+    pub outputs: Vec<ProposalOutput>,
+    pub change_address: String,
+    pub fee: u64,
+}
+
+impl UnsignedProposal {
+    pub fn serialized_version() -> u64 {
+        return 1;
+    }
+
+    pub fn total_input_value(&self) -> u64 {
+        self.sapling_spends.iter().map(|s| s.note.value).sum::<u64>() + self.utxos.iter().map(|u| u.value).sum::<u64>()
+    }
+
+    pub fn total_output_value(&self) -> u64 {
+        self.outputs.iter().map(|o| o.value).sum::<u64>()
+    }
+
+    // What goes back to the change address, or None if the inputs don't cover the outputs and the fee
+    pub fn change_value(&self) -> Option<u64> {
+        self.total_input_value()
+            .checked_sub(self.total_output_value())
+            .and_then(|v| v.checked_sub(self.fee))
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let version = reader.read_u64::<LittleEndian>()?;
+        if version > Self::serialized_version() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Can't read proposal version {}, newest supported is {}", version, Self::serialized_version()),
+            ));
+        }
+
+        let target_height = reader.read_u32::<LittleEndian>()?;
+        let sapling_spends = Vector::read(&mut reader, |r| ProposalSaplingSpend::read(r))?;
+        let utxos = Vector::read(&mut reader, |r| Utxo::read(r))?;
+        let outputs = Vector::read(&mut reader, |r| ProposalOutput::read(r))?;
+        let change_address = read_string(&mut reader)?;
+        let fee = reader.read_u64::<LittleEndian>()?;
+
+        Ok(Self {
+            target_height,
+            sapling_spends,
+            utxos,
+            outputs,
+            change_address,
+            fee,
+        })
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u64::<LittleEndian>(Self::serialized_version())?;
+
+        writer.write_u32::<LittleEndian>(self.target_height)?;
+        Vector::write(&mut writer, &self.sapling_spends, |w, s| s.write(w))?;
+        Vector::write(&mut writer, &self.utxos, |w, u| u.write(w))?;
+        Vector::write(&mut writer, &self.outputs, |w, o| o.write(w))?;
+        write_string(&mut writer, &self.change_address)?;
+        writer.write_u64::<LittleEndian>(self.fee)
+    }
+}
@@ -0,0 +1,240 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Read, Write};
+use zcash_encoding::{Optional, Vector};
+
+use super::utils::{read_string, write_string};
+
+// When a scheduled payment is next due: at a block height, or at a unix time (in seconds)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DueAt {
+    Height(u32),
+    Time(u64),
+}
+
+impl DueAt {
+    pub fn is_due(&self, height: u32, now: u64) -> bool {
+        match self {
+            DueAt::Height(h) => height >= *h,
+            DueAt::Time(t) => now >= *t,
+        }
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        match reader.read_u8()? {
+            0 => Ok(DueAt::Height(reader.read_u32::<LittleEndian>()?)),
+            1 => Ok(DueAt::Time(reader.read_u64::<LittleEndian>()?)),
+            t => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown due type {}", t),
+            )),
+        }
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        match self {
+            DueAt::Height(h) => {
+                writer.write_u8(0)?;
+                writer.write_u32::<LittleEndian>(*h)
+            }
+            DueAt::Time(t) => {
+                writer.write_u8(1)?;
+                writer.write_u64::<LittleEndian>(*t)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledRecipient {
+    pub address: String,
+    pub value: u64,
+    pub memo: Option<String>,
+}
+
+impl ScheduledRecipient {
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let address = read_string(&mut reader)?;
+        let value = reader.read_u64::<LittleEndian>()?;
+        let memo = Optional::read(&mut reader, |r| read_string(r))?;
+
+        Ok(Self { address, value, memo })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_string(&mut writer, &self.address)?;
+        writer.write_u64::<LittleEndian>(self.value)?;
+        Optional::write(&mut writer, self.memo.as_ref(), |w, m| write_string(w, m))
+    }
+}
+
+// A Tx that paid a scheduled payment, and the due date it paid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledPaymentRecord {
+    pub due: DueAt,
+    pub txid: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledPayment {
+    pub id: u64,
+    pub recipients: Vec<ScheduledRecipient>,
+    pub next_due: DueAt,
+
+    // How often the payment repeats, in blocks if it is due at a height or in seconds if it is due at a time.
+    // 0 means it is only paid once.
+    pub interval: u64,
+
+    // Set once a one-time payment has been paid
+    pub finished: bool,
+
+    pub history: Vec<ScheduledPaymentRecord>,
+}
+
+impl ScheduledPayment {
+    pub fn total_value(&self) -> u64 {
+        self.recipients.iter().map(|r| r.value).sum()
+    }
+
+    pub fn is_due(&self, height: u32, now: u64) -> bool {
+        !self.finished && self.next_due.is_due(height, now)
+    }
+
+    // Record the Tx that paid the current due date, and move on to the next one. If several periods were missed,
+    // the payment is only made once, and the next due date is the first one after `height`/`now`.
+    pub fn record_payment(&mut self, txid: String, height: u32, now: u64) {
+        self.history.push(ScheduledPaymentRecord {
+            due: self.next_due,
+            txid,
+        });
+
+        if self.interval == 0 {
+            self.finished = true;
+            return;
+        }
+
+        self.next_due = match self.next_due {
+            DueAt::Height(h) => {
+                let periods = (height.saturating_sub(h) as u64) / self.interval + 1;
+                DueAt::Height((h as u64 + periods * self.interval).min(u32::MAX as u64) as u32)
+            }
+            DueAt::Time(t) => {
+                let periods = now.saturating_sub(t) / self.interval + 1;
+                DueAt::Time(t.saturating_add(periods.saturating_mul(self.interval)))
+            }
+        };
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let id = reader.read_u64::<LittleEndian>()?;
+        let recipients = Vector::read(&mut reader, |r| ScheduledRecipient::read(r))?;
+        let next_due = DueAt::read(&mut reader)?;
+        let interval = reader.read_u64::<LittleEndian>()?;
+        let finished = reader.read_u8()? > 0;
+        let history = Vector::read(&mut reader, |r| {
+            let due = DueAt::read(&mut *r)?;
+            let txid = read_string(r)?;
+            Ok(ScheduledPaymentRecord { due, txid })
+        })?;
+
+        Ok(Self {
+            id,
+            recipients,
+            next_due,
+            interval,
+            finished,
+            history,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.id)?;
+        Vector::write(&mut writer, &self.recipients, |w, r| r.write(w))?;
+        self.next_due.write(&mut writer)?;
+        writer.write_u64::<LittleEndian>(self.interval)?;
+        writer.write_u8(if self.finished { 1 } else { 0 })?;
+        Vector::write(&mut writer, &self.history, |w, h| {
+            h.due.write(&mut *w)?;
+            write_string(w, &h.txid)
+        })
+    }
+}
+
+// All the payments scheduled in this wallet
+pub struct ScheduledPayments {
+    next_id: u64,
+    pub payments: Vec<ScheduledPayment>,
+}
+
+impl ScheduledPayments {
+    pub fn serialized_version() -> u64 {
+        return 1;
+    }
+
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            payments: vec![],
+        }
+    }
+
+    pub fn add(&mut self, recipients: Vec<ScheduledRecipient>, next_due: DueAt, interval: u64) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.payments.push(ScheduledPayment {
+            id,
+            recipients,
+            next_due,
+            interval,
+            finished: false,
+            history: vec![],
+        });
+
+        id
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        let len = self.payments.len();
+        self.payments.retain(|p| p.id != id);
+
+        self.payments.len() != len
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut ScheduledPayment> {
+        self.payments.iter_mut().find(|p| p.id == id)
+    }
+
+    pub fn due(&self, height: u32, now: u64) -> Vec<ScheduledPayment> {
+        self.payments
+            .iter()
+            .filter(|p| p.is_due(height, now))
+            .cloned()
+            .collect()
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let version = reader.read_u64::<LittleEndian>()?;
+        if version > Self::serialized_version() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Can't read scheduled payments version {}, newest supported is {}",
+                    version,
+                    Self::serialized_version()
+                ),
+            ));
+        }
+
+        let next_id = reader.read_u64::<LittleEndian>()?;
+        let payments = Vector::read(&mut reader, |r| ScheduledPayment::read(r))?;
+
+        Ok(Self { next_id, payments })
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u64::<LittleEndian>(Self::serialized_version())?;
+
+        writer.write_u64::<LittleEndian>(self.next_id)?;
+        Vector::write(&mut writer, &self.payments, |w, p| p.write(w))
+    }
+}
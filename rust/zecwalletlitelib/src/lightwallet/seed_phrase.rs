@@ -0,0 +1,272 @@
+use bip39::{Language, Mnemonic};
+use json::{object, JsonValue};
+
+// Wallets are created with, and can only be restored from, 24 word seed phrases
+pub const SEED_PHRASE_WORDS: usize = 24;
+
+// How many completions and closest matches to suggest for a word
+pub const MAX_SUGGESTIONS: usize = 5;
+
+// Words further than this from every word in the list are too mistyped to guess
+const MAX_TYPO_DISTANCE: usize = 2;
+
+// Why a seed phrase can't be restored. The first problem found is reported, in this order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedPhraseError {
+    // The word at this position (from 0) isn't in the BIP39 word list
+    InvalidWord(usize),
+    // All the words are valid, but there are this many of them
+    WrongLength(usize),
+    // The right number of valid words, but the last word doesn't match the checksum of the others
+    Checksum,
+}
+
+impl SeedPhraseError {
+    fn kind(&self) -> &'static str {
+        match self {
+            SeedPhraseError::InvalidWord(_) => "invalid_word",
+            SeedPhraseError::WrongLength(_) => "wrong_length",
+            SeedPhraseError::Checksum => "checksum",
+        }
+    }
+
+    fn message(&self, words: &[&str]) -> String {
+        match self {
+            SeedPhraseError::InvalidWord(i) => format!("Word {} ('{}') isn't a seed phrase word", i + 1, words[*i]),
+            SeedPhraseError::WrongLength(0) => "The seed phrase is empty".to_string(),
+            SeedPhraseError::WrongLength(n) => format!(
+                "The seed phrase has {} words, but it should have {}",
+                n, SEED_PHRASE_WORDS
+            ),
+            SeedPhraseError::Checksum => {
+                "The seed phrase's checksum doesn't match. One of the words is wrong, or they are out of order"
+                    .to_string()
+            }
+        }
+    }
+}
+
+// The English BIP39 word list, which is sorted
+fn wordlist() -> &'static [&'static str] {
+    Language::English.wordlist().get_words_by_prefix("")
+}
+
+pub fn is_seed_word(word: &str) -> bool {
+    wordlist().binary_search(&word).is_ok()
+}
+
+// The words that start with `prefix`, for completing a word as it is typed. Every word is uniquely identified by
+// its first 4 letters, so this is at most one word once 4 letters have been typed.
+pub fn word_completions(prefix: &str, max: usize) -> Vec<&'static str> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return vec![];
+    }
+
+    Language::English
+        .wordlist()
+        .get_words_by_prefix(&prefix)
+        .iter()
+        .take(max)
+        .cloned()
+        .collect()
+}
+
+// The words closest to a mistyped `word`, by the number of letters that have to be added, removed, changed or
+// swapped with their neighbour. Closest first, and alphabetical among words that are as close.
+pub fn closest_words(word: &str, max: usize) -> Vec<&'static str> {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() {
+        return vec![];
+    }
+
+    let mut matches = wordlist()
+        .iter()
+        .map(|w| (edit_distance(&word, w), *w))
+        .filter(|(d, _)| *d <= MAX_TYPO_DISTANCE)
+        .collect::<Vec<_>>();
+    matches.sort();
+
+    matches.into_iter().take(max).map(|(_, w)| w).collect()
+}
+
+// Optimal string alignment distance: the Levenshtein distance, where swapping two neighbouring letters is also
+// a single edit, since that is a common typo
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+// Check a seed phrase before restoring from it. The words have to be exactly as they are in the word list, since
+// that is what restoring needs, though the suggestions for a word that isn't are case insensitive.
+pub fn check_seed_phrase(phrase: &str) -> Result<(), SeedPhraseError> {
+    let words = phrase.split_whitespace().collect::<Vec<_>>();
+
+    if let Some(i) = words.iter().position(|w| !is_seed_word(w)) {
+        return Err(SeedPhraseError::InvalidWord(i));
+    }
+    if words.len() != SEED_PHRASE_WORDS {
+        return Err(SeedPhraseError::WrongLength(words.len()));
+    }
+    if Mnemonic::from_phrase(&words.join(" "), Language::English).is_err() {
+        return Err(SeedPhraseError::Checksum);
+    }
+
+    Ok(())
+}
+
+// The error to show for a seed phrase that can't be restored
+pub fn seed_phrase_error(phrase: &str) -> Option<String> {
+    let words = phrase.split_whitespace().collect::<Vec<_>>();
+    check_seed_phrase(phrase).err().map(|e| e.message(&words))
+}
+
+// Suggestions for a single word, as it is typed or after a typo
+pub fn word_suggestions(word: &str) -> JsonValue {
+    object! {
+        "word"        => word,
+        "valid"       => is_seed_word(word),
+        "completions" => word_completions(word, MAX_SUGGESTIONS),
+        "closest"     => closest_words(word, MAX_SUGGESTIONS),
+    }
+}
+
+// Everything the UI needs to show about a seed phrase as it is entered, or before it is restored: every word that
+// isn't in the word list, with its position (from 1) and suggestions, and whether the phrase is complete and its
+// checksum matches. A partial phrase is reported as "wrong_length" once all its words are valid.
+pub fn seed_phrase_report(phrase: &str) -> JsonValue {
+    let words = phrase.split_whitespace().collect::<Vec<_>>();
+    let result = check_seed_phrase(phrase);
+
+    let invalid_words = words
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| !is_seed_word(w))
+        .map(|(i, w)| {
+            object! {
+                "position"    => i + 1,
+                "word"        => *w,
+                "completions" => word_completions(w, MAX_SUGGESTIONS),
+                "closest"     => closest_words(w, MAX_SUGGESTIONS),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut j = object! {
+        "valid"          => result.is_ok(),
+        "word_count"     => words.len(),
+        "expected_words" => SEED_PHRASE_WORDS,
+        "invalid_words"  => invalid_words,
+    };
+    if let Err(e) = result {
+        j["error"] = e.kind().into();
+        j["message"] = e.message(&words).into();
+        if let SeedPhraseError::InvalidWord(i) = e {
+            j["position"] = (i + 1).into();
+        }
+    }
+
+    j
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{
+        check_seed_phrase, closest_words, edit_distance, seed_phrase_report, word_completions, SeedPhraseError,
+    };
+    use crate::lightclient::tests::TEST_SEED;
+
+    #[test]
+    fn word_lookups() {
+        assert_eq!(word_completions("aba", 5), vec!["abandon"]);
+        assert_eq!(word_completions("zo", 5), vec!["zone", "zoo"]);
+        assert_eq!(word_completions("Chim", 5), vec!["chimney"]);
+        assert_eq!(word_completions("ab", 3).len(), 3);
+        assert!(word_completions("xq", 5).is_empty());
+        assert!(word_completions("", 5).is_empty());
+
+        assert_eq!(edit_distance("chimeny", "chimney"), 1);
+        assert_eq!(edit_distance("chimny", "chimney"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        assert!(closest_words("chimeny", 5).contains(&"chimney"));
+        assert!(closest_words("girafe", 5).contains(&"giraffe"));
+        assert_eq!(closest_words("Letter", 5)[0], "letter");
+        assert!(closest_words("qqqqqqqq", 5).is_empty());
+    }
+
+    #[test]
+    fn seed_phrase_checks() {
+        assert_eq!(check_seed_phrase(TEST_SEED), Ok(()));
+        assert_eq!(check_seed_phrase(&format!("  {}\n", TEST_SEED)), Ok(()));
+
+        // The first invalid word is reported, even in a partial phrase
+        assert_eq!(
+            check_seed_phrase("chimney better bulb horor rebuild whisper"),
+            Err(SeedPhraseError::InvalidWord(3))
+        );
+        assert_eq!(
+            check_seed_phrase("chimney better bulb horror rebuild whisper"),
+            Err(SeedPhraseError::WrongLength(6))
+        );
+        assert_eq!(check_seed_phrase(""), Err(SeedPhraseError::WrongLength(0)));
+
+        // Swapping two words breaks the checksum
+        let mut words = TEST_SEED.split(' ').collect::<Vec<_>>();
+        words.swap(0, 1);
+        assert_eq!(check_seed_phrase(&words.join(" ")), Err(SeedPhraseError::Checksum));
+
+        // 12 word phrases are valid BIP39, but aren't the length of a wallet's seed phrase
+        let twelve = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(check_seed_phrase(twelve), Err(SeedPhraseError::WrongLength(12)));
+    }
+
+    #[test]
+    fn seed_phrase_reports() {
+        let r = seed_phrase_report(TEST_SEED);
+        assert_eq!(r["valid"].as_bool().unwrap(), true);
+        assert_eq!(r["word_count"].as_usize().unwrap(), 24);
+        assert!(r["error"].is_null());
+
+        let r = seed_phrase_report("chimney beter bulb Horror reb");
+        assert_eq!(r["valid"].as_bool().unwrap(), false);
+        assert_eq!(r["error"], "invalid_word");
+        assert_eq!(r["position"].as_usize().unwrap(), 2);
+        assert_eq!(r["invalid_words"].len(), 3);
+        assert_eq!(r["invalid_words"][0]["word"], "beter");
+        assert!(r["invalid_words"][0]["closest"].members().any(|w| w == "better"));
+        assert_eq!(r["invalid_words"][1]["position"].as_usize().unwrap(), 4);
+        assert_eq!(r["invalid_words"][1]["completions"][0], "horror");
+        assert_eq!(r["invalid_words"][2]["completions"][0], "rebel");
+
+        let r = seed_phrase_report("chimney better bulb");
+        assert_eq!(r["error"], "wrong_length");
+        assert!(r["message"].as_str().unwrap().contains("has 3 words"));
+
+        let mut words = TEST_SEED.split(' ').collect::<Vec<_>>();
+        words.swap(0, 1);
+        let r = seed_phrase_report(&words.join(" "));
+        assert_eq!(r["error"], "checksum");
+        assert_eq!(r["invalid_words"].len(), 0);
+    }
+}
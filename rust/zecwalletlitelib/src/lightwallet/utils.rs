@@ -21,16 +21,5 @@ pub fn write_string<W: Write>(mut writer: W, s: &String) -> io::Result<()> {
 
 // Interpret a string or hex-encoded memo, and return a Memo object
 pub fn interpret_memo_string(memo_str: String) -> Result<MemoBytes, String> {
-    // If the string starts with an "0x", and contains only hex chars ([a-f0-9]+) then
-    // interpret it as a hex
-    let s_bytes = if memo_str.to_lowercase().starts_with("0x") {
-        match hex::decode(&memo_str[2..memo_str.len()]) {
-            Ok(data) => data,
-            Err(_) => Vec::from(memo_str.as_bytes()),
-        }
-    } else {
-        Vec::from(memo_str.as_bytes())
-    };
-
-    MemoBytes::from_bytes(&s_bytes).map_err(|_| format!("Error creating output. Memo '{:?}' is too long", memo_str))
+    super::memo::parse_memo(&memo_str)
 }
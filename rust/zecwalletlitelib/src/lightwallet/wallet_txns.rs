@@ -218,11 +218,7 @@ impl WalletTxns {
             .flat_map(|(txid, wtx)| {
                 // Fetch notes that are before the before_block.
                 wtx.s_notes.iter().filter_map(move |snd| {
-                    if wtx.block <= before_block
-                        && snd.have_spending_key
-                        && snd.witnesses.len() > 0
-                        && snd.spent.is_none()
-                    {
+                    if wtx.block <= before_block && snd.witnesses.len() > 0 && snd.spent.is_none() {
                         Some((txid.clone(), snd.nullifier.clone()))
                     } else {
                         None
@@ -327,6 +323,54 @@ impl WalletTxns {
         self.remove_txids(txids_to_remove);
     }
 
+    // Remember the raw bytes and the expiry height of a Tx we just broadcast, so it can be rebroadcast
+    // until it is mined, or dropped when it expires
+    pub(crate) fn set_pending_broadcast(&mut self, txid: &TxId, raw_tx: Vec<u8>, expiry_height: u32) {
+        if let Some(wtx) = self.current.get_mut(txid) {
+            if wtx.unconfirmed {
+                wtx.raw_tx = Some(raw_tx);
+                wtx.expiry_height = expiry_height;
+            }
+        }
+    }
+
+    // All the Txs we sent that haven't been mined yet, along with their raw bytes and expiry height
+    pub(crate) fn get_pending_broadcasts(&self) -> Vec<(TxId, Vec<u8>, u32)> {
+        self.current
+            .values()
+            .filter(|wtx| wtx.unconfirmed)
+            .filter_map(|wtx| {
+                wtx.raw_tx
+                    .as_ref()
+                    .map(|raw_tx| (wtx.txid.clone(), raw_tx.clone(), wtx.expiry_height))
+            })
+            .collect()
+    }
+
+    // Remove the Txs we sent that can't be mined any more because the chain is past their expiry height.
+    // The notes and utxos they spent become spendable again. Returns the txids that were removed.
+    pub(crate) fn remove_expired_sends(&mut self, latest_height: u64) -> Vec<TxId> {
+        let txids_to_remove = self
+            .current
+            .values()
+            .filter(|wtx| {
+                wtx.unconfirmed
+                    && wtx.raw_tx.is_some()
+                    && wtx.expiry_height > 0
+                    && latest_height >= wtx.expiry_height as u64
+            })
+            .map(|wtx| wtx.txid.clone())
+            .collect::<Vec<_>>();
+
+        txids_to_remove
+            .iter()
+            .for_each(|t| info!("Removing expired sent tx {}", t));
+
+        self.remove_txids(txids_to_remove.clone());
+
+        txids_to_remove
+    }
+
     // Will mark the nullifier of the given txid as spent. Returns the amount of the nullifier
     pub fn mark_txid_o_nf_spent(
         &mut self,
@@ -408,6 +452,11 @@ impl WalletTxns {
             wtx.unconfirmed = unconfirmed;
             wtx.block = height;
             wtx.datetime = datetime;
+
+            // Once mined, there is no need to rebroadcast it any more
+            if !unconfirmed {
+                wtx.raw_tx = None;
+            }
         }
 
         wtx
@@ -581,6 +630,7 @@ impl WalletTxns {
                 spent_at_height: None,
                 spent: None,
                 unconfirmed_spent: None,
+                frozen: false,
             });
         }
     }
@@ -614,6 +664,7 @@ impl WalletTxns {
                     memo: None,
                     is_change,
                     have_spending_key: false,
+                    frozen: false,
                 };
 
                 wtx.s_notes.push(nd);
@@ -680,6 +731,7 @@ impl WalletTxns {
         to: PaymentAddress,
         extfvk: &ExtendedFullViewingKey,
         have_spending_key: bool,
+        watch_only: bool,
         witness: IncrementalWitness<Node>,
     ) {
         // Check if this is a change note
@@ -689,8 +741,10 @@ impl WalletTxns {
         // Update the block height, in case this was a mempool or unconfirmed tx.
         wtx.block = height;
 
+        // Witnesses are only kept for notes we can spend, or in a watch-only wallet, which puts together unsigned
+        // proposals that spend its notes. Notes of viewing keys imported into a regular wallet don't need them.
         let nullifier = note.nf(&extfvk.fvk.vk.nk, witness.position() as u64);
-        let witnesses = if have_spending_key {
+        let witnesses = if have_spending_key || watch_only {
             WitnessCache::new(vec![witness], u64::from(height))
         } else {
             WitnessCache::empty()
@@ -709,6 +763,7 @@ impl WalletTxns {
                     memo: None,
                     is_change,
                     have_spending_key,
+                    frozen: false,
                 };
 
                 wtx.s_notes.push(nd);
@@ -0,0 +1,54 @@
+use std::io::{self, Read, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use super::utils;
+
+// The account every wallet starts with. Keys that were added before there were accounts, imported keys and
+// the addresses found when restoring from the seed all belong to it.
+pub const DEFAULT_ACCOUNT: u32 = 0;
+
+// The default account's z-keys were derived one per z-address, at the ZIP-32 account indexes from 0 up, so the
+// other accounts' z-keys are derived clear of them, at account' ACCOUNT_ZIP32_BASE + the account's id.
+const ACCOUNT_ZIP32_BASE: u32 = 1 << 30;
+
+// The ZIP-32 account index of the z-key of an account other than the default one. Every such account has just
+// this one z-key, and its other z-addresses are diversified addresses of it.
+pub fn account_zip32_index(account: u32) -> u32 {
+    ACCOUNT_ZIP32_BASE + account
+}
+
+// An account groups some of the wallet's keys, so that they have their own balance and history, and sends from
+// the account only spend the funds received by its keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletAccount {
+    pub id: u32,
+    pub name: String,
+}
+
+impl WalletAccount {
+    pub fn new(id: u32, name: String) -> Self {
+        WalletAccount { id, name }
+    }
+
+    fn serialized_version() -> u8 {
+        return 1;
+    }
+
+    pub fn read<R: Read>(mut inp: R) -> io::Result<Self> {
+        let version = inp.read_u8()?;
+        assert!(version <= Self::serialized_version());
+
+        let id = inp.read_u32::<LittleEndian>()?;
+        let name = utils::read_string(&mut inp)?;
+
+        Ok(WalletAccount { id, name })
+    }
+
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_u8(Self::serialized_version())?;
+
+        out.write_u32::<LittleEndian>(self.id)?;
+        utils::write_string(&mut out, &self.name)
+    }
+}
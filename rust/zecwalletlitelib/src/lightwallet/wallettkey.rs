@@ -5,8 +5,9 @@ use ripemd160::Digest;
 use secp256k1::SecretKey;
 use sha2::Sha256;
 use sodiumoxide::crypto::secretbox;
+use zcash_client_backend::address::RecipientAddress;
 use zcash_encoding::{Optional, Vector};
-use zcash_primitives::consensus;
+use zcash_primitives::{consensus, legacy::TransparentAddress};
 
 use crate::{
     lightclient::lightclient_config::LightClientConfig,
@@ -16,12 +17,15 @@ use crate::{
 use super::{
     keys::{FromBase58Check, ToBase58Check},
     utils,
+    walletaccount::DEFAULT_ACCOUNT,
 };
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum WalletTKeyType {
     HdKey = 0,
     ImportedKey = 1,
+    // Only the address, so funds received at it can be watched but not spent
+    WatchOnly = 2,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +38,9 @@ pub struct WalletTKey {
     // If this is a HD key, what is the key number
     pub(super) hdkey_num: Option<u32>,
 
+    // The account this key belongs to. HD keys are derived along the BIP44 path of their account.
+    pub(super) account: u32,
+
     // If locked, the encrypted private key is stored here
     enc_key: Option<Vec<u8>>,
     nonce: Option<Vec<u8>>,
@@ -43,6 +50,7 @@ impl WalletTKey {
     pub fn get_taddr_from_bip39seed<P: consensus::Parameters>(
         config: &LightClientConfig<P>,
         bip39_seed: &[u8],
+        account: u32,
         pos: u32,
     ) -> secp256k1::SecretKey {
         assert_eq!(bip39_seed.len(), 64);
@@ -53,7 +61,7 @@ impl WalletTKey {
             .unwrap()
             .derive_private_key(KeyIndex::hardened_from_normalize_index(config.get_coin_type()).unwrap())
             .unwrap()
-            .derive_private_key(KeyIndex::hardened_from_normalize_index(0).unwrap())
+            .derive_private_key(KeyIndex::hardened_from_normalize_index(account).unwrap())
             .unwrap()
             .derive_private_key(KeyIndex::Normal(0))
             .unwrap()
@@ -79,6 +87,7 @@ impl WalletTKey {
             key: Some(sk.clone()),
             address: taddr.clone(),
             hdkey_num: Some(num),
+            account: DEFAULT_ACCOUNT,
             locked: false,
             enc_key: None,
             nonce: None,
@@ -105,6 +114,7 @@ impl WalletTKey {
             key: Some(key),
             address,
             hdkey_num: None,
+            account: DEFAULT_ACCOUNT,
             locked: false,
             enc_key: None,
             nonce: None,
@@ -115,10 +125,19 @@ impl WalletTKey {
         config: &LightClientConfig<P>,
         hdkey_num: u32,
         bip39_seed: &[u8],
+    ) -> Self {
+        Self::new_hdkey_for_account(config, DEFAULT_ACCOUNT, hdkey_num, bip39_seed)
+    }
+
+    pub fn new_hdkey_for_account<P: consensus::Parameters>(
+        config: &LightClientConfig<P>,
+        account: u32,
+        hdkey_num: u32,
+        bip39_seed: &[u8],
     ) -> Self {
         let pos = hdkey_num;
 
-        let sk = Self::get_taddr_from_bip39seed(&config, bip39_seed, pos);
+        let sk = Self::get_taddr_from_bip39seed(&config, bip39_seed, account, pos);
         let address = Self::address_from_prefix_sk(&config.base58_pubkey_address(), &sk);
 
         WalletTKey {
@@ -126,6 +145,7 @@ impl WalletTKey {
             key: Some(sk),
             address,
             hdkey_num: Some(hdkey_num),
+            account,
             locked: false,
             enc_key: None,
             nonce: None,
@@ -141,6 +161,29 @@ impl WalletTKey {
         Ok(self.key.as_ref().unwrap()[..].to_base58check(&config.base58_secretkey_prefix(), &[0x01]))
     }
 
+    pub fn new_watch_only<P: consensus::Parameters>(config: &LightClientConfig<P>, address: &str) -> io::Result<Self> {
+        match RecipientAddress::decode(&config.get_params(), address) {
+            Some(RecipientAddress::Transparent(TransparentAddress::PublicKey(_))) => {}
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Not a transparent address: {}", address),
+                ))
+            }
+        }
+
+        Ok(WalletTKey {
+            keytype: WalletTKeyType::WatchOnly,
+            key: None,
+            address: address.to_string(),
+            hdkey_num: None,
+            account: DEFAULT_ACCOUNT,
+            locked: false,
+            enc_key: None,
+            nonce: None,
+        })
+    }
+
     #[cfg(test)]
     pub fn empty(ta: &String) -> Self {
         WalletTKey {
@@ -148,6 +191,7 @@ impl WalletTKey {
             key: None,
             address: ta.clone(),
             hdkey_num: None,
+            account: DEFAULT_ACCOUNT,
             locked: false,
             enc_key: None,
             nonce: None,
@@ -167,7 +211,7 @@ impl WalletTKey {
     }
 
     fn serialized_version() -> u8 {
-        return 1;
+        return 2;
     }
 
     pub fn read<R: Read>(mut inp: R) -> io::Result<Self> {
@@ -177,6 +221,7 @@ impl WalletTKey {
         let keytype: WalletTKeyType = match inp.read_u32::<LittleEndian>()? {
             0 => Ok(WalletTKeyType::HdKey),
             1 => Ok(WalletTKeyType::ImportedKey),
+            2 => Ok(WalletTKeyType::WatchOnly),
             n => Err(io::Error::new(
                 ErrorKind::InvalidInput,
                 format!("Unknown zkey type {}", n),
@@ -197,12 +242,19 @@ impl WalletTKey {
         let enc_key = Optional::read(&mut inp, |r| Vector::read(r, |r| r.read_u8()))?;
         let nonce = Optional::read(&mut inp, |r| Vector::read(r, |r| r.read_u8()))?;
 
+        let account = if version <= 1 {
+            DEFAULT_ACCOUNT
+        } else {
+            inp.read_u32::<LittleEndian>()?
+        };
+
         Ok(WalletTKey {
             keytype,
             locked,
             key,
             address,
             hdkey_num,
+            account,
             enc_key,
             nonce,
         })
@@ -228,7 +280,9 @@ impl WalletTKey {
         // Write nonce
         Optional::write(&mut out, self.nonce.as_ref(), |o, v| {
             Vector::write(o, &v[..], |o, n| o.write_u8(*n))
-        })
+        })?;
+
+        out.write_u32::<LittleEndian>(self.account)
     }
 
     pub fn lock(&mut self) -> io::Result<()> {
@@ -250,6 +304,9 @@ impl WalletTKey {
                 self.key = None;
                 self.locked = true;
             }
+            WalletTKeyType::WatchOnly => {
+                // There is no key to remove
+            }
         }
 
         Ok(())
@@ -263,7 +320,7 @@ impl WalletTKey {
     ) -> io::Result<()> {
         match self.keytype {
             WalletTKeyType::HdKey => {
-                let sk = Self::get_taddr_from_bip39seed(&config, &bip39_seed, self.hdkey_num.unwrap());
+                let sk = Self::get_taddr_from_bip39seed(&config, &bip39_seed, self.account, self.hdkey_num.unwrap());
                 let address = Self::address_from_prefix_sk(&config.base58_pubkey_address(), &sk);
 
                 if address != self.address {
@@ -297,6 +354,7 @@ impl WalletTKey {
                     .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
                 self.key = Some(key);
             }
+            WalletTKeyType::WatchOnly => {}
         };
 
         self.locked = false;
@@ -317,6 +375,7 @@ impl WalletTKey {
                 self.enc_key = Some(secretbox::seal(&sk_bytes, &nonce, &key));
                 self.nonce = Some(nonce.as_ref().to_vec());
             }
+            WalletTKeyType::WatchOnly => {}
         }
 
         // Also lock after encrypt
@@ -341,6 +400,7 @@ impl WalletTKey {
                 self.nonce = None;
                 Ok(())
             }
+            WalletTKeyType::WatchOnly => Ok(()),
         }
     }
 }
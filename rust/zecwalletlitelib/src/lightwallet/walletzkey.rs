@@ -9,12 +9,13 @@ use zcash_encoding::{Optional, Vector};
 use zcash_primitives::consensus;
 use zcash_primitives::{
     sapling::PaymentAddress,
-    zip32::{ExtendedFullViewingKey, ExtendedSpendingKey},
+    zip32::{DiversifierIndex, ExtendedFullViewingKey, ExtendedSpendingKey},
 };
 
 use crate::lightclient::lightclient_config::LightClientConfig;
 
 use super::keys::Keys;
+use super::walletaccount::DEFAULT_ACCOUNT;
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum WalletZKeyType {
@@ -35,6 +36,14 @@ pub struct WalletZKey {
     // If this is a HD key, what is the key number
     pub(super) hdkey_num: Option<u32>,
 
+    // The diversified addresses handed out for this key, in the order they were created, with their diversifier
+    // index. They all share the key's ivk, so they don't add to the cost of trial decryption.
+    pub(super) diversified: Vec<([u8; 11], PaymentAddress)>,
+
+    // The account this key belongs to. The key's own ZIP-32 account index is its hdkey_num, see
+    // `account_zip32_index`.
+    pub(super) account: u32,
+
     // If locked, the encrypted private key is stored here
     enc_key: Option<Vec<u8>>,
     nonce: Option<Vec<u8>>,
@@ -52,6 +61,8 @@ impl WalletZKey {
             extfvk,
             zaddress,
             hdkey_num: Some(hdkey_num),
+            diversified: vec![],
+            account: DEFAULT_ACCOUNT,
             enc_key: None,
             nonce: None,
         }
@@ -67,6 +78,8 @@ impl WalletZKey {
             extfvk,
             zaddress,
             hdkey_num: Some(hdkey_num),
+            diversified: vec![],
+            account: DEFAULT_ACCOUNT,
             enc_key: None,
             nonce: None,
         }
@@ -83,6 +96,8 @@ impl WalletZKey {
             extfvk,
             zaddress,
             hdkey_num: None,
+            diversified: vec![],
+            account: DEFAULT_ACCOUNT,
             enc_key: None,
             nonce: None,
         }
@@ -98,6 +113,8 @@ impl WalletZKey {
             extfvk,
             zaddress,
             hdkey_num: None,
+            diversified: vec![],
+            account: DEFAULT_ACCOUNT,
             enc_key: None,
             nonce: None,
         }
@@ -111,8 +128,55 @@ impl WalletZKey {
         &self.extfvk
     }
 
+    // The key's default address, followed by all of its diversified addresses
+    pub fn all_addresses(&self) -> Vec<&'_ PaymentAddress> {
+        std::iter::once(&self.zaddress)
+            .chain(self.diversified.iter().map(|(_, pa)| pa))
+            .collect()
+    }
+
+    // If `address` is an address of this key. This is true for every diversified address of the key, even the ones
+    // that weren't created by this wallet.
+    pub fn owns_address(&self, address: &PaymentAddress) -> bool {
+        self.extfvk.fvk.vk.to_payment_address(*address.diversifier()).as_ref() == Some(address)
+    }
+
+    // The next diversified address of this key, without keeping it. It only needs the viewing key, so this works
+    // for locked wallets and viewing keys too. Returns None if the key has run out of diversifiers.
+    pub fn next_diversified_address(&self) -> Option<([u8; 11], PaymentAddress)> {
+        let mut index = match self.diversified.last() {
+            Some((d, _)) => DiversifierIndex(*d),
+            None => self.extfvk.default_address().0,
+        };
+        index.increment().ok()?;
+
+        self.extfvk
+            .find_address(index)
+            .map(|(index, address)| (index.0, address))
+    }
+
+    // Create the next diversified address of this key
+    pub fn new_diversified_address(&mut self) -> Option<PaymentAddress> {
+        let (index, address) = self.next_diversified_address()?;
+        self.diversified.push((index, address.clone()));
+
+        Some(address)
+    }
+
+    // Keep `address`, if it is the next diversified address of this key. This is for an address that was handed
+    // out by `next_diversified_address`, once it has been used.
+    pub fn keep_diversified_address(&mut self, address: &PaymentAddress) -> bool {
+        match self.next_diversified_address() {
+            Some((index, next)) if &next == address => {
+                self.diversified.push((index, next));
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn serialized_version() -> u8 {
-        return 1;
+        return 3;
     }
 
     pub fn read<R: Read>(mut inp: R) -> io::Result<Self> {
@@ -140,6 +204,29 @@ impl WalletZKey {
         let enc_key = Optional::read(&mut inp, |r| Vector::read(r, |r| r.read_u8()))?;
         let nonce = Optional::read(&mut inp, |r| Vector::read(r, |r| r.read_u8()))?;
 
+        let diversified = if version <= 1 {
+            vec![]
+        } else {
+            Vector::read(&mut inp, |r| {
+                let mut index = [0u8; 11];
+                r.read_exact(&mut index)?;
+
+                match extfvk.find_address(DiversifierIndex(index)) {
+                    Some((d, pa)) if d.0 == index => Ok((index, pa)),
+                    _ => Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid diversifier index {}", hex::encode(index)),
+                    )),
+                }
+            })?
+        };
+
+        let account = if version <= 2 {
+            DEFAULT_ACCOUNT
+        } else {
+            inp.read_u32::<LittleEndian>()?
+        };
+
         Ok(WalletZKey {
             keytype,
             locked,
@@ -147,6 +234,8 @@ impl WalletZKey {
             extfvk,
             zaddress,
             hdkey_num,
+            diversified,
+            account,
             enc_key,
             nonce,
         })
@@ -175,7 +264,12 @@ impl WalletZKey {
         // Write nonce
         Optional::write(&mut out, self.nonce.as_ref(), |o, v| {
             Vector::write(o, &v[..], |o, n| o.write_u8(*n))
-        })
+        })?;
+
+        // Write the diversifier indexes of the diversified addresses. The addresses are derived again on read.
+        Vector::write(&mut out, &self.diversified, |o, (d, _)| o.write_all(d))?;
+
+        out.write_u32::<LittleEndian>(self.account)
     }
 
     pub fn lock(&mut self) -> io::Result<()> {
@@ -324,6 +418,8 @@ pub mod tests {
         decode_extended_full_viewing_key, decode_extended_spending_key, encode_payment_address,
     };
 
+    use zcash_primitives::zip32::ExtendedSpendingKey;
+
     use super::WalletZKey;
     use crate::lightclient::lightclient_config::{LightClientConfig, UnitTestNetwork};
 
@@ -369,6 +465,38 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_diversified_addresses() {
+        let config = get_config();
+
+        // Priv Key's address is "zs1va5902apnzlhdu0pw9r9q7ca8s4vnsrp2alr6xndt69jnepn2v2qrj9vg3wfcnjyks5pg65g9dc"
+        let viewkey = "zxviews1qvvx7cqdqyqqpqqte7292el2875kw2fgvnkmlmrufyszlcy8xgstwarnumqye3tr3d9rr3ydjm9zl9464majh4pa3ejkfy779dm38sfnkar67et7ykxkk0z9rfsmf9jclfj2k85xt2exkg4pu5xqyzyxzlqa6x3p9wrd7pwdq2uvyg0sal6zenqgfepsdp8shestvkzxuhm846r2h3m4jvsrpmxl8pfczxq87886k0wdasppffjnd2eh47nlmkdvrk6rgyyl0ekh3ycqtvvje";
+
+        let extfvk = decode_extended_full_viewing_key(config.hrp_sapling_viewing_key(), viewkey)
+            .unwrap()
+            .unwrap();
+        let mut wzk = WalletZKey::new_imported_viewkey(extfvk);
+
+        // A viewing key can create diversified addresses, and they are all different
+        let d1 = wzk.new_diversified_address().unwrap();
+        let d2 = wzk.new_diversified_address().unwrap();
+        assert!(d1 != wzk.zaddress && d2 != wzk.zaddress && d1 != d2);
+        assert_eq!(wzk.all_addresses(), vec![&wzk.zaddress, &d1, &d2]);
+
+        // They belong to the key, but the addresses of other keys don't
+        assert!(wzk.owns_address(&d1));
+        assert!(wzk.owns_address(&d2));
+        let other = WalletZKey::new_hdkey(0, ExtendedSpendingKey::master(&[1u8; 32]));
+        assert!(!wzk.owns_address(&other.zaddress));
+
+        // They are read back with the key
+        let mut v: Vec<u8> = vec![];
+        wzk.write(&mut v).unwrap();
+        let wzk2 = WalletZKey::read(&v[..]).unwrap();
+        assert_eq!(wzk, wzk2);
+        assert_eq!(wzk2.all_addresses(), vec![&wzk.zaddress, &d1, &d2]);
+    }
+
     #[test]
     fn test_encrypt_decrypt_sk() {
         let config = get_config();
@@ -0,0 +1,424 @@
+use json::{object, JsonValue};
+use std::collections::BTreeMap;
+use zcash_client_backend::address::RecipientAddress;
+use zcash_primitives::{consensus, memo::Memo};
+
+// Payment request URIs, as in ZIP-321, with the "bitcoinz:" scheme. For example:
+// bitcoinz:zs1...?amount=1.5&memo=VGhhbmtzIQ&address.1=t1...&amount.1=0.25&message=Invoice%2042
+pub const URI_SCHEME: &str = "bitcoinz";
+
+// Zats per BTCZ, and the most BTCZ there will ever be
+const COIN: u64 = 100_000_000;
+const MAX_MONEY: u64 = 21_000_000_000 * COIN;
+
+const MAX_MEMO_SIZE: usize = 512;
+const MAX_PAYMENT_INDEX: usize = 9999;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payment {
+    pub address: String,
+
+    // In zats
+    pub amount: Option<u64>,
+
+    // The raw memo bytes, which can only go to a shielded address
+    pub memo: Option<Vec<u8>>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+}
+
+impl Payment {
+    pub fn new(address: String, amount: Option<u64>) -> Self {
+        Self {
+            address,
+            amount,
+            memo: None,
+            label: None,
+            message: None,
+        }
+    }
+
+    // The memo as the send command takes it: the text, if it is a text memo, or else "0x" and the hex bytes
+    pub fn memo_string(&self) -> Option<String> {
+        self.memo.as_ref().map(|bytes| match Memo::from_bytes(bytes) {
+            Ok(Memo::Text(t)) => t.to_string(),
+            _ => format!("0x{}", hex::encode(bytes)),
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        object! {
+            "address" => self.address.clone(),
+            "amount"  => self.amount,
+            "memo"    => self.memo_string(),
+            "label"   => self.label.clone(),
+            "message" => self.message.clone(),
+        }
+    }
+
+    fn check<P: consensus::Parameters>(&self, params: &P, index: usize) -> Result<(), String> {
+        match RecipientAddress::decode(params, &self.address) {
+            Some(RecipientAddress::Transparent(_)) if self.memo.is_some() => {
+                return Err(format!(
+                    "Payment {} has a memo, but {} is a t-address",
+                    index, self.address
+                ))
+            }
+            Some(_) => {}
+            None => return Err(format!("Payment {} has an invalid address {}", index, self.address)),
+        }
+
+        if self.amount.map(|a| a > MAX_MONEY).unwrap_or(false) {
+            return Err(format!("Payment {} has an amount that is too large", index));
+        }
+        if self.memo.as_ref().map(|m| m.len() > MAX_MEMO_SIZE).unwrap_or(false) {
+            return Err(format!(
+                "Payment {} has a memo longer than {} bytes",
+                index, MAX_MEMO_SIZE
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub payments: Vec<Payment>,
+}
+
+impl PaymentRequest {
+    pub fn is_payment_uri(s: &str) -> bool {
+        s.get(..URI_SCHEME.len() + 1)
+            .map(|scheme| scheme.eq_ignore_ascii_case(&format!("{}:", URI_SCHEME)))
+            .unwrap_or(false)
+    }
+
+    // Parse a payment URI, checking every address against the network's address prefixes
+    pub fn from_uri<P: consensus::Parameters>(params: &P, uri: &str) -> Result<Self, String> {
+        if !Self::is_payment_uri(uri) {
+            return Err(format!("Not a {}: payment URI", URI_SCHEME));
+        }
+
+        // Some older wallets write "bitcoinz://<address>"
+        let rest = &uri[URI_SCHEME.len() + 1..];
+        let rest = rest.strip_prefix("//").unwrap_or(rest);
+
+        let (path, query) = match rest.find('?') {
+            Some(i) => (&rest[..i], &rest[i + 1..]),
+            None => (rest, ""),
+        };
+
+        // All the parameters, by payment index. The address in the path is payment 0.
+        let mut params_by_index: BTreeMap<usize, BTreeMap<String, String>> = BTreeMap::new();
+        if !path.is_empty() {
+            params_by_index
+                .entry(0)
+                .or_default()
+                .insert("address".to_string(), path.to_string());
+        }
+
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = match param.find('=') {
+                Some(i) => (&param[..i], &param[i + 1..]),
+                None => return Err(format!("Parameter '{}' has no value", param)),
+            };
+            let (name, index) = parse_param_name(key)?;
+
+            match name {
+                "address" | "amount" | "memo" | "label" | "message" => {}
+                n if n.starts_with("req-") => return Err(format!("Unsupported required parameter '{}'", key)),
+                // Other parameters can be ignored
+                _ => continue,
+            }
+
+            if params_by_index
+                .entry(index)
+                .or_default()
+                .insert(name.to_string(), value.to_string())
+                .is_some()
+            {
+                return Err(format!("Parameter '{}' appears more than once", key));
+            }
+        }
+
+        if params_by_index.is_empty() {
+            return Err("The payment URI has no payments".to_string());
+        }
+
+        let mut payments = vec![];
+        for (index, p) in params_by_index.iter() {
+            let address = match p.get("address") {
+                Some(a) => a.clone(),
+                None => return Err(format!("Payment {} has no address", index)),
+            };
+
+            let payment = Payment {
+                address,
+                amount: p.get("amount").map(|a| parse_amount(a)).transpose()?,
+                memo: p
+                    .get("memo")
+                    .map(|m| {
+                        base64::decode_config(m, base64::URL_SAFE_NO_PAD)
+                            .map_err(|e| format!("Payment {} has an invalid memo: {}", index, e))
+                    })
+                    .transpose()?,
+                label: p.get("label").map(|l| percent_decode(l)).transpose()?,
+                message: p.get("message").map(|m| percent_decode(m)).transpose()?,
+            };
+            payment.check(params, *index)?;
+
+            payments.push(payment);
+        }
+
+        Ok(Self { payments })
+    }
+
+    // Write the payment URI. The first payment's address is the path, and the other payments are numbered from 1.
+    pub fn to_uri<P: consensus::Parameters>(&self, params: &P) -> Result<String, String> {
+        if self.payments.is_empty() {
+            return Err("A payment URI needs at least one payment".to_string());
+        }
+
+        let mut query = vec![];
+        for (index, payment) in self.payments.iter().enumerate() {
+            payment.check(params, index)?;
+
+            let suffix = if index == 0 {
+                "".to_string()
+            } else {
+                format!(".{}", index)
+            };
+            if index > 0 {
+                query.push(format!("address{}={}", suffix, payment.address));
+            }
+            if let Some(amount) = payment.amount {
+                query.push(format!("amount{}={}", suffix, format_amount(amount)));
+            }
+            if let Some(memo) = payment.memo.as_ref() {
+                query.push(format!(
+                    "memo{}={}",
+                    suffix,
+                    base64::encode_config(memo, base64::URL_SAFE_NO_PAD)
+                ));
+            }
+            if let Some(label) = payment.label.as_ref() {
+                query.push(format!("label{}={}", suffix, percent_encode(label)));
+            }
+            if let Some(message) = payment.message.as_ref() {
+                query.push(format!("message{}={}", suffix, percent_encode(message)));
+            }
+        }
+
+        let mut uri = format!("{}:{}", URI_SCHEME, self.payments[0].address);
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+
+        Ok(uri)
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        object! {
+            "payments" => self.payments.iter().map(|p| p.to_json()).collect::<Vec<_>>(),
+        }
+    }
+}
+
+// Split "amount.2" into ("amount", 2). A name without an index is payment 0.
+fn parse_param_name(key: &str) -> Result<(&str, usize), String> {
+    let (name, index) = match key.find('.') {
+        Some(i) => (&key[..i], &key[i + 1..]),
+        None => return Ok((key, 0)),
+    };
+
+    // Indexes have no leading zeros, and payment 0 is written without one
+    if index.is_empty() || index.starts_with('0') || !index.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid payment index in '{}'", key));
+    }
+    match index.parse::<usize>() {
+        Ok(i) if i <= MAX_PAYMENT_INDEX => Ok((name, i)),
+        _ => Err(format!("Invalid payment index in '{}'", key)),
+    }
+}
+
+// Parse a BTCZ amount with up to 8 decimals into zats
+fn parse_amount(s: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid amount '{}'", s);
+
+    let (whole, frac) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if whole.is_empty()
+        || frac.len() > 8
+        || (s.contains('.') && frac.is_empty())
+        || !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let whole = whole.parse::<u64>().map_err(|_| invalid())?;
+    let frac = format!("{:0<8}", frac).parse::<u64>().map_err(|_| invalid())?;
+
+    whole
+        .checked_mul(COIN)
+        .and_then(|w| w.checked_add(frac))
+        .filter(|a| *a <= MAX_MONEY)
+        .ok_or_else(invalid)
+}
+
+fn format_amount(zats: u64) -> String {
+    let whole = zats / COIN;
+    let frac = zats % COIN;
+
+    if frac == 0 {
+        format!("{}", whole)
+    } else {
+        format!("{}.{}", whole, format!("{:08}", frac).trim_end_matches('0'))
+    }
+}
+
+// Characters that can be left as they are in a parameter value
+fn is_qchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~!$'()*+,;:@".contains(&b)
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| {
+            if is_qchar(b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> Result<String, String> {
+    let bytes = s.as_bytes();
+    let mut decoded = vec![];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s
+                .get(i + 1..i + 3)
+                .ok_or_else(|| format!("Invalid percent-encoding in '{}'", s))?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| format!("Invalid percent-encoding in '{}'", s))?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|e| format!("Invalid text in '{}': {}", s, e))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{format_amount, parse_amount, Payment, PaymentRequest};
+    use crate::bitcoinz_params::BITCOINZ_MAINNET;
+    use crate::lightclient::tests::{EXT_TADDR, EXT_ZADDR};
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse_amount("1").unwrap(), 100_000_000);
+        assert_eq!(parse_amount("1.5").unwrap(), 150_000_000);
+        assert_eq!(parse_amount("0.00000001").unwrap(), 1);
+        assert_eq!(parse_amount("21000000000").unwrap(), 21_000_000_000 * 100_000_000);
+        assert!(parse_amount("21000000000.00000001").is_err());
+        assert!(parse_amount("0.000000001").is_err());
+        assert!(parse_amount("1.").is_err());
+        assert!(parse_amount(".5").is_err());
+        assert!(parse_amount("-1").is_err());
+        assert!(parse_amount("1e8").is_err());
+
+        assert_eq!(format_amount(150_000_000), "1.5");
+        assert_eq!(format_amount(100_000_000), "1");
+        assert_eq!(format_amount(1), "0.00000001");
+    }
+
+    #[test]
+    fn parse_uris() {
+        let uri = format!(
+            "bitcoinz:{}?amount=1.50000000&message=Test%20payment%20caf%C3%A9%20%26%20more",
+            EXT_TADDR
+        );
+        let req = PaymentRequest::from_uri(&BITCOINZ_MAINNET, &uri).unwrap();
+        assert_eq!(req.payments.len(), 1);
+        assert_eq!(req.payments[0].address, EXT_TADDR);
+        assert_eq!(req.payments[0].amount, Some(150_000_000));
+        assert_eq!(req.payments[0].message, Some("Test payment café & more".to_string()));
+
+        // Several payments, with a memo, in any order
+        let uri = format!(
+            "BITCOINZ:?amount.1=0.25&address.1={}&address={}&memo=VGhhbmtzIQ&label=Bob&unknown=ignored",
+            EXT_TADDR, EXT_ZADDR
+        );
+        let req = PaymentRequest::from_uri(&BITCOINZ_MAINNET, &uri).unwrap();
+        assert_eq!(req.payments.len(), 2);
+        assert_eq!(req.payments[0].address, EXT_ZADDR);
+        assert_eq!(req.payments[0].amount, None);
+        assert_eq!(req.payments[0].memo_string(), Some("Thanks!".to_string()));
+        assert_eq!(req.payments[0].label, Some("Bob".to_string()));
+        assert_eq!(req.payments[1].address, EXT_TADDR);
+        assert_eq!(req.payments[1].amount, Some(25_000_000));
+
+        // Older wallets' format
+        let req = PaymentRequest::from_uri(&BITCOINZ_MAINNET, &format!("bitcoinz://{}", EXT_TADDR)).unwrap();
+        assert_eq!(req.payments[0].address, EXT_TADDR);
+
+        let bad = vec![
+            format!("zcash:{}", EXT_TADDR),
+            "bitcoinz:".to_string(),
+            "bitcoinz:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+            format!("bitcoinz:{}?memo=VGhhbmtzIQ", EXT_TADDR),
+            format!("bitcoinz:{}?amount=1&amount=2", EXT_TADDR),
+            format!("bitcoinz:{}?address={}", EXT_TADDR, EXT_TADDR),
+            format!("bitcoinz:{}?amount.01=1", EXT_TADDR),
+            format!("bitcoinz:{}?amount.1=1", EXT_TADDR),
+            format!("bitcoinz:{}?req-expires=100", EXT_TADDR),
+            format!("bitcoinz:{}?message=%ZZ", EXT_TADDR),
+            format!("bitcoinz:{}?memo={}", EXT_ZADDR, "A".repeat(700)),
+        ];
+        for uri in bad {
+            assert!(PaymentRequest::from_uri(&BITCOINZ_MAINNET, &uri).is_err(), "{}", uri);
+        }
+    }
+
+    #[test]
+    fn uri_round_trip() {
+        let req = PaymentRequest {
+            payments: vec![
+                Payment {
+                    memo: Some("Invoice #42, thanks".as_bytes().to_vec()),
+                    label: Some("Alice & Co".to_string()),
+                    ..Payment::new(EXT_ZADDR.to_string(), Some(123_456_789))
+                },
+                Payment {
+                    message: Some("Tip".to_string()),
+                    ..Payment::new(EXT_TADDR.to_string(), None)
+                },
+            ],
+        };
+
+        let uri = req.to_uri(&BITCOINZ_MAINNET).unwrap();
+        assert!(uri.starts_with(&format!("bitcoinz:{}?amount=1.23456789&memo=", EXT_ZADDR)));
+        assert!(uri.contains("label=Alice%20%26%20Co"));
+        assert!(uri.contains(&format!("address.1={}", EXT_TADDR)));
+        assert_eq!(PaymentRequest::from_uri(&BITCOINZ_MAINNET, &uri).unwrap(), req);
+
+        // A memo can't go to a t-address
+        let req = PaymentRequest {
+            payments: vec![Payment {
+                memo: Some(vec![1, 2, 3]),
+                ..Payment::new(EXT_TADDR.to_string(), None)
+            }],
+        };
+        assert!(req.to_uri(&BITCOINZ_MAINNET).is_err());
+    }
+}